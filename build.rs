@@ -8,13 +8,34 @@ fn main() {
     }
     println!("cargo::rustc-check-cfg=cfg(subcommand)");
 
-    let command_build_enabled = std::env::vars()
-        .any(|(name, var)| name.starts_with("CARGO_FEATURE_SUBCOMMAND_DIST_BUILD_") && var == "1");
+    // `dist-build-recipes` is not a part of `dist-build`, as it refers to the archives
+    let command_build_enabled = std::env::vars().any(|(name, var)| {
+        name.starts_with("CARGO_FEATURE_SUBCOMMAND_DIST_BUILD_")
+            && name != "CARGO_FEATURE_SUBCOMMAND_DIST_BUILD_RECIPES"
+            && var == "1"
+    });
     if command_build_enabled {
         println!("cargo:rustc-cfg=subcommand_dist_build");
     }
     println!("cargo::rustc-check-cfg=cfg(subcommand_dist_build)");
 
+    // steps generating architecture-independent artifacts are skipped if their
+    // inputs are unchanged
+    let dist_fingerprint_enabled = [
+        "CARGO_FEATURE_SUBCOMMAND_DIST_BUILD_CLI_DOC",
+        "CARGO_FEATURE_SUBCOMMAND_DIST_BUILD_COMPLETION",
+        "CARGO_FEATURE_SUBCOMMAND_DIST_BUILD_DOC",
+        "CARGO_FEATURE_SUBCOMMAND_DIST_BUILD_LICENSE",
+        "CARGO_FEATURE_SUBCOMMAND_DIST_BUILD_MAN",
+        "CARGO_FEATURE_SUBCOMMAND_DIST_BUILD_README",
+    ]
+    .iter()
+    .any(|name| std::env::var(name).is_ok_and(|var| var == "1"));
+    if dist_fingerprint_enabled {
+        println!("cargo:rustc-cfg=dist_fingerprint");
+    }
+    println!("cargo::rustc-check-cfg=cfg(dist_fingerprint)");
+
     println!(
         "cargo:rustc-env=DEFAULT_TARGET={}",
         std::env::var("TARGET").unwrap()
@@ -1,12 +1,327 @@
 //! Utilities for creating archives.
 
-use cargo_metadata::camino::Utf8Path;
-use flate2::{Compression, write::GzEncoder};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, Write},
+    str::FromStr,
+};
 
-use crate::{Result, fs::ToRelative};
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use eyre::{bail, ensure};
+use flate2::write::GzEncoder;
+use walkdir::WalkDir;
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+use crate::{Error, Result, fs::ToRelative};
+
+/// Format of the archive file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, clap::ValueEnum)]
+#[non_exhaustive]
+pub enum ArchiveFormat {
+    /// Compressed tar archive (`.tar.gz`, `.tar.xz`, etc.).
+    #[default]
+    Tar,
+    /// Zip archive (`.zip`).
+    Zip,
+}
+
+impl fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tar => f.write_str("tar"),
+            Self::Zip => f.write_str("zip"),
+        }
+    }
+}
+
+/// Compression codec used to compress the archive.
+///
+/// Codecs other than [`Gzip`](Self::Gzip) require the corresponding cargo
+/// feature (`archive-xz`, `archive-zstd` or `archive-bzip2`) to be enabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, clap::ValueEnum)]
+#[non_exhaustive]
+pub enum CompressionCodec {
+    /// Gzip (deflate) compression.
+    #[default]
+    Gzip,
+    /// XZ (LZMA2) compression.
+    Xz,
+    /// Zstandard compression.
+    Zstd,
+    /// Bzip2 compression.
+    Bzip2,
+}
+
+impl fmt::Display for CompressionCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gzip => f.write_str("gzip"),
+            Self::Xz => f.write_str("xz"),
+            Self::Zstd => f.write_str("zstd"),
+            Self::Bzip2 => f.write_str("bzip2"),
+        }
+    }
+}
+
+impl FromStr for CompressionCodec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "gzip" | "gz" => Ok(Self::Gzip),
+            "xz" => Ok(Self::Xz),
+            "zstd" | "zst" => Ok(Self::Zstd),
+            "bzip2" | "bz2" => Ok(Self::Bzip2),
+            _ => bail!("unknown compression codec: {s}"),
+        }
+    }
+}
+
+impl CompressionCodec {
+    /// Returns the file extension of the compressed tar archive, without
+    /// `.tar.` prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cli_xtask::archive::CompressionCodec;
+    ///
+    /// assert_eq!(CompressionCodec::Gzip.extension(), "gz");
+    /// assert_eq!(CompressionCodec::Zstd.extension(), "zst");
+    /// ```
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Gzip => "gz",
+            Self::Xz => "xz",
+            Self::Zstd => "zst",
+            Self::Bzip2 => "bz2",
+        }
+    }
+
+    /// Returns the range of valid compression levels of the codec.
+    pub fn level_range(self) -> std::ops::RangeInclusive<u32> {
+        match self {
+            Self::Gzip => 0..=9,
+            Self::Xz => 0..=9,
+            Self::Zstd => 1..=22,
+            Self::Bzip2 => 1..=9,
+        }
+    }
+}
+
+/// Compression setting of the archive, consisting of a codec and an optional
+/// compression level.
+///
+/// If the level is not specified, the default level of the codec is used.
+///
+/// # Examples
+///
+/// `Compression` can be parsed from a string in the form of `CODEC[:LEVEL]`:
+///
+/// ```rust
+/// # fn main() -> cli_xtask::Result<()> {
+/// use cli_xtask::archive::{Compression, CompressionCodec};
+///
+/// let compression: Compression = "zstd:19".parse()?;
+/// assert_eq!(compression, Compression::new(CompressionCodec::Zstd, Some(19)));
+///
+/// let compression: Compression = "xz".parse()?;
+/// assert_eq!(compression, Compression::new(CompressionCodec::Xz, None));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Compression {
+    /// Compression codec.
+    pub codec: CompressionCodec,
+    /// Compression level, or `None` to use the default level of the codec.
+    pub level: Option<u32>,
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.level {
+            Some(level) => write!(f, "{}:{level}", self.codec),
+            None => write!(f, "{}", self.codec),
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (codec, level) = match s.split_once(':') {
+            Some((codec, level)) => (codec, Some(level.parse()?)),
+            None => (s, None),
+        };
+        let compression = Self::new(codec.parse()?, level);
+        compression.validate()?;
+        Ok(compression)
+    }
+}
+
+impl Compression {
+    /// Creates a new `Compression` with the given codec and level.
+    pub fn new(codec: CompressionCodec, level: Option<u32>) -> Self {
+        Self { codec, level }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(level) = self.level {
+            let range = self.codec.level_range();
+            ensure!(
+                range.contains(&level),
+                "invalid {} compression level: {level} (expected {}..={})",
+                self.codec,
+                range.start(),
+                range.end()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Options to configure how an archive is created.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn main() -> cli_xtask::Result<()> {
+/// use cli_xtask::archive::{ArchiveFormat, ArchiveOptions, Compression, CompressionCodec};
+///
+/// ArchiveOptions::new()
+///     .format(ArchiveFormat::Zip)
+///     .create("foo.zip", ["./foo/", "./bar.txt"])?;
+///
+/// let options = ArchiveOptions::new().compression(Compression::new(CompressionCodec::Xz, Some(9)));
+/// assert_eq!(options.extension(), "tar.xz");
+/// options.create("foo.tar.xz", ["./foo/", "./bar.txt"])?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveOptions {
+    format: ArchiveFormat,
+    compression: Compression,
+    reproducible: bool,
+    prefix: String,
+}
+
+impl ArchiveOptions {
+    /// Creates a new `ArchiveOptions` with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the format of the archive.
+    ///
+    /// Defaults to [`ArchiveFormat::Tar`].
+    pub fn format(mut self, format: ArchiveFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the compression of the archive.
+    ///
+    /// For zip archives, the codec selects the compression method of each
+    /// entry.
+    /// Defaults to gzip with the default compression level.
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets whether to create a reproducible archive.
+    ///
+    /// If enabled, the archive is created byte-for-byte identically as long
+    /// as the contents of the files are the same:
+    ///
+    /// * Entries are added in the order sorted by their paths.
+    /// * Modification times of all entries are set to the value of the
+    ///   `SOURCE_DATE_EPOCH` environment variable, or to
+    ///   1980-01-01T00:00:00Z if it is not set.
+    /// * Owner and group of all entries are set to `0` and owner and group
+    ///   names are left empty.
+    /// * Permissions are normalized to `0o755` for directories and
+    ///   executable files and to `0o644` for other files.
+    ///
+    /// Defaults to `false`.
+    pub fn reproducible(mut self, reproducible: bool) -> Self {
+        self.reproducible = reproducible;
+        self
+    }
+
+    /// Sets the directory prefix of the entries in the archive.
+    ///
+    /// All entries are placed under the given directory, so that extracting
+    /// the archive does not scatter the files into the current directory.
+    /// Use `/` to separate nested directories.
+    /// Defaults to the empty string, which places the entries at the root of
+    /// the archive.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::archive::ArchiveOptions;
+    ///
+    /// // creates `app-v1.0.0/foo/...` and `app-v1.0.0/bar.txt` entries
+    /// ArchiveOptions::new()
+    ///     .prefix("app-v1.0.0")
+    ///     .create("foo.tar.gz", ["./foo/", "./bar.txt"])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Returns the file extension of the archive, without leading dot.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cli_xtask::archive::{ArchiveFormat, ArchiveOptions};
+    ///
+    /// assert_eq!(ArchiveOptions::new().extension(), "tar.gz");
+    /// assert_eq!(ArchiveOptions::new().format(ArchiveFormat::Zip).extension(), "zip");
+    /// ```
+    pub fn extension(&self) -> String {
+        match self.format {
+            ArchiveFormat::Tar => format!("tar.{}", self.compression.codec.extension()),
+            ArchiveFormat::Zip => "zip".to_string(),
+        }
+    }
+
+    /// Create an archive from the given paths.
+    ///
+    /// Each path is added to the root of the archive (or to the directory
+    /// specified by [`prefix`](Self::prefix)) with its file name.
+    /// Directories are added recursively.
+    #[tracing::instrument(name = "archive::create", skip_all, err)]
+    pub fn create(
+        &self,
+        archive_path: impl AsRef<Utf8Path>,
+        src: impl IntoIterator<Item = impl AsRef<Utf8Path>>,
+    ) -> Result<()> {
+        self.compression.validate()?;
+        let mtime = self.reproducible.then(source_date_epoch).transpose()?;
+        let prefix = self.prefix.trim_matches('/');
+        let archive = crate::fs::create_file(&archive_path)?;
+        match self.format {
+            ArchiveFormat::Tar => create_tar(archive, self.compression, mtime, prefix, src),
+            ArchiveFormat::Zip => create_zip(archive, self.compression, mtime, prefix, src),
+        }
+    }
+}
 
 /// Create a `tar.gz` archive from the given paths.
 ///
+/// Use [`ArchiveOptions`] to create an archive in other formats.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -15,23 +330,103 @@ use crate::{Result, fs::ToRelative};
 /// # Ok(())
 /// # }
 /// ```
-#[tracing::instrument(name = "archive::create", skip_all, err)]
 pub fn create(
     archive_path: impl AsRef<Utf8Path>,
     src: impl IntoIterator<Item = impl AsRef<Utf8Path>>,
 ) -> Result<()> {
-    let archive = crate::fs::create_file(&archive_path)?;
-    let enc = GzEncoder::new(archive, Compression::default());
-    let mut tar = tar::Builder::new(enc);
+    ArchiveOptions::new().create(archive_path, src)
+}
+
+/// Timestamp used for reproducible archives if `SOURCE_DATE_EPOCH` is not set.
+///
+/// This is the earliest timestamp that can be represented in zip archives
+/// (1980-01-01T00:00:00Z).
+const DEFAULT_SOURCE_DATE_EPOCH: u64 = 315_532_800;
+
+fn source_date_epoch() -> Result<u64> {
+    Ok(crate::time::source_date_epoch()?.unwrap_or(DEFAULT_SOURCE_DATE_EPOCH))
+}
+
+/// File or directory to be added to the archive.
+struct Entry {
+    path: Utf8PathBuf,
+    name: String,
+    metadata: std::fs::Metadata,
+}
+
+fn collect_entries(
+    src: impl IntoIterator<Item = impl AsRef<Utf8Path>>,
+    prefix: &str,
+    sort: bool,
+) -> Result<Vec<Entry>> {
+    let mut src = src
+        .into_iter()
+        .map(|src| src.as_ref().to_owned())
+        .collect::<Vec<_>>();
+    if sort {
+        src.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    }
 
-    for src in src.into_iter() {
-        let src = src.as_ref();
+    let mut entries = vec![];
+    for src in &src {
+        let base = src.parent().unwrap_or(src);
         if src.is_file() {
             tracing::info!("adding file: {}", src.to_relative());
-            tar.append_path_with_name(src, src.file_name().unwrap())?;
         } else {
             tracing::info!("adding directory: {}", src.to_relative());
-            tar.append_dir_all(src.file_name().unwrap(), src)?;
+        }
+
+        for entry in WalkDir::new(src).follow_links(true).sort_by_file_name() {
+            let entry = entry?;
+            let path = <&Utf8Path>::try_from(entry.path())?;
+            let name = (!prefix.is_empty())
+                .then_some(prefix)
+                .into_iter()
+                .chain(path.strip_prefix(base)?.components().map(|c| c.as_str()))
+                .collect::<Vec<_>>()
+                .join("/");
+            entries.push(Entry {
+                path: path.to_owned(),
+                name,
+                metadata: entry.metadata()?,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn create_tar(
+    archive: File,
+    compression: Compression,
+    mtime: Option<u64>,
+    prefix: &str,
+    src: impl IntoIterator<Item = impl AsRef<Utf8Path>>,
+) -> Result<()> {
+    let enc = Encoder::new(archive, compression)?;
+    let mut tar = tar::Builder::new(enc);
+
+    if let Some(mtime) = mtime {
+        for entry in collect_entries(src, prefix, true)? {
+            let mut header = tar::Header::new_gnu();
+            header.set_metadata_in_mode(&entry.metadata, tar::HeaderMode::Deterministic);
+            header.set_mtime(mtime);
+            if entry.metadata.is_dir() {
+                tar.append_data(&mut header, &entry.name, io::empty())?;
+            } else {
+                tar.append_data(&mut header, &entry.name, File::open(&entry.path)?)?;
+            }
+        }
+    } else {
+        for src in src.into_iter() {
+            let src = src.as_ref();
+            let name = Utf8Path::new(prefix).join(src.file_name().unwrap());
+            if src.is_file() {
+                tracing::info!("adding file: {}", src.to_relative());
+                tar.append_path_with_name(src, name)?;
+            } else {
+                tracing::info!("adding directory: {}", src.to_relative());
+                tar.append_dir_all(name, src)?;
+            }
         }
     }
 
@@ -42,3 +437,455 @@ pub fn create(
 
     Ok(())
 }
+
+fn create_zip(
+    archive: File,
+    compression: Compression,
+    mtime: Option<u64>,
+    prefix: &str,
+    src: impl IntoIterator<Item = impl AsRef<Utf8Path>>,
+) -> Result<()> {
+    let method = match compression.codec {
+        CompressionCodec::Gzip => CompressionMethod::Deflated,
+        #[cfg(feature = "archive-xz")]
+        CompressionCodec::Xz => CompressionMethod::Xz,
+        #[cfg(feature = "archive-zstd")]
+        CompressionCodec::Zstd => CompressionMethod::Zstd,
+        #[cfg(feature = "archive-bzip2")]
+        CompressionCodec::Bzip2 => CompressionMethod::Bzip2,
+        #[allow(unreachable_patterns)]
+        codec => return Err(codec_not_enabled(codec)),
+    };
+
+    let mut zip = ZipWriter::new(archive);
+    let mut options = SimpleFileOptions::default()
+        .compression_method(method)
+        .compression_level(compression.level.map(i64::from));
+    if let Some(mtime) = mtime {
+        options = options.last_modified_time(zip_date_time(mtime)?);
+    }
+
+    for entry in collect_entries(src, prefix, mtime.is_some())? {
+        let mode = if mtime.is_some() {
+            normalized_mode(&entry.metadata)
+        } else {
+            unix_mode(&entry.metadata)
+        };
+        let options = options.unix_permissions(mode);
+        if entry.metadata.is_dir() {
+            zip.add_directory(entry.name, options)?;
+        } else {
+            zip.start_file(entry.name, options)?;
+            io::copy(&mut File::open(&entry.path)?, &mut zip)?;
+        }
+    }
+    // errors in drop are ignored, so we should flush the data here
+    let archive = zip.finish()?;
+    archive.sync_all()?;
+
+    Ok(())
+}
+
+fn codec_not_enabled(codec: CompressionCodec) -> Error {
+    eyre::eyre!("{codec} compression is not enabled, enable `archive-{codec}` feature to use it")
+}
+
+enum Encoder<W: Write> {
+    Gzip(GzEncoder<W>),
+    #[cfg(feature = "archive-xz")]
+    Xz(Box<lzma_rust2::XzWriter<W>>),
+    #[cfg(feature = "archive-zstd")]
+    Zstd(zstd::Encoder<'static, W>),
+    #[cfg(feature = "archive-bzip2")]
+    Bzip2(bzip2::write::BzEncoder<W>),
+}
+
+impl<W: Write> Encoder<W> {
+    fn new(writer: W, compression: Compression) -> Result<Self> {
+        let Compression { codec, level } = compression;
+        let enc = match codec {
+            CompressionCodec::Gzip => {
+                let level =
+                    level.map_or_else(flate2::Compression::default, flate2::Compression::new);
+                Self::Gzip(GzEncoder::new(writer, level))
+            }
+            #[cfg(feature = "archive-xz")]
+            CompressionCodec::Xz => {
+                let options = lzma_rust2::XzOptions::with_preset(level.unwrap_or(6));
+                Self::Xz(Box::new(lzma_rust2::XzWriter::new(writer, options)?))
+            }
+            #[cfg(feature = "archive-zstd")]
+            CompressionCodec::Zstd => {
+                // level 0 means the default level of zstd
+                let level = level.map_or(0, |level| level as i32);
+                Self::Zstd(zstd::Encoder::new(writer, level)?)
+            }
+            #[cfg(feature = "archive-bzip2")]
+            CompressionCodec::Bzip2 => {
+                let level = level.map_or_else(bzip2::Compression::default, bzip2::Compression::new);
+                Self::Bzip2(bzip2::write::BzEncoder::new(writer, level))
+            }
+            #[allow(unreachable_patterns)]
+            codec => return Err(codec_not_enabled(codec)),
+        };
+        Ok(enc)
+    }
+
+    fn finish(self) -> Result<W> {
+        let writer = match self {
+            Self::Gzip(enc) => enc.finish()?,
+            #[cfg(feature = "archive-xz")]
+            Self::Xz(enc) => enc.finish()?,
+            #[cfg(feature = "archive-zstd")]
+            Self::Zstd(enc) => enc.finish()?,
+            #[cfg(feature = "archive-bzip2")]
+            Self::Bzip2(enc) => enc.finish()?,
+        };
+        Ok(writer)
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Gzip(enc) => enc.write(buf),
+            #[cfg(feature = "archive-xz")]
+            Self::Xz(enc) => enc.write(buf),
+            #[cfg(feature = "archive-zstd")]
+            Self::Zstd(enc) => enc.write(buf),
+            #[cfg(feature = "archive-bzip2")]
+            Self::Bzip2(enc) => enc.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Gzip(enc) => enc.flush(),
+            #[cfg(feature = "archive-xz")]
+            Self::Xz(enc) => enc.flush(),
+            #[cfg(feature = "archive-zstd")]
+            Self::Zstd(enc) => enc.flush(),
+            #[cfg(feature = "archive-bzip2")]
+            Self::Bzip2(enc) => enc.flush(),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn unix_mode(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode()
+}
+
+#[cfg(not(unix))]
+fn unix_mode(metadata: &std::fs::Metadata) -> u32 {
+    if metadata.is_dir() { 0o755 } else { 0o644 }
+}
+
+fn normalized_mode(metadata: &std::fs::Metadata) -> u32 {
+    if metadata.is_dir() || unix_mode(metadata) & 0o111 != 0 {
+        0o755
+    } else {
+        0o644
+    }
+}
+
+/// Converts the UNIX timestamp into the date and time stored in zip archives.
+fn zip_date_time(timestamp: u64) -> Result<zip::DateTime> {
+    let timestamp = timestamp.max(DEFAULT_SOURCE_DATE_EPOCH);
+    let (days, secs) = (timestamp / 86400, timestamp % 86400);
+
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    let date_time = zip::DateTime::from_date_and_time(
+        u16::try_from(year)?,
+        month as u8,
+        day as u8,
+        (secs / 3600) as u8,
+        (secs / 60 % 60) as u8,
+        (secs % 60) as u8,
+    )
+    .map_err(|e| eyre::eyre!("timestamp {timestamp} cannot be stored in zip archive: {e}"))?;
+    Ok(date_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use cargo_metadata::camino::Utf8PathBuf;
+    use flate2::read::GzDecoder;
+    use tempfile::TempDir;
+    use zip::ZipArchive;
+
+    use super::*;
+
+    fn create_sources() -> (TempDir, Vec<Utf8PathBuf>) {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8PathBuf::try_from(temp_dir.path().to_owned()).unwrap();
+        std::fs::create_dir_all(root.join("foo/sub")).unwrap();
+        std::fs::write(root.join("foo/a.txt"), "a").unwrap();
+        std::fs::write(root.join("foo/sub/b.txt"), "b").unwrap();
+        std::fs::write(root.join("bar.txt"), "bar").unwrap();
+        let src = vec![root.join("foo"), root.join("bar.txt")];
+        (temp_dir, src)
+    }
+
+    fn tar_entries(path: &Utf8Path, codec: CompressionCodec) -> Vec<(String, String)> {
+        let file = File::open(path).unwrap();
+        let reader: Box<dyn Read> = match codec {
+            CompressionCodec::Gzip => Box::new(GzDecoder::new(file)),
+            #[cfg(feature = "archive-xz")]
+            CompressionCodec::Xz => Box::new(lzma_rust2::XzReader::new(file, false)),
+            #[cfg(feature = "archive-zstd")]
+            CompressionCodec::Zstd => Box::new(zstd::Decoder::new(file).unwrap()),
+            #[cfg(feature = "archive-bzip2")]
+            CompressionCodec::Bzip2 => Box::new(bzip2::read::BzDecoder::new(file)),
+            #[allow(unreachable_patterns)]
+            codec => panic!("codec not enabled: {codec}"),
+        };
+        let mut tar = tar::Archive::new(reader);
+        let mut entries = vec![];
+        for entry in tar.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_str().unwrap().to_owned();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            entries.push((name, content));
+        }
+        entries
+    }
+
+    fn zip_entries(path: &Utf8Path) -> Vec<(String, String)> {
+        let mut zip = ZipArchive::new(File::open(path).unwrap()).unwrap();
+        let mut entries = vec![];
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i).unwrap();
+            let name = entry.name().to_owned();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            entries.push((name, content));
+        }
+        entries
+    }
+
+    #[test]
+    fn compression() {
+        let cases = [
+            ("gzip", Ok(Compression::new(CompressionCodec::Gzip, None))),
+            (
+                "gz:9",
+                Ok(Compression::new(CompressionCodec::Gzip, Some(9))),
+            ),
+            ("xz:0", Ok(Compression::new(CompressionCodec::Xz, Some(0)))),
+            (
+                "zst:22",
+                Ok(Compression::new(CompressionCodec::Zstd, Some(22))),
+            ),
+            ("bz2", Ok(Compression::new(CompressionCodec::Bzip2, None))),
+            ("lz4", Err("unknown compression codec: lz4")),
+            (
+                "zstd:0",
+                Err("invalid zstd compression level: 0 (expected 1..=22)"),
+            ),
+            (
+                "gzip:10",
+                Err("invalid gzip compression level: 10 (expected 0..=9)"),
+            ),
+        ];
+        for (input, expected) in cases {
+            let res = input.parse::<Compression>().map_err(|e| e.to_string());
+            assert_eq!(res, expected.map_err(str::to_owned), "input: {input}");
+        }
+        assert!("gzip:x".parse::<Compression>().is_err());
+
+        for (compression, expected) in [("gzip", "gzip"), ("gz:9", "gzip:9"), ("zst:3", "zstd:3")] {
+            let compression = compression.parse::<Compression>().unwrap();
+            assert_eq!(compression.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn extension() {
+        let cases = [
+            (ArchiveFormat::Tar, CompressionCodec::Gzip, "tar.gz"),
+            (ArchiveFormat::Tar, CompressionCodec::Xz, "tar.xz"),
+            (ArchiveFormat::Tar, CompressionCodec::Zstd, "tar.zst"),
+            (ArchiveFormat::Tar, CompressionCodec::Bzip2, "tar.bz2"),
+            (ArchiveFormat::Zip, CompressionCodec::Gzip, "zip"),
+            (ArchiveFormat::Zip, CompressionCodec::Xz, "zip"),
+        ];
+        for (format, codec, expected) in cases {
+            let options = ArchiveOptions::new()
+                .format(format)
+                .compression(Compression::new(codec, None));
+            assert_eq!(options.extension(), expected, "{format} {codec}");
+        }
+    }
+
+    #[test]
+    fn create_archive() {
+        let (temp_dir, src) = create_sources();
+        let out_dir = Utf8PathBuf::try_from(temp_dir.path().join("out")).unwrap();
+
+        let codecs = [
+            CompressionCodec::Gzip,
+            #[cfg(feature = "archive-xz")]
+            CompressionCodec::Xz,
+            #[cfg(feature = "archive-zstd")]
+            CompressionCodec::Zstd,
+            #[cfg(feature = "archive-bzip2")]
+            CompressionCodec::Bzip2,
+        ];
+        for codec in codecs {
+            let options = ArchiveOptions::new().compression(Compression::new(codec, Some(1)));
+            let tar_path = out_dir.join(format!("test.{}", options.extension()));
+            options.create(&tar_path, &src).unwrap();
+            let mut entries = tar_entries(&tar_path, codec);
+            entries.sort();
+            let entries = entries
+                .iter()
+                .filter(|(name, _)| name.ends_with(".txt"))
+                .map(|(name, content)| (name.as_str(), content.as_str()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                entries,
+                [
+                    ("bar.txt", "bar"),
+                    ("foo/a.txt", "a"),
+                    ("foo/sub/b.txt", "b")
+                ],
+                "codec: {codec}"
+            );
+        }
+
+        let zip_path = out_dir.join("test.zip");
+        ArchiveOptions::new()
+            .format(ArchiveFormat::Zip)
+            .create(&zip_path, &src)
+            .unwrap();
+        let entries = zip_entries(&zip_path);
+        let entries = entries
+            .iter()
+            .map(|(name, content)| (name.as_str(), content.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                ("foo/", ""),
+                ("foo/a.txt", "a"),
+                ("foo/sub/", ""),
+                ("foo/sub/b.txt", "b"),
+                ("bar.txt", "bar"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reproducible() {
+        let (temp_dir, src) = create_sources();
+        let out_dir = Utf8PathBuf::try_from(temp_dir.path().join("out")).unwrap();
+
+        for format in [ArchiveFormat::Tar, ArchiveFormat::Zip] {
+            let options = ArchiveOptions::new().format(format).reproducible(true);
+            let path1 = out_dir.join(format!("test1.{}", options.extension()));
+            let path2 = out_dir.join(format!("test2.{}", options.extension()));
+            options.create(&path1, &src).unwrap();
+            // change the modification time of the source file
+            File::options()
+                .write(true)
+                .open(&src[1])
+                .unwrap()
+                .set_modified(std::time::SystemTime::UNIX_EPOCH)
+                .unwrap();
+            options.create(&path2, &src).unwrap();
+            assert_eq!(
+                std::fs::read(&path1).unwrap(),
+                std::fs::read(&path2).unwrap(),
+                "format: {format}"
+            );
+        }
+    }
+
+    #[test]
+    fn prefix() {
+        let (temp_dir, src) = create_sources();
+        let out_dir = Utf8PathBuf::try_from(temp_dir.path().join("out")).unwrap();
+
+        for reproducible in [false, true] {
+            let options = ArchiveOptions::new()
+                .prefix("/app/v1/")
+                .reproducible(reproducible);
+            let tar_path = out_dir.join("test.tar.gz");
+            options.create(&tar_path, &src).unwrap();
+            let mut names = tar_entries(&tar_path, CompressionCodec::Gzip)
+                .into_iter()
+                .map(|(name, _)| name.trim_end_matches('/').to_owned())
+                .collect::<Vec<_>>();
+            names.sort();
+            assert_eq!(
+                names,
+                [
+                    "app/v1/bar.txt",
+                    "app/v1/foo",
+                    "app/v1/foo/a.txt",
+                    "app/v1/foo/sub",
+                    "app/v1/foo/sub/b.txt",
+                ],
+                "reproducible: {reproducible}"
+            );
+
+            let zip_path = out_dir.join("test.zip");
+            options
+                .format(ArchiveFormat::Zip)
+                .create(&zip_path, &src)
+                .unwrap();
+            let mut names = zip_entries(&zip_path)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>();
+            names.sort();
+            assert_eq!(
+                names,
+                [
+                    "app/v1/bar.txt",
+                    "app/v1/foo/",
+                    "app/v1/foo/a.txt",
+                    "app/v1/foo/sub/",
+                    "app/v1/foo/sub/b.txt",
+                ],
+                "reproducible: {reproducible}"
+            );
+        }
+    }
+
+    #[test]
+    fn zip_date_time() {
+        for (timestamp, expected) in [
+            (0, (1980, 1, 1, 0, 0, 0)),
+            (DEFAULT_SOURCE_DATE_EPOCH, (1980, 1, 1, 0, 0, 0)),
+            (951_782_400, (2000, 2, 29, 0, 0, 0)),
+            (1_700_000_000, (2023, 11, 14, 22, 13, 20)),
+        ] {
+            let dt = super::zip_date_time(timestamp).unwrap();
+            let actual = (
+                dt.year(),
+                dt.month(),
+                dt.day(),
+                dt.hour(),
+                dt.minute(),
+                dt.second(),
+            );
+            assert_eq!(actual, expected, "timestamp: {timestamp}");
+        }
+    }
+}
@@ -259,6 +259,32 @@ impl FeatureArgs {
     }
 }
 
+/// Command line arguments to control the reuse of the artifacts generated by
+/// the previous runs.
+///
+/// The `dist-build-*` steps generating architecture-independent artifacts
+/// record the fingerprints of their inputs, and skip the generation if the
+/// inputs are unchanged since the last run, unless `--force` is given.
+#[cfg(dist_fingerprint)]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        feature = "subcommand-dist-build-cli-doc",
+        feature = "subcommand-dist-build-completion",
+        feature = "subcommand-dist-build-doc",
+        feature = "subcommand-dist-build-license",
+        feature = "subcommand-dist-build-man",
+        feature = "subcommand-dist-build-readme"
+    )))
+)]
+#[derive(Debug, Clone, Default, clap::Args)]
+#[non_exhaustive]
+pub struct FingerprintArgs {
+    /// Regenerate the artifacts even if their inputs are unchanged.
+    #[clap(long = "force", id = "fingerprint_force")]
+    pub force: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
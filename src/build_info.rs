@@ -0,0 +1,62 @@
+//! Information about the executables built by `cargo xtask dist-build-bin`.
+
+use std::collections::BTreeMap;
+
+use cargo_metadata::camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, config::DistConfig};
+
+/// Information about the executables built for a target triple.
+///
+/// The information is stored as `<target>.build-info.json` next to the working
+/// directory of the target triple, so that it is not archived together with the
+/// executables.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct BuildInfo {
+    pub(crate) packages: BTreeMap<String, PackageBuildInfo>,
+}
+
+/// Information about the executables built for a package.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct PackageBuildInfo {
+    /// Cargo profile used to build the executables.
+    pub(crate) profile: String,
+}
+
+impl BuildInfo {
+    /// Returns the path of the build information for the given target triple.
+    pub(crate) fn path(config: &DistConfig, target_triple: &str) -> Utf8PathBuf {
+        config
+            .dist_base_working_directory()
+            .join(format!("{target_triple}.build-info.json"))
+    }
+
+    /// Reads the build information for the given target triple.
+    ///
+    /// Returns `None` if the executables are not built for the target triple.
+    #[cfg(feature = "subcommand-dist-archive")]
+    pub(crate) fn read(config: &DistConfig, target_triple: &str) -> Result<Option<Self>> {
+        let path = Self::path(config, target_triple);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let file = std::fs::File::open(path)?;
+        Ok(Some(serde_json::from_reader(std::io::BufReader::new(
+            file,
+        ))?))
+    }
+
+    /// Writes the build information for the given target triple.
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    pub(crate) fn write(&self, config: &DistConfig, target_triple: &str) -> Result<()> {
+        use std::io::Write as _;
+
+        let mut file = crate::fs::create_file(Self::path(config, target_triple))?;
+        serde_json::to_writer_pretty(&mut file, self)?;
+        writeln!(file)?;
+        Ok(())
+    }
+}
@@ -1,27 +1,150 @@
 //! Utilities for Cargo command execution.
 
 use std::{
+    fmt,
     io::BufReader,
     iter,
     process::{Command, Stdio},
+    str::FromStr,
 };
 
 use cargo_metadata::{
-    Artifact, Message, Metadata, MetadataCommand, Package, Target, TargetKind, camino::Utf8PathBuf,
+    Artifact, Message, Metadata, MetadataCommand, Package, Target, TargetKind,
+    camino::{Utf8Path, Utf8PathBuf},
 };
 use eyre::{bail, ensure, eyre};
 
 use crate::{Error, Result};
 
+/// Tool used to build the artifacts.
+///
+/// # Examples
+///
+/// ```rust
+/// use cli_xtask::cargo::BuildBackend;
+///
+/// assert_eq!("cross".parse::<BuildBackend>().unwrap(), BuildBackend::Cross);
+/// assert_eq!(
+///     "cargo auditable build".parse::<BuildBackend>().unwrap(),
+///     BuildBackend::Custom(vec!["cargo".into(), "auditable".into(), "build".into()])
+/// );
+/// assert_eq!(BuildBackend::Zigbuild.to_string(), "zigbuild");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BuildBackend {
+    /// `cargo build`.
+    #[default]
+    Cargo,
+    /// `cross build`, building in a container provided by [`cross`].
+    ///
+    /// [`cross`]: https://github.com/cross-rs/cross
+    Cross,
+    /// `cargo zigbuild`, linking with [`cargo-zigbuild`].
+    ///
+    /// [`cargo-zigbuild`]: https://github.com/rust-cross/cargo-zigbuild
+    Zigbuild,
+    /// Custom command, given as a program followed by its arguments.
+    ///
+    /// The command must accept the same arguments as `cargo build`, including
+    /// `--message-format=json-render-diagnostics`, and place the artifacts in
+    /// the workspace's target directory.
+    Custom(Vec<String>),
+}
+
+impl fmt::Display for BuildBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cargo => f.write_str("cargo"),
+            Self::Cross => f.write_str("cross"),
+            Self::Zigbuild => f.write_str("zigbuild"),
+            Self::Custom(command) => f.write_str(&command.join(" ")),
+        }
+    }
+}
+
+impl FromStr for BuildBackend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "cargo" => Ok(Self::Cargo),
+            "cross" => Ok(Self::Cross),
+            "zigbuild" => Ok(Self::Zigbuild),
+            _ => {
+                let command = s.split_whitespace().map(String::from).collect::<Vec<_>>();
+                ensure!(!command.is_empty(), "build backend must not be empty");
+                Ok(Self::Custom(command))
+            }
+        }
+    }
+}
+
+impl BuildBackend {
+    /// Returns the program and the arguments that start a build.
+    fn command(&self) -> Result<(&str, Vec<&str>)> {
+        let (program, args) = match self {
+            Self::Cargo => ("cargo", vec!["build"]),
+            Self::Cross => ("cross", vec!["build"]),
+            Self::Zigbuild => ("cargo", vec!["zigbuild"]),
+            Self::Custom(command) => {
+                let Some((program, args)) = command.split_first() else {
+                    bail!("custom build backend must not be empty");
+                };
+                (program.as_str(), args.iter().map(String::as_str).collect())
+            }
+        };
+        Ok((program, args))
+    }
+
+    /// Returns the target directory the backend places the artifacts in.
+    ///
+    /// `cross` may use a target directory different from the workspace's one,
+    /// as seen from the host, so it is queried with `cross metadata`.
+    /// The other backends use the workspace's target directory.
+    pub fn target_directory(
+        &self,
+        metadata: &Metadata,
+        target_triple: Option<&str>,
+    ) -> Result<Utf8PathBuf> {
+        match self {
+            Self::Cross => {
+                let mut cmd = MetadataCommand::new();
+                cmd.cargo_path("cross").no_deps();
+                if let Some(target_triple) = target_triple {
+                    cmd.other_options(["--target".to_string(), target_triple.to_string()]);
+                }
+                Ok(cmd.exec()?.target_directory)
+            }
+            Self::Cargo | Self::Zigbuild | Self::Custom(_) => Ok(metadata.target_directory.clone()),
+        }
+    }
+}
+
 /// Executes a `cargo build` command and returns paths to the build artifacts.
 ///
+/// The build artifacts are the executables, and the library files of the
+/// `cdylib`, `dylib` and `staticlib` targets (see [`is_library_file`]).
+/// Library files are returned only for the given package and target, not
+/// for their dependencies.
+///
 /// # Examples
 ///
 /// ```no_run
 /// # fn main() -> cli_xtask::Result<()> {
+/// use cli_xtask::cargo::BuildBackend;
+///
 /// // executes cargo build
 /// let workspace = cli_xtask::workspace::current();
-/// for bin in cli_xtask::cargo::build(workspace, None, None, None, None, false, None)? {
+/// for bin in cli_xtask::cargo::build(
+///     workspace,
+///     None,
+///     None,
+///     None,
+///     None,
+///     &BuildBackend::Cargo,
+///     None,
+/// )? {
 ///     let bin = bin?;
 ///     println!("{bin}");
 /// }
@@ -36,7 +159,7 @@ use crate::{Error, Result};
 ///     Some(target),
 ///     Some("release"),
 ///     vec!["--features", "feature-a"],
-///     true,
+///     &BuildBackend::Cross,
 ///     Some("aarch64-unknown-linux-gnu"),
 /// )? {
 ///     let bin = bin?;
@@ -52,11 +175,10 @@ pub fn build<'a>(
     target: Option<&'a Target>,
     profile: Option<&'a str>,
     build_options: impl IntoIterator<Item = &'a str>,
-    use_cross: bool,
+    backend: &'a BuildBackend,
     target_triple: Option<&'a str>,
 ) -> Result<impl Iterator<Item = Result<Utf8PathBuf>> + 'a> {
-    let cmd_name = if use_cross { "cross" } else { "cargo" };
-    let mut args = vec!["build"];
+    let (cmd_name, mut args) = backend.command()?;
 
     if let Some(package) = package {
         args.extend(["--package", package.name.as_str()]);
@@ -69,7 +191,17 @@ pub fn build<'a>(
                 TargetKind::Example => args.extend(["--example", target.name.as_str()]),
                 TargetKind::Test => args.extend(["--test", target.name.as_str()]),
                 TargetKind::Bench => args.extend(["--bench", target.name.as_str()]),
-                TargetKind::Lib => args.extend(["--lib"]),
+                TargetKind::Lib
+                | TargetKind::RLib
+                | TargetKind::DyLib
+                | TargetKind::CDyLib
+                | TargetKind::StaticLib
+                | TargetKind::ProcMacro => {
+                    // a library target may have multiple crate types
+                    if !args.contains(&"--lib") {
+                        args.push("--lib");
+                    }
+                }
                 _ => bail!("unsupported target kind: {}", kind),
             }
         }
@@ -85,16 +217,9 @@ pub fn build<'a>(
 
     args.extend(build_options);
 
-    let cross_target_dir = if use_cross {
-        let mut cmd = MetadataCommand::new();
-        cmd.cargo_path("cross").no_deps();
-        if let Some(target_triple) = target_triple {
-            cmd.other_options(["--target".to_string(), target_triple.to_string()]);
-        }
-        Some(cmd.exec()?.target_directory)
-    } else {
-        None
-    };
+    // artifacts are reported with paths in the backend's target directory
+    let backend_target_dir = Some(backend.target_directory(metadata, target_triple)?)
+        .filter(|dir| *dir != metadata.target_directory);
 
     tracing::info!("{} {}", cmd_name, args.join(" "));
     args.push("--message-format=json-render-diagnostics");
@@ -108,14 +233,36 @@ pub fn build<'a>(
     let reader = BufReader::new(stdout);
     let it = Message::parse_stream(reader)
         .map(|res| res.map_err(Error::from))
-        .filter_map(|res| match res {
-            Ok(Message::CompilerArtifact(Artifact { executable, .. })) => executable.map(Ok),
-            Err(e) => Some(Err(e)),
-            _ => None,
+        .flat_map(move |res| match res {
+            Ok(Message::CompilerArtifact(Artifact {
+                executable: Some(executable),
+                ..
+            })) => vec![Ok(executable)],
+            Ok(Message::CompilerArtifact(Artifact {
+                package_id,
+                target: artifact_target,
+                filenames,
+                ..
+            })) => {
+                let requested = package.is_none_or(|package| package.id == package_id)
+                    && target.is_none_or(|target| {
+                        target.name == artifact_target.name && target.kind == artifact_target.kind
+                    });
+                if !requested {
+                    return vec![];
+                }
+                filenames
+                    .into_iter()
+                    .filter(|file| is_library_file(file))
+                    .map(Ok)
+                    .collect()
+            }
+            Err(e) => vec![Err(e)],
+            _ => vec![],
         })
         .map(move |res| {
             res.and_then(|mut exe| {
-                if let Some(target_dir) = &cross_target_dir {
+                if let Some(target_dir) = &backend_target_dir {
                     let relative = exe.strip_prefix(target_dir)?;
                     exe = metadata.target_directory.join(relative);
                 }
@@ -132,3 +279,88 @@ pub fn build<'a>(
         );
     Ok(it)
 }
+
+/// Returns `true` if the given file is a library file to be distributed.
+///
+/// Shared libraries (`.so`, `.dylib`, `.dll`), static libraries (`.a`,
+/// `.lib`) and import libraries (`.dll.a`, `.dll.lib`) are library files.
+/// Rust-specific libraries (`.rlib`, `.rmeta`) are not.
+///
+/// # Examples
+///
+/// ```rust
+/// use cli_xtask::{camino::Utf8Path, cargo};
+///
+/// assert!(cargo::is_library_file(Utf8Path::new("target/release/libfoo.so")));
+/// assert!(cargo::is_library_file(Utf8Path::new("target/release/foo.dll.lib")));
+/// assert!(!cargo::is_library_file(Utf8Path::new("target/release/libfoo.rlib")));
+/// assert!(!cargo::is_library_file(Utf8Path::new("target/release/foo")));
+/// ```
+pub fn is_library_file(path: &Utf8Path) -> bool {
+    matches!(path.extension(), Some("so" | "dylib" | "dll" | "a" | "lib"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_backend() {
+        let custom =
+            |args: &[&str]| BuildBackend::Custom(args.iter().map(|s| s.to_string()).collect());
+        let cases = [
+            ("cargo", BuildBackend::Cargo, ("cargo", vec!["build"])),
+            ("cross", BuildBackend::Cross, ("cross", vec!["build"])),
+            (
+                "zigbuild",
+                BuildBackend::Zigbuild,
+                ("cargo", vec!["zigbuild"]),
+            ),
+            (
+                "cargo auditable build",
+                custom(&["cargo", "auditable", "build"]),
+                ("cargo", vec!["auditable", "build"]),
+            ),
+            ("my-build", custom(&["my-build"]), ("my-build", vec![])),
+        ];
+        for (input, backend, command) in cases {
+            assert_eq!(
+                input.parse::<BuildBackend>().unwrap(),
+                backend,
+                "input: {input}"
+            );
+            assert_eq!(backend.to_string(), input, "input: {input}");
+            assert_eq!(backend.command().unwrap(), command, "input: {input}");
+        }
+
+        for input in ["", "  "] {
+            assert!(input.parse::<BuildBackend>().is_err(), "input: {input:?}");
+        }
+        assert!(BuildBackend::Custom(vec![]).command().is_err());
+    }
+
+    #[test]
+    fn library_file() {
+        let cases = [
+            ("libfoo.so", true),
+            ("libfoo.dylib", true),
+            ("foo.dll", true),
+            ("libfoo.a", true),
+            ("foo.lib", true),
+            ("foo.dll.a", true),
+            ("foo.dll.lib", true),
+            ("libfoo.rlib", false),
+            ("libfoo.rmeta", false),
+            ("foo", false),
+            ("foo.exe", false),
+            ("foo.pdb", false),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(
+                is_library_file(Utf8Path::new(path)),
+                expected,
+                "path: {path}"
+            );
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     Result, Run, Xtask,
-    config::{Config, ConfigBuilder, DistConfigBuilder, DistTargetConfigBuilder},
+    config::{Config, ConfigBuilder, DistConfigBuilder, DistPackageConfigBuilder},
     workspace,
 };
 
@@ -11,7 +11,9 @@ where
     /// Entry point for xtask crate.
     ///
     /// This function initializes error handler and logger, then runs the
-    /// subcommand. Default configuration will be passed to subcommand.
+    /// subcommand. Configuration read from the `[workspace.metadata.cli-xtask]`
+    /// and `[package.metadata.cli-xtask]` tables of `Cargo.toml` will be passed
+    /// to subcommand (see [`DistConfigBuilder::from_metadata`]).
     ///
     /// # Examples
     ///
@@ -25,14 +27,12 @@ where
     pub fn main() -> Result<()> {
         Self::main_with_config(|| {
             let workspace = workspace::current();
-            let (dist, package) = DistConfigBuilder::from_root_package(workspace)?;
-            let targets = package
-                .all_binaries()
+            let (dist, packages) = DistConfigBuilder::from_metadata(workspace)?;
+            let packages = packages
                 .into_iter()
-                .map(DistTargetConfigBuilder::build)
+                .map(DistPackageConfigBuilder::build)
                 .collect::<Result<Vec<_>>>()?;
-            let package = package.targets(targets).build()?;
-            let dist = dist.package(package).build()?;
+            let dist = dist.packages(packages).build()?;
             let config = ConfigBuilder::new().dist(dist).build()?;
             Ok(config)
         })
@@ -0,0 +1,83 @@
+//! Utilities for walking the tree of the commands.
+
+use std::iter;
+
+/// Returns an iterator over the command and all its descendant subcommands.
+///
+/// Subcommands are renamed to the full command line to invoke them (e.g.
+/// `app sub`), and inherit the version and the author of the parent command.
+pub(crate) fn iterate_commands(cmd: clap::Command) -> Box<dyn Iterator<Item = clap::Command>> {
+    #[allow(clippy::needless_collect)]
+    let subcommands = cmd.get_subcommands().cloned().collect::<Vec<_>>();
+    let command_name = cmd.get_name().to_string();
+    let command_version = cmd.get_version().map(str::to_string);
+    let command_long_version = cmd.get_long_version().map(str::to_string);
+    let command_author = cmd.get_author().map(str::to_string);
+
+    let it = iter::once(cmd).chain(
+        subcommands
+            .into_iter()
+            .map(move |mut subcommand| {
+                let name = format!("{command_name} {}", subcommand.get_name());
+                subcommand = subcommand.name(name);
+                if subcommand.get_version().is_none()
+                    && let Some(version) = &command_version
+                {
+                    subcommand = subcommand.version(version);
+                }
+                if subcommand.get_long_version().is_none()
+                    && let Some(long_version) = &command_long_version
+                {
+                    subcommand = subcommand.long_version(long_version);
+                }
+                if subcommand.get_author().is_none()
+                    && let Some(author) = &command_author
+                {
+                    subcommand = subcommand.author(author);
+                }
+                subcommand
+            })
+            .flat_map(iterate_commands),
+    );
+    Box::new(it)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands() {
+        let cmd = clap::Command::new("app")
+            .version("1.0.0")
+            .author("Author")
+            .subcommand(
+                clap::Command::new("sub").subcommand(clap::Command::new("leaf").version("2.0.0")),
+            )
+            .subcommand(clap::Command::new("other"));
+
+        let commands = iterate_commands(cmd)
+            .map(|cmd| {
+                (
+                    cmd.get_name().to_owned(),
+                    cmd.get_version().map(str::to_owned),
+                    cmd.get_author().map(str::to_owned),
+                )
+            })
+            .collect::<Vec<_>>();
+        let expected = [
+            ("app", "1.0.0"),
+            ("app sub", "1.0.0"),
+            ("app sub leaf", "2.0.0"),
+            ("app other", "1.0.0"),
+        ]
+        .map(|(name, version)| {
+            (
+                name.to_owned(),
+                Some(version.to_owned()),
+                Some("Author".to_owned()),
+            )
+        });
+        assert_eq!(commands, expected);
+    }
+}
@@ -0,0 +1,183 @@
+//! Shell completion file generators.
+
+use std::{fmt, io::Write, sync::Arc};
+
+use clap_complete::Generator;
+
+/// Shell supported by `cargo xtask dist-build-completion` out of the box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, clap::ValueEnum)]
+#[non_exhaustive]
+pub enum Shell {
+    /// Bourne Again SHell (bash)
+    Bash,
+    /// Elvish shell
+    Elvish,
+    /// Friendly Interactive SHell (fish)
+    Fish,
+    /// PowerShell
+    #[allow(clippy::enum_variant_names)]
+    #[value(name = "powershell")]
+    PowerShell,
+    /// Z SHell (zsh)
+    Zsh,
+    /// Nushell
+    Nushell,
+}
+
+impl Shell {
+    /// Returns all the supported shells.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cli_xtask::completion::Shell;
+    ///
+    /// assert_eq!(Shell::all().len(), 6);
+    /// assert!(Shell::all().contains(&Shell::Nushell));
+    /// ```
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Bash,
+            Self::Elvish,
+            Self::Fish,
+            Self::PowerShell,
+            Self::Zsh,
+            Self::Nushell,
+        ]
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Shell::Bash => fmt::Display::fmt(&clap_complete::Shell::Bash, f),
+            Shell::Elvish => fmt::Display::fmt(&clap_complete::Shell::Elvish, f),
+            Shell::Fish => fmt::Display::fmt(&clap_complete::Shell::Fish, f),
+            Shell::PowerShell => fmt::Display::fmt(&clap_complete::Shell::PowerShell, f),
+            Shell::Zsh => fmt::Display::fmt(&clap_complete::Shell::Zsh, f),
+            Shell::Nushell => fmt::Display::fmt("nushell", f),
+        }
+    }
+}
+
+impl Generator for Shell {
+    fn file_name(&self, name: &str) -> String {
+        match self {
+            Shell::Bash => Generator::file_name(&clap_complete::Shell::Bash, name),
+            Shell::Elvish => Generator::file_name(&clap_complete::Shell::Elvish, name),
+            Shell::Fish => Generator::file_name(&clap_complete::Shell::Fish, name),
+            Shell::PowerShell => Generator::file_name(&clap_complete::Shell::PowerShell, name),
+            Shell::Zsh => Generator::file_name(&clap_complete::Shell::Zsh, name),
+            Shell::Nushell => Generator::file_name(&clap_complete_nushell::Nushell, name),
+        }
+    }
+
+    fn generate(&self, cmd: &clap::Command, buf: &mut dyn Write) {
+        match self {
+            Shell::Bash => Generator::generate(&clap_complete::Shell::Bash, cmd, buf),
+            Shell::Elvish => Generator::generate(&clap_complete::Shell::Elvish, cmd, buf),
+            Shell::Fish => Generator::generate(&clap_complete::Shell::Fish, cmd, buf),
+            Shell::PowerShell => Generator::generate(&clap_complete::Shell::PowerShell, cmd, buf),
+            Shell::Zsh => Generator::generate(&clap_complete::Shell::Zsh, cmd, buf),
+            Shell::Nushell => Generator::generate(&clap_complete_nushell::Nushell, cmd, buf),
+        }
+    }
+}
+
+/// Named [`Generator`] generating completion files for a shell not supported
+/// out of the box.
+///
+/// # Examples
+///
+/// ```rust
+/// use cli_xtask::{clap_complete, completion::CompletionGenerator};
+///
+/// let generator = CompletionGenerator::new("bash-compat", clap_complete::Shell::Bash);
+/// assert_eq!(generator.name(), "bash-compat");
+/// ```
+#[derive(Clone)]
+pub struct CompletionGenerator {
+    name: String,
+    generator: Arc<dyn Generator + Send + Sync>,
+}
+
+impl fmt::Debug for CompletionGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompletionGenerator")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CompletionGenerator {
+    /// Creates a new `CompletionGenerator` with the given name.
+    ///
+    /// The name is used to select the generator with
+    /// `cargo xtask dist-build-completion --shell`.
+    pub fn new(name: impl Into<String>, generator: impl Generator + Send + Sync + 'static) -> Self {
+        Self {
+            name: name.into(),
+            generator: Arc::new(generator),
+        }
+    }
+
+    /// Returns the name of the generator.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Generator for CompletionGenerator {
+    fn file_name(&self, name: &str) -> String {
+        self.generator.file_name(name)
+    }
+
+    fn generate(&self, cmd: &clap::Command, buf: &mut dyn Write) {
+        self.generator.generate(cmd, buf)
+    }
+
+    fn try_generate(&self, cmd: &clap::Command, buf: &mut dyn Write) -> std::io::Result<()> {
+        self.generator.try_generate(cmd, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::ValueEnum as _;
+
+    use super::*;
+
+    #[test]
+    fn shell() {
+        let cases = [
+            (Shell::Bash, "bash", "app.bash"),
+            (Shell::Elvish, "elvish", "app.elv"),
+            (Shell::Fish, "fish", "app.fish"),
+            (Shell::PowerShell, "powershell", "_app.ps1"),
+            (Shell::Zsh, "zsh", "_app"),
+            (Shell::Nushell, "nushell", "app.nu"),
+        ];
+        assert_eq!(Shell::all().len(), cases.len());
+        for (shell, name, file_name) in cases {
+            assert_eq!(shell.to_string(), name, "shell: {shell:?}");
+            assert_eq!(Shell::from_str(name, false), Ok(shell), "shell: {shell:?}");
+            assert_eq!(shell.file_name("app"), file_name, "shell: {shell:?}");
+        }
+    }
+
+    #[test]
+    fn custom_generator() {
+        let mut cmd = clap::Command::new("app")
+            .bin_name("app")
+            .arg(clap::Arg::new("flag").long("flag"));
+        cmd.build();
+        let generator = CompletionGenerator::new("bash-compat", clap_complete::Shell::Bash);
+        assert_eq!(generator.file_name("app"), "app.bash");
+
+        let mut expected = vec![];
+        clap_complete::Shell::Bash.generate(&cmd, &mut expected);
+        let mut actual = vec![];
+        generator.generate(&cmd, &mut actual);
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,11 +1,27 @@
 //! Data structures for workflow configuration.
 
 mod dist;
+#[cfg(any(
+    feature = "subcommand-dist-build-license",
+    feature = "subcommand-dist-build-doc"
+))]
+mod dist_file;
 mod dist_package;
 mod dist_target;
+#[cfg(feature = "config-metadata")]
+mod metadata;
 
 use eyre::eyre;
 
+#[cfg(any(
+    feature = "subcommand-dist-build-license",
+    feature = "subcommand-dist-build-doc"
+))]
+pub use self::dist_file::DistFile;
+#[cfg(feature = "subcommand-dist-build-readme")]
+pub use self::dist_package::ReadmeFormat;
+#[cfg(feature = "subcommand-dist-build-man")]
+pub use self::dist_target::ManDate;
 pub use self::{
     dist::{DistConfig, DistConfigBuilder},
     dist_package::{DistPackageConfig, DistPackageConfigBuilder},
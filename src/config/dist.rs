@@ -6,6 +6,12 @@ use eyre::eyre;
 
 use super::{DistPackageConfig, DistPackageConfigBuilder};
 use crate::Result;
+#[cfg(feature = "subcommand-dist-archive")]
+use crate::archive::{ArchiveFormat, Compression};
+#[cfg(feature = "subcommand-dist-build-bin")]
+use crate::cargo::BuildBackend;
+#[cfg(feature = "subcommand-dist-build-completion")]
+use crate::completion::{CompletionGenerator, Shell};
 
 /// Configures and constructs [`DistConfig`].
 ///
@@ -29,6 +35,40 @@ pub struct DistConfigBuilder<'a> {
     packages: Vec<DistPackageConfig<'a>>,
     #[cfg(feature = "subcommand-dist-build-bin")]
     cargo_build_options: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    cargo_profile: String,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    strip_executables: bool,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    split_debuginfo: bool,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    target_triples: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    build_backend: BuildBackend,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    target_build_backends: Vec<(String, BuildBackend)>,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_format: ArchiveFormat,
+    #[cfg(feature = "subcommand-dist-archive")]
+    target_archive_formats: Vec<(String, ArchiveFormat)>,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_compressions: Vec<Compression>,
+    #[cfg(feature = "subcommand-dist-archive")]
+    reproducible_archive: bool,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_prefix: String,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_name: String,
+    #[cfg(feature = "subcommand-dist-build-recipes")]
+    download_url: String,
+    #[cfg(feature = "subcommand-dist-build-completion")]
+    completion_shells: Vec<Shell>,
+    #[cfg(feature = "subcommand-dist-build-completion")]
+    completion_generators: Vec<CompletionGenerator>,
+    #[cfg(feature = "subcommand-dist-build-cli-doc")]
+    cli_doc_directory: Option<Utf8PathBuf>,
+    #[cfg(feature = "subcommand-dist-build-license")]
+    third_party_licenses: bool,
 }
 
 impl<'a> DistConfigBuilder<'a> {
@@ -60,6 +100,40 @@ impl<'a> DistConfigBuilder<'a> {
             packages: vec![],
             #[cfg(feature = "subcommand-dist-build-bin")]
             cargo_build_options: vec![],
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            cargo_profile: "release".to_string(),
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            strip_executables: false,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            split_debuginfo: false,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            target_triples: vec![],
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            build_backend: BuildBackend::default(),
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            target_build_backends: vec![],
+            #[cfg(feature = "subcommand-dist-archive")]
+            archive_format: ArchiveFormat::default(),
+            #[cfg(feature = "subcommand-dist-archive")]
+            target_archive_formats: vec![],
+            #[cfg(feature = "subcommand-dist-archive")]
+            archive_compressions: vec![Compression::default()],
+            #[cfg(feature = "subcommand-dist-archive")]
+            reproducible_archive: false,
+            #[cfg(feature = "subcommand-dist-archive")]
+            archive_prefix: "{name}-{target}".to_string(),
+            #[cfg(feature = "subcommand-dist-archive")]
+            archive_name: "{name}-{target}".to_string(),
+            #[cfg(feature = "subcommand-dist-build-recipes")]
+            download_url: "{repository}/releases/download/v{version}/{file}".to_string(),
+            #[cfg(feature = "subcommand-dist-build-completion")]
+            completion_shells: Shell::all().to_vec(),
+            #[cfg(feature = "subcommand-dist-build-completion")]
+            completion_generators: vec![],
+            #[cfg(feature = "subcommand-dist-build-cli-doc")]
+            cli_doc_directory: None,
+            #[cfg(feature = "subcommand-dist-build-license")]
+            third_party_licenses: false,
         }
     }
 
@@ -126,6 +200,92 @@ impl<'a> DistConfigBuilder<'a> {
         Ok(Self::from_package(workspace, package))
     }
 
+    /// Creates a new `DistConfigBuilder` from the `[workspace.metadata.cli-xtask.dist]`
+    /// and `[package.metadata.cli-xtask.dist]` tables of the given workspace.
+    ///
+    /// The workspace table selects the packages to distribute and configures
+    /// the whole distribution:
+    ///
+    /// ```toml
+    /// [workspace.metadata.cli-xtask.dist]
+    /// name = "app-v1.0.0"              # default: `{package}-v{version}` of the first package
+    /// packages = ["app", "app-cli"]    # default: the root package, or the default members
+    /// cargo-build-options = ["--locked"]
+    /// cargo-profile = "dist"
+    /// target-triples = ["x86_64-unknown-linux-gnu"]
+    /// strip-executables = true
+    /// split-debuginfo = true
+    /// archive-format = "tar"
+    /// archive-compressions = ["gz", "xz"]
+    /// reproducible-archive = true
+    /// archive-prefix = "{name}"
+    /// archive-name = "{name}-{target}"
+    /// download-url = "https://example.com/{file}"
+    /// third-party-licenses = true
+    /// ```
+    ///
+    /// The package table configures each of the packages:
+    ///
+    /// ```toml
+    /// [package.metadata.cli-xtask.dist]
+    /// binaries = ["app"]               # default: all the binaries
+    /// cargo-build-options = ["--features", "cli"]
+    /// cargo-profile = "dist"
+    /// c-header = "include/app.h"
+    /// pkg-config = true
+    /// documents = ["README.md", { src = "docs/*.md", dest = "guide" }]
+    /// license-files = ["LICENSE-MIT", { src = "LICENSE", dest = "COPYING" }]
+    /// extra-files = [{ src = "etc/app.toml", dest = "share/app/app.toml", mode = 0o600 }]
+    /// ```
+    ///
+    /// Paths containing glob metacharacters are expanded as glob patterns,
+    /// with `dest` as the destination directory. Listing `license-files`
+    /// disables the detection of the license files. Keys used by the disabled
+    /// features are ignored with a warning.
+    ///
+    /// The returned builders can be further configured by the code, which
+    /// overrides the settings read from the tables.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tables are invalid, or if the packages or the
+    /// binaries listed in them are not found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{
+    ///     config::{DistConfigBuilder, DistPackageConfigBuilder},
+    ///     workspace,
+    /// };
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_configs) = DistConfigBuilder::from_metadata(workspace)?;
+    /// let pkg_configs = pkg_configs
+    ///     .into_iter()
+    ///     .map(DistPackageConfigBuilder::build)
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// let dist_config = dist_config.packages(pkg_configs).build()?;
+    ///
+    /// let root_package = workspace.root_package().unwrap();
+    /// assert_eq!(
+    ///     dist_config.name(),
+    ///     format!("{}-v{}", root_package.name, root_package.version)
+    /// );
+    /// assert_eq!(dist_config.packages().len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "config-metadata")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "config-metadata")))]
+    pub fn from_metadata(
+        workspace: &'a Metadata,
+    ) -> Result<(Self, Vec<DistPackageConfigBuilder<'a>>)> {
+        super::metadata::dist_builders(workspace)
+    }
+
     /// Creates a new `DistConfigBuilder` from a package with the given name in
     /// the the given workspace.
     ///
@@ -298,12 +458,648 @@ impl<'a> DistConfigBuilder<'a> {
         self
     }
 
+    /// Sets the cargo profile used to build the executables.
+    ///
+    /// The profile given by [`DistPackageConfigBuilder::cargo_profile`] or by
+    /// `cargo xtask dist-build-bin --profile` takes precedence over it.
+    /// Defaults to `release`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .cargo_profile("dist")
+    ///     .build()?;
+    /// assert_eq!(dist_config.cargo_profile(), "dist");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn cargo_profile(mut self, profile: impl Into<String>) -> Self {
+        self.cargo_profile = profile.into();
+        self
+    }
+
+    /// Sets whether to strip the debug info and symbols from the executables
+    /// copied into the distribution.
+    ///
+    /// Stripping is done with `objcopy` for ELF executables (Linux and BSD
+    /// targets) and `strip` for Mach-O executables (Apple targets).
+    /// Executables for `*-windows-msvc` targets don't contain debug info, so
+    /// they are left untouched. Executables for the other targets, such as
+    /// `wasm32-*` and `*-windows-gnu`, are not stripped.
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .strip_executables(true)
+    ///     .build()?;
+    /// assert!(dist_config.strip_executables());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn strip_executables(mut self, strip: bool) -> Self {
+        self.strip_executables = strip;
+        self
+    }
+
+    /// Sets whether to split the debug info of the executables into separate
+    /// files before stripping them.
+    ///
+    /// The debug info files (`.debug` for ELF, `.dSYM` for Mach-O and `.pdb`
+    /// for `*-windows-msvc` targets) are placed in the
+    /// [`dist_symbols_directory`](DistConfig::dist_symbols_directory) and
+    /// archived into a symbols archive separated from the executables.
+    /// Implies [`strip_executables`](Self::strip_executables).
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .split_debuginfo(true)
+    ///     .build()?;
+    /// assert!(dist_config.split_debuginfo());
+    /// assert!(dist_config.strip_executables());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn split_debuginfo(mut self, split: bool) -> Self {
+        self.split_debuginfo = split;
+        self
+    }
+
+    /// Adds the target triples to build the executables for by default.
+    ///
+    /// The target triples given by `cargo xtask dist-build-bin --target`
+    /// take precedence over them.
+    /// If no target triple is given, the executables are built for the host.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .target_triples(["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"])
+    ///     .build()?;
+    /// assert_eq!(
+    ///     dist_config.target_triples(),
+    ///     ["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn target_triples(
+        mut self,
+        target_triples: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.target_triples
+            .extend(target_triples.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the default build backend used to build the executables.
+    ///
+    /// The default build backend is used for the target triples that do not
+    /// match any pattern given by
+    /// [`target_build_backend`](Self::target_build_backend).
+    /// Defaults to [`BuildBackend::Cargo`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{cargo::BuildBackend, config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .build_backend(BuildBackend::Zigbuild)
+    ///     .build()?;
+    /// assert_eq!(
+    ///     dist_config.build_backend("x86_64-unknown-linux-gnu"),
+    ///     &BuildBackend::Zigbuild
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn build_backend(mut self, backend: BuildBackend) -> Self {
+        self.build_backend = backend;
+        self
+    }
+
+    /// Sets the build backend for the target triples matching the given
+    /// pattern.
+    ///
+    /// `*` in the pattern matches any sequence of characters.
+    /// If multiple patterns match a target triple, the first added one is
+    /// used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{cargo::BuildBackend, config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .target_build_backend("*-linux-musl", BuildBackend::Cross)
+    ///     .build()?;
+    /// assert_eq!(
+    ///     dist_config.build_backend("x86_64-unknown-linux-musl"),
+    ///     &BuildBackend::Cross
+    /// );
+    /// assert_eq!(
+    ///     dist_config.build_backend("x86_64-unknown-linux-gnu"),
+    ///     &BuildBackend::Cargo
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn target_build_backend(
+        mut self,
+        pattern: impl Into<String>,
+        backend: BuildBackend,
+    ) -> Self {
+        self.target_build_backends.push((pattern.into(), backend));
+        self
+    }
+
+    /// Sets the default archive format of the distribution.
+    ///
+    /// The default archive format is used for the target triples that do not
+    /// match any pattern given by
+    /// [`target_archive_format`](Self::target_archive_format).
+    /// Defaults to [`ArchiveFormat::Tar`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{archive::ArchiveFormat, config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .archive_format(ArchiveFormat::Zip)
+    ///     .build()?;
+    /// assert_eq!(dist_config.archive_format("x86_64-unknown-linux-gnu"), ArchiveFormat::Zip);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn archive_format(mut self, format: ArchiveFormat) -> Self {
+        self.archive_format = format;
+        self
+    }
+
+    /// Sets the archive format for the target triples matching the given
+    /// pattern.
+    ///
+    /// `*` in the pattern matches any sequence of characters.
+    /// If multiple patterns match a target triple, the first added one is
+    /// used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{archive::ArchiveFormat, config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .target_archive_format("*-windows-*", ArchiveFormat::Zip)
+    ///     .build()?;
+    /// assert_eq!(dist_config.archive_format("x86_64-pc-windows-msvc"), ArchiveFormat::Zip);
+    /// assert_eq!(dist_config.archive_format("x86_64-unknown-linux-gnu"), ArchiveFormat::Tar);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn target_archive_format(
+        mut self,
+        pattern: impl Into<String>,
+        format: ArchiveFormat,
+    ) -> Self {
+        self.target_archive_formats.push((pattern.into(), format));
+        self
+    }
+
+    /// Sets the compression of the distribution archives.
+    ///
+    /// This replaces the compressions set previously.
+    /// Defaults to gzip with the default compression level.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{
+    ///     archive::{Compression, CompressionCodec},
+    ///     config::DistConfigBuilder,
+    ///     workspace,
+    /// };
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .archive_compression(Compression::new(CompressionCodec::Xz, Some(9)))
+    ///     .build()?;
+    /// assert_eq!(
+    ///     dist_config.archive_compressions(),
+    ///     [Compression::new(CompressionCodec::Xz, Some(9))]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn archive_compression(self, compression: Compression) -> Self {
+        self.archive_compressions([compression])
+    }
+
+    /// Sets the compressions of the distribution archives.
+    ///
+    /// A tar archive is created for each compression, so that the artifacts
+    /// can be distributed in multiple formats (e.g. `.tar.gz` and `.tar.xz`).
+    /// Zip archives are created only once, with the first compression.
+    /// This replaces the compressions set previously.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{
+    ///     archive::{Compression, CompressionCodec},
+    ///     config::DistConfigBuilder,
+    ///     workspace,
+    /// };
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .archive_compressions([
+    ///         Compression::default(),
+    ///         Compression::new(CompressionCodec::Zstd, None),
+    ///     ])
+    ///     .build()?;
+    /// assert_eq!(dist_config.archive_compressions().len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn archive_compressions(
+        mut self,
+        compressions: impl IntoIterator<Item = Compression>,
+    ) -> Self {
+        self.archive_compressions = compressions.into_iter().collect();
+        self
+    }
+
+    /// Sets whether to create reproducible distribution archives.
+    ///
+    /// See [`ArchiveOptions::reproducible`] for details.
+    /// Defaults to `false`.
+    ///
+    /// [`ArchiveOptions::reproducible`]: crate::archive::ArchiveOptions::reproducible
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .reproducible_archive(true)
+    ///     .build()?;
+    /// assert!(dist_config.reproducible_archive());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn reproducible_archive(mut self, reproducible: bool) -> Self {
+        self.reproducible_archive = reproducible;
+        self
+    }
+
+    /// Sets the template of the top-level directory inside the distribution
+    /// archives.
+    ///
+    /// All artifacts are placed under this directory in the archives.
+    /// The same placeholders as [`archive_name`](Self::archive_name) are
+    /// available.
+    /// Use the empty string to place the artifacts at the root of the
+    /// archives.
+    /// Defaults to `{name}-{target}`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace).build()?;
+    /// assert_eq!(
+    ///     dist_config.archive_prefix("x86_64-unknown-linux-gnu")?,
+    ///     "app-dist-x86_64-unknown-linux-gnu"
+    /// );
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .archive_prefix("{name}")
+    ///     .build()?;
+    /// assert_eq!(dist_config.archive_prefix("x86_64-unknown-linux-gnu")?, "app-dist");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn archive_prefix(mut self, template: impl Into<String>) -> Self {
+        self.archive_prefix = template.into();
+        self
+    }
+
+    /// Sets the template of the file name of the distribution archives,
+    /// without the extension.
+    ///
+    /// The extension is derived from the archive format and the compression
+    /// (e.g. `.tar.gz`, `.zip`).
+    /// The following placeholders are replaced:
+    ///
+    /// * `{name}` - the name of the distribution
+    /// * `{package}` - the name of the first distributed package, or of the
+    ///   workspace root package if no package is distributed
+    /// * `{version}` - the version of the package above
+    /// * `{target}` - the target triple (e.g. `x86_64-unknown-linux-gnu`)
+    /// * `{arch}`, `{vendor}`, `{os}`, `{env}` - the components of the target
+    ///   triple (e.g. `x86_64`, `unknown`, `linux` and `gnu`). Components
+    ///   missing in the target triple are replaced with the empty string,
+    ///   except that `{vendor}` is replaced with `unknown`.
+    ///
+    /// The archive containing only architecture-independent artifacts uses
+    /// `noarch` as its target triple and as its `{arch}`.
+    /// Defaults to `{name}-{target}`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .archive_name("{package}-v{version}-{os}-{arch}")
+    ///     .build()?;
+    /// let version = &workspace.root_package().unwrap().version;
+    /// assert_eq!(
+    ///     dist_config.archive_name("x86_64-unknown-linux-gnu")?,
+    ///     format!("cli-xtask-v{version}-linux-x86_64")
+    /// );
+    /// assert_eq!(
+    ///     dist_config.archive_name("aarch64-apple-darwin")?,
+    ///     format!("cli-xtask-v{version}-darwin-aarch64")
+    /// );
+    /// assert_eq!(
+    ///     dist_config.archive_name("wasm32-wasip1")?,
+    ///     format!("cli-xtask-v{version}-wasip1-wasm32")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn archive_name(mut self, template: impl Into<String>) -> Self {
+        self.archive_name = template.into();
+        self
+    }
+
+    /// Sets the template of the URL to download the distribution archives
+    /// from, used in the package manager recipes.
+    ///
+    /// In addition to the placeholders of [`archive_name`](Self::archive_name),
+    /// the following placeholders are replaced:
+    ///
+    /// * `{file}` - the file name of the archive
+    /// * `{repository}` - the `repository` of the package above, without the
+    ///   trailing `/` or `.git`
+    ///
+    /// Defaults to `{repository}/releases/download/v{version}/{file}`, which
+    /// points to the assets of GitHub releases.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    /// let version = &workspace.root_package().unwrap().version;
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace).build()?;
+    /// assert_eq!(
+    ///     dist_config.download_url("x86_64-unknown-linux-gnu", "app.tar.gz")?,
+    ///     format!("https://github.com/gifnksm/cli-xtask/releases/download/v{version}/app.tar.gz")
+    /// );
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .download_url("https://example.com/{package}/{version}/{file}")
+    ///     .build()?;
+    /// assert_eq!(
+    ///     dist_config.download_url("x86_64-unknown-linux-gnu", "app.tar.gz")?,
+    ///     format!("https://example.com/cli-xtask/{version}/app.tar.gz")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-recipes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-recipes")))]
+    pub fn download_url(mut self, template: impl Into<String>) -> Self {
+        self.download_url = template.into();
+        self
+    }
+
+    /// Sets the shells to generate completion files for.
+    ///
+    /// Defaults to all the shells in [`Shell::all`].
+    /// This replaces the shells set previously.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{completion::Shell, config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace).build()?;
+    /// assert_eq!(dist_config.completion_shells(), Shell::all());
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .completion_shells([Shell::Bash, Shell::Zsh])
+    ///     .build()?;
+    /// assert_eq!(dist_config.completion_shells(), [Shell::Bash, Shell::Zsh]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-completion")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-completion")))]
+    pub fn completion_shells(mut self, shells: impl IntoIterator<Item = Shell>) -> Self {
+        self.completion_shells = shells.into_iter().collect();
+        self
+    }
+
+    /// Registers a completion file generator for a shell not supported out of
+    /// the box.
+    ///
+    /// Completion files are generated with the registered generators in
+    /// addition to the [shells](Self::completion_shells), and the generator can
+    /// be selected by `name` with `cargo xtask dist-build-completion --shell`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{clap_complete, config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .completion_generator("bash-compat", clap_complete::Shell::Bash)
+    ///     .build()?;
+    /// assert_eq!(dist_config.completion_generators()[0].name(), "bash-compat");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-completion")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-completion")))]
+    pub fn completion_generator(
+        mut self,
+        name: impl Into<String>,
+        generator: impl clap_complete::Generator + Send + Sync + 'static,
+    ) -> Self {
+        self.completion_generators
+            .push(CompletionGenerator::new(name, generator));
+        self
+    }
+
+    /// Sets the directory in the workspace where the command line reference
+    /// pages are kept.
+    ///
+    /// `cargo xtask dist-build-cli-doc --check` (and `cargo xtask lint`)
+    /// checks that the pages in the directory are up to date.
+    /// If the given path is a relative path, it is resolved against the
+    /// workspace root directory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .cli_doc_directory("doc/cli")
+    ///     .build()?;
+    /// assert_eq!(
+    ///     dist_config.cli_doc_directory().unwrap(),
+    ///     workspace.workspace_root.join("doc/cli")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-cli-doc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-cli-doc")))]
+    pub fn cli_doc_directory(mut self, dir: impl Into<Utf8PathBuf>) -> Self {
+        self.cli_doc_directory = Some(self.metadata.workspace_root.join(dir.into()));
+        self
+    }
+
+    /// Sets whether to build the license files of the third-party crates.
+    ///
+    /// If enabled, `cargo xtask dist-build-license` walks the normal
+    /// dependencies of the distributed packages resolved for each built target
+    /// triple, and writes their license expressions and license texts into
+    /// `THIRD_PARTY_LICENSES` (plain text) and `THIRD_PARTY_LICENSES.html` in
+    /// the working directory of the target triple.
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let dist_config = DistConfigBuilder::new("app-dist", workspace)
+    ///     .third_party_licenses(true)
+    ///     .build()?;
+    /// assert!(dist_config.third_party_licenses());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-license")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-license")))]
+    pub fn third_party_licenses(mut self, enabled: bool) -> Self {
+        self.third_party_licenses = enabled;
+        self
+    }
+
     /// Builds a [`DistConfig`] from the current configuration.
     ///
     /// # Errors
     ///
     /// Returns an error if the [`DistConfig`] cannot be built.
     pub fn build(self) -> Result<DistConfig<'a>> {
+        #[cfg(feature = "subcommand-dist-archive")]
+        eyre::ensure!(
+            !self.archive_compressions.is_empty(),
+            "at least one archive compression must be specified"
+        );
+
         Ok(DistConfig {
             name: self.name,
             metadata: self.metadata,
@@ -312,6 +1108,40 @@ impl<'a> DistConfigBuilder<'a> {
             packages: self.packages,
             #[cfg(feature = "subcommand-dist-build-bin")]
             cargo_build_options: self.cargo_build_options,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            cargo_profile: self.cargo_profile,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            strip_executables: self.strip_executables,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            split_debuginfo: self.split_debuginfo,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            target_triples: self.target_triples,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            build_backend: self.build_backend,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            target_build_backends: self.target_build_backends,
+            #[cfg(feature = "subcommand-dist-archive")]
+            archive_format: self.archive_format,
+            #[cfg(feature = "subcommand-dist-archive")]
+            target_archive_formats: self.target_archive_formats,
+            #[cfg(feature = "subcommand-dist-archive")]
+            archive_compressions: self.archive_compressions,
+            #[cfg(feature = "subcommand-dist-archive")]
+            reproducible_archive: self.reproducible_archive,
+            #[cfg(feature = "subcommand-dist-archive")]
+            archive_prefix: self.archive_prefix,
+            #[cfg(feature = "subcommand-dist-archive")]
+            archive_name: self.archive_name,
+            #[cfg(feature = "subcommand-dist-build-recipes")]
+            download_url: self.download_url,
+            #[cfg(feature = "subcommand-dist-build-completion")]
+            completion_shells: self.completion_shells,
+            #[cfg(feature = "subcommand-dist-build-completion")]
+            completion_generators: self.completion_generators,
+            #[cfg(feature = "subcommand-dist-build-cli-doc")]
+            cli_doc_directory: self.cli_doc_directory,
+            #[cfg(feature = "subcommand-dist-build-license")]
+            third_party_licenses: self.third_party_licenses,
         })
     }
 }
@@ -340,6 +1170,40 @@ pub struct DistConfig<'a> {
     packages: Vec<DistPackageConfig<'a>>,
     #[cfg(feature = "subcommand-dist-build-bin")]
     cargo_build_options: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    cargo_profile: String,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    strip_executables: bool,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    split_debuginfo: bool,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    target_triples: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    build_backend: BuildBackend,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    target_build_backends: Vec<(String, BuildBackend)>,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_format: ArchiveFormat,
+    #[cfg(feature = "subcommand-dist-archive")]
+    target_archive_formats: Vec<(String, ArchiveFormat)>,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_compressions: Vec<Compression>,
+    #[cfg(feature = "subcommand-dist-archive")]
+    reproducible_archive: bool,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_prefix: String,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_name: String,
+    #[cfg(feature = "subcommand-dist-build-recipes")]
+    download_url: String,
+    #[cfg(feature = "subcommand-dist-build-completion")]
+    completion_shells: Vec<Shell>,
+    #[cfg(feature = "subcommand-dist-build-completion")]
+    completion_generators: Vec<CompletionGenerator>,
+    #[cfg(feature = "subcommand-dist-build-cli-doc")]
+    cli_doc_directory: Option<Utf8PathBuf>,
+    #[cfg(feature = "subcommand-dist-build-license")]
+    third_party_licenses: bool,
 }
 
 impl<'a> DistConfig<'a> {
@@ -374,15 +1238,485 @@ impl<'a> DistConfig<'a> {
         self.dist_base_working_directory.join(target_triple)
     }
 
+    /// Returns the directory where the debug info files split from the
+    /// executables for the given target triple will be placed at.
+    #[cfg(any(
+        feature = "subcommand-dist-archive",
+        feature = "subcommand-dist-build-bin"
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "subcommand-dist-archive",
+            feature = "subcommand-dist-build-bin"
+        )))
+    )]
+    pub fn dist_symbols_directory(&self, target_triple: &str) -> Utf8PathBuf {
+        self.dist_base_working_directory
+            .join("symbols")
+            .join(target_triple)
+    }
+
     /// Returns the configurations of the packages that will be distributed.
     pub fn packages(&'_ self) -> &'_ [DistPackageConfig<'_>] {
         &self.packages
     }
 
+    /// Returns the target triples whose artifacts are staged in the base
+    /// working directory, in sorted order.
+    #[cfg(any(
+        feature = "subcommand-dist-archive",
+        feature = "subcommand-dist-build-deb",
+        feature = "subcommand-dist-build-license",
+        feature = "subcommand-dist-build-rpm",
+        feature = "subcommand-dist-build-sbom",
+        feature = "subcommand-dist-install"
+    ))]
+    pub(crate) fn built_target_triples(&self) -> Result<Vec<String>> {
+        let mut target_triples = vec![];
+        if !self.dist_base_working_directory.is_dir() {
+            return Ok(target_triples);
+        }
+        for entry in self.dist_base_working_directory.read_dir_utf8()? {
+            let entry = entry?;
+            // `.fingerprint` is a hidden directory
+            if !entry.file_type()?.is_dir()
+                || entry.file_name().starts_with('.')
+                || ["noarch", "symbols"].contains(&entry.file_name())
+            {
+                continue;
+            }
+            target_triples.push(entry.file_name().to_owned());
+        }
+        target_triples.sort();
+        Ok(target_triples)
+    }
+
     /// Returns the cargo build options that will be used to build the
     #[cfg(feature = "subcommand-dist-build-bin")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
     pub fn cargo_build_options(&self) -> &[String] {
         &self.cargo_build_options
     }
+
+    /// Returns the cargo profile used to build the executables.
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn cargo_profile(&self) -> &str {
+        &self.cargo_profile
+    }
+
+    /// Returns `true` if the executables are stripped.
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn strip_executables(&self) -> bool {
+        self.strip_executables || self.split_debuginfo
+    }
+
+    /// Returns `true` if the debug info of the executables is split into
+    /// separate files.
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn split_debuginfo(&self) -> bool {
+        self.split_debuginfo
+    }
+
+    /// Returns the target triples to build the executables for by default.
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn target_triples(&self) -> &[String] {
+        &self.target_triples
+    }
+
+    /// Returns the build backend that will be used to build the executables
+    /// for the given target triple.
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn build_backend(&self, target_triple: &str) -> &BuildBackend {
+        self.target_build_backends
+            .iter()
+            .find(|(pattern, _)| matches_wildcard(pattern, target_triple))
+            .map(|(_, backend)| backend)
+            .unwrap_or(&self.build_backend)
+    }
+
+    /// Returns the archive format that will be used to archive the artifacts
+    /// for the given target triple.
+    ///
+    /// The archive containing only architecture-independent artifacts uses
+    /// `noarch` as its target triple.
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn archive_format(&self, target_triple: &str) -> ArchiveFormat {
+        self.target_archive_formats
+            .iter()
+            .find(|(pattern, _)| matches_wildcard(pattern, target_triple))
+            .map(|(_, format)| *format)
+            .unwrap_or(self.archive_format)
+    }
+
+    /// Returns the compressions that will be used to compress the archives.
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn archive_compressions(&self) -> &[Compression] {
+        &self.archive_compressions
+    }
+
+    /// Returns whether to create reproducible distribution archives.
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn reproducible_archive(&self) -> bool {
+        self.reproducible_archive
+    }
+
+    /// Returns the top-level directory inside the archive for the given
+    /// target triple.
+    ///
+    /// The archive containing only architecture-independent artifacts uses
+    /// `noarch` as its target triple.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the template contains an unknown placeholder.
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn archive_prefix(&self, target_triple: &str) -> Result<String> {
+        self.render_archive_template(&self.archive_prefix, target_triple, |_| None)
+    }
+
+    /// Returns the file name of the archive for the given target triple,
+    /// without the extension.
+    ///
+    /// The archive containing only architecture-independent artifacts uses
+    /// `noarch` as its target triple.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the template contains an unknown placeholder, or
+    /// if the template refers to the package but no package is found.
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+    pub fn archive_name(&self, target_triple: &str) -> Result<String> {
+        self.render_archive_template(&self.archive_name, target_triple, |_| None)
+    }
+
+    /// Returns the URL to download the archive with the given file name and
+    /// target triple from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the template contains an unknown placeholder, or
+    /// if the template refers to the package or its repository but they are
+    /// not found.
+    #[cfg(feature = "subcommand-dist-build-recipes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-recipes")))]
+    pub fn download_url(&self, target_triple: &str, file: &str) -> Result<String> {
+        let template = &self.download_url;
+        let repository = self
+            .template_package()
+            .and_then(|package| package.repository.as_deref());
+
+        let mut missing_repository = false;
+        let res = self.render_archive_template(template, target_triple, |key| {
+            let value = match key {
+                "file" => file.to_owned(),
+                "repository" => {
+                    let Some(repository) = repository else {
+                        missing_repository = true;
+                        return Some(String::new());
+                    };
+                    let repository = repository.trim_end_matches('/');
+                    let repository = repository.strip_suffix(".git").unwrap_or(repository);
+                    repository.to_owned()
+                }
+                _ => return None,
+            };
+            Some(value)
+        })?;
+        if missing_repository {
+            eyre::bail!(
+                "`repository` of the package is not set, required by `{{repository}}` in template: {template}"
+            );
+        }
+        Ok(res)
+    }
+
+    /// Returns the shells to generate completion files for.
+    #[cfg(feature = "subcommand-dist-build-completion")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-completion")))]
+    pub fn completion_shells(&self) -> &[Shell] {
+        &self.completion_shells
+    }
+
+    /// Returns the registered completion file generators.
+    #[cfg(feature = "subcommand-dist-build-completion")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-completion")))]
+    pub fn completion_generators(&self) -> &[CompletionGenerator] {
+        &self.completion_generators
+    }
+
+    /// Returns the directory in the workspace where the command line reference
+    /// pages are kept, if configured.
+    #[cfg(feature = "subcommand-dist-build-cli-doc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-cli-doc")))]
+    pub fn cli_doc_directory(&self) -> Option<&Utf8Path> {
+        self.cli_doc_directory.as_deref()
+    }
+
+    /// Returns whether to build the license files of the third-party crates.
+    #[cfg(feature = "subcommand-dist-build-license")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-license")))]
+    pub fn third_party_licenses(&self) -> bool {
+        self.third_party_licenses
+    }
+
+    /// Returns the package referred by `{package}` and `{version}` in the
+    /// templates.
+    #[cfg(feature = "subcommand-dist-archive")]
+    fn template_package(&self) -> Option<&'a Package> {
+        self.packages
+            .first()
+            .map(|package| package.metadata())
+            .or_else(|| self.metadata.root_package())
+    }
+
+    #[cfg(feature = "subcommand-dist-archive")]
+    fn render_archive_template(
+        &self,
+        template: &str,
+        target_triple: &str,
+        mut extra: impl FnMut(&str) -> Option<String>,
+    ) -> Result<String> {
+        let package = self.template_package();
+        let [arch, vendor, os, env] = split_target_triple(target_triple);
+
+        let mut missing_package = false;
+        let res = render_template(template, |key| {
+            let value = match key {
+                "name" => self.name.clone(),
+                "package" | "version" => {
+                    let Some(package) = package else {
+                        missing_package = true;
+                        return Some(String::new());
+                    };
+                    if key == "package" {
+                        package.name.to_string()
+                    } else {
+                        package.version.to_string()
+                    }
+                }
+                "target" => target_triple.to_owned(),
+                "arch" => arch.to_owned(),
+                "vendor" => vendor.to_owned(),
+                "os" => os.to_owned(),
+                "env" => env.to_owned(),
+                _ => return extra(key),
+            };
+            Some(value)
+        })?;
+        if missing_package {
+            eyre::bail!(
+                "no package found for `{{package}}` or `{{version}}` in template: {template}"
+            );
+        }
+        Ok(res)
+    }
+}
+
+/// Replaces `{key}` placeholders in the template with the values returned by
+/// `value`.
+#[cfg(any(
+    feature = "subcommand-dist-archive",
+    feature = "subcommand-dist-build-readme"
+))]
+pub(super) fn render_template(
+    template: &str,
+    mut value: impl FnMut(&str) -> Option<String>,
+) -> Result<String> {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            eyre::bail!("unclosed placeholder in template: {template}");
+        };
+        let key = &rest[start + 1..start + len];
+        let Some(value) = value(key) else {
+            eyre::bail!("unknown placeholder `{{{key}}}` in template: {template}");
+        };
+        output.push_str(&value);
+        rest = &rest[start + len + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Splits the target triple into `[arch, vendor, os, env]`.
+#[cfg(feature = "subcommand-dist-archive")]
+fn split_target_triple(target_triple: &str) -> [&str; 4] {
+    // vendors appearing in the target triples supported by rustc
+    const VENDORS: &[&str] = &[
+        "unknown",
+        "pc",
+        "apple",
+        "nvidia",
+        "fortanix",
+        "sun",
+        "wrs",
+        "uwp",
+        "kmc",
+        "sony",
+        "nintendo",
+        "espressif",
+        "esp",
+        "risc0",
+        "unikraft",
+        "win7",
+        "ibm",
+        "openwrt",
+        "lynx",
+        "mti",
+        "nuttx",
+        "amd",
+        "oe",
+    ];
+
+    if target_triple == "noarch" {
+        return ["noarch", "", "", ""];
+    }
+    let mut parts = target_triple.splitn(2, '-');
+    let arch = parts.next().unwrap_or_default();
+    let rest = parts.next().unwrap_or_default();
+    let (vendor, rest) = match rest.split_once('-') {
+        Some((vendor, rest)) if VENDORS.contains(&vendor) => (vendor, rest),
+        None if VENDORS.contains(&rest) => (rest, ""),
+        _ => ("unknown", rest),
+    };
+    let (os, env) = rest.split_once('-').unwrap_or((rest, ""));
+    [arch, vendor, os, env]
+}
+
+#[cfg(any(
+    feature = "subcommand-dist-archive",
+    feature = "subcommand-dist-build-bin"
+))]
+fn matches_wildcard(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // `split` always yields at least one item
+    let first = parts.next().unwrap();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else {
+        // no `*` in the pattern
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(all(test, feature = "subcommand-dist-archive"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template() {
+        let value = |key: &str| match key {
+            "name" => Some("app".to_owned()),
+            "target" => Some("x86_64-unknown-linux-gnu".to_owned()),
+            "empty" => Some(String::new()),
+            _ => None,
+        };
+        let cases = [
+            ("", Ok("")),
+            ("app.tar", Ok("app.tar")),
+            ("{name}", Ok("app")),
+            (
+                "{name}-{target}.tar",
+                Ok("app-x86_64-unknown-linux-gnu.tar"),
+            ),
+            ("{name}{empty}}", Ok("app}")),
+            ("{name", Err("unclosed placeholder in template: {name")),
+            (
+                "{name}-{version}",
+                Err("unknown placeholder `{version}` in template: {name}-{version}"),
+            ),
+            ("{}", Err("unknown placeholder `{}` in template: {}")),
+        ];
+        for (input, expected) in cases {
+            let res = render_template(input, value).map_err(|e| e.to_string());
+            assert_eq!(
+                res.as_deref(),
+                expected.map_err(str::to_owned).as_deref(),
+                "input: {input}"
+            );
+        }
+    }
+
+    #[test]
+    fn wildcard() {
+        let cases = [
+            ("*-windows-*", "x86_64-pc-windows-msvc", true),
+            ("*-windows-*", "x86_64-unknown-linux-gnu", false),
+            ("*-linux-*", "x86_64-unknown-linux-gnu", true),
+            ("x86_64-*", "x86_64-apple-darwin", true),
+            ("*-darwin", "aarch64-apple-darwin", true),
+            ("*-darwin", "aarch64-apple-darwin-foo", false),
+            ("noarch", "noarch", true),
+            ("noarch", "noarch2", false),
+            ("*", "noarch", true),
+            ("a*b*a", "aba", true),
+            ("a*a", "a", false),
+        ];
+        for (pattern, text, expected) in cases {
+            assert_eq!(
+                matches_wildcard(pattern, text),
+                expected,
+                "pattern: {pattern}, text: {text}"
+            );
+        }
+    }
+
+    #[test]
+    fn target_triple() {
+        let cases = [
+            (
+                "x86_64-unknown-linux-gnu",
+                ["x86_64", "unknown", "linux", "gnu"],
+            ),
+            (
+                "x86_64-pc-windows-msvc",
+                ["x86_64", "pc", "windows", "msvc"],
+            ),
+            ("aarch64-apple-darwin", ["aarch64", "apple", "darwin", ""]),
+            (
+                "armv7-unknown-linux-gnueabihf",
+                ["armv7", "unknown", "linux", "gnueabihf"],
+            ),
+            (
+                "x86_64-linux-android",
+                ["x86_64", "unknown", "linux", "android"],
+            ),
+            (
+                "aarch64-linux-android",
+                ["aarch64", "unknown", "linux", "android"],
+            ),
+            ("wasm32-wasip1", ["wasm32", "unknown", "wasip1", ""]),
+            (
+                "wasm32-unknown-unknown",
+                ["wasm32", "unknown", "unknown", ""],
+            ),
+            ("nvptx64-nvidia-cuda", ["nvptx64", "nvidia", "cuda", ""]),
+            ("noarch", ["noarch", "", "", ""]),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(split_target_triple(input), expected, "input: {input}");
+        }
+    }
 }
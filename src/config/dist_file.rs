@@ -0,0 +1,392 @@
+use cargo_metadata::camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use eyre::{bail, ensure, eyre};
+
+use crate::{Result, fs::ToRelative};
+
+/// File copied into the distribution, with its destination path and Unix
+/// permission bits.
+///
+/// `DistFile`s are built from the file paths and the glob patterns given to
+/// [`DistPackageConfigBuilder`](super::DistPackageConfigBuilder) (e.g.
+/// [`documents_glob`](super::DistPackageConfigBuilder::documents_glob)).
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        feature = "subcommand-dist-build-license",
+        feature = "subcommand-dist-build-doc"
+    )))
+)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DistFile {
+    src: Utf8PathBuf,
+    dest: Utf8PathBuf,
+    mode: u32,
+    root_relative: bool,
+}
+
+impl DistFile {
+    /// Returns the path to the source file.
+    pub fn src(&self) -> &Utf8Path {
+        &self.src
+    }
+
+    /// Returns the destination path of the file.
+    ///
+    /// The path is relative to the directory the kind of the file is placed
+    /// in (e.g. the document directory for documents).
+    pub fn dest(&self) -> &Utf8Path {
+        &self.dest
+    }
+
+    /// Returns the Unix permission bits of the file.
+    pub fn mode(&self) -> u32 {
+        self.mode
+    }
+
+    /// Returns `true` if the file is installed relative to the root directory
+    /// instead of the installation prefix (e.g. `/usr`) of the system
+    /// packages.
+    ///
+    /// Extra files whose destination path is given with a leading `/` are
+    /// root-relative. [`dest`](Self::dest) returns the path without the
+    /// leading `/`.
+    pub fn is_root_relative(&self) -> bool {
+        self.root_relative
+    }
+
+    /// Returns `true` if the file is a configuration file, which is a
+    /// root-relative file under `etc/`.
+    ///
+    /// Configuration files modified by the user are preserved by the system
+    /// package managers on upgrade.
+    pub fn is_config(&self) -> bool {
+        self.root_relative && self.dest.starts_with("etc")
+    }
+}
+
+/// File or glob pattern given to the builder, expanded into [`DistFile`]s
+/// when the configuration is built.
+#[derive(Debug)]
+pub(super) enum DistFileSpec {
+    /// File copied to `dest`, or to its file name if `dest` is `None`.
+    File {
+        src: Utf8PathBuf,
+        dest: Option<Utf8PathBuf>,
+        mode: u32,
+    },
+    /// Files matching the pattern, copied into `dest_dir` preserving their
+    /// paths relative to the non-glob part of the pattern.
+    Glob {
+        pattern: String,
+        dest_dir: Utf8PathBuf,
+        mode: u32,
+    },
+}
+
+impl DistFileSpec {
+    /// Creates a spec of the file, resolving the relative path against `root`.
+    pub(super) fn file(
+        root: &Utf8Path,
+        src: impl Into<Utf8PathBuf>,
+        dest: Option<Utf8PathBuf>,
+        mode: u32,
+    ) -> Self {
+        Self::File {
+            src: root.join(src.into()),
+            dest,
+            mode,
+        }
+    }
+
+    /// Expands the spec into the files to be distributed.
+    ///
+    /// Relative glob patterns are resolved against `root`.
+    pub(super) fn expand(self, root: &Utf8Path) -> Result<Vec<DistFile>> {
+        let mut files = match self {
+            Self::File { src, dest, mode } => {
+                let dest = match dest {
+                    Some(dest) => dest,
+                    None => src
+                        .file_name()
+                        .ok_or_else(|| eyre!("file has no name: {}", src.to_relative()))?
+                        .into(),
+                };
+                vec![DistFile {
+                    src,
+                    dest,
+                    mode,
+                    root_relative: false,
+                }]
+            }
+            Self::Glob {
+                pattern,
+                dest_dir,
+                mode,
+            } => expand_glob(root, &pattern, &dest_dir, mode)?,
+        };
+        for file in &mut files {
+            if let Ok(dest) = file.dest.strip_prefix("/") {
+                file.dest = dest.to_owned();
+                file.root_relative = true;
+            }
+            ensure!(
+                file.dest
+                    .components()
+                    .all(|c| matches!(c, Utf8Component::Normal(_))),
+                "destination must be a relative path without `..`: {}",
+                file.dest
+            );
+        }
+        Ok(files)
+    }
+}
+
+/// Expands the specs into the files to be distributed.
+///
+/// Root-relative destination paths and files sharing the same destination
+/// path are not allowed.
+pub(super) fn expand_all(
+    root: &Utf8Path,
+    specs: impl IntoIterator<Item = DistFileSpec>,
+) -> Result<Vec<DistFile>> {
+    let mut files = vec![];
+    for spec in specs {
+        for file in spec.expand(root)? {
+            ensure!(
+                !file.root_relative,
+                "destination must be a relative path: /{}",
+                file.dest
+            );
+            files.push(file);
+        }
+    }
+    ensure_unique_dests(&files)?;
+    Ok(files)
+}
+
+/// Expands the specs of the extra files into the files to be distributed.
+///
+/// Destination paths under `etc/` must be root-relative, as they would be
+/// installed into `/usr/etc` of the system packages otherwise. Files sharing
+/// the same destination path are not allowed, regardless of whether they are
+/// root-relative, as they are placed in the same path of the archives.
+#[cfg(feature = "subcommand-dist-build-doc")]
+pub(super) fn expand_extra_files(
+    root: &Utf8Path,
+    specs: impl IntoIterator<Item = DistFileSpec>,
+) -> Result<Vec<DistFile>> {
+    let mut files = vec![];
+    for spec in specs {
+        for file in spec.expand(root)? {
+            ensure!(
+                file.root_relative || !file.dest.starts_with("etc"),
+                "extra file `{}` would be installed into `/usr/{}` of the system packages, use `/{}` as the destination to install it into `/etc`",
+                file.src.to_relative(),
+                file.dest,
+                file.dest
+            );
+            files.push(file);
+        }
+    }
+    ensure_unique_dests(&files)?;
+    Ok(files)
+}
+
+fn ensure_unique_dests(files: &[DistFile]) -> Result<()> {
+    let mut srcs = std::collections::HashMap::new();
+    for file in files {
+        if let Some(src) = srcs.insert(&file.dest, &file.src) {
+            bail!(
+                "`{}` and `{}` have the same destination path: {}",
+                src.to_relative(),
+                file.src.to_relative(),
+                file.dest
+            );
+        }
+    }
+    Ok(())
+}
+
+fn expand_glob(
+    root: &Utf8Path,
+    pattern: &str,
+    dest_dir: &Utf8Path,
+    mode: u32,
+) -> Result<Vec<DistFile>> {
+    use glob::{MatchOptions, Pattern};
+
+    // the leading components without glob metacharacters, which are stripped
+    // from the destination paths
+    let base = Utf8Path::new(pattern)
+        .components()
+        .take_while(|c| !c.as_str().contains(['*', '?', '[']))
+        .collect::<Utf8PathBuf>();
+    let base = root.join(base);
+
+    let full_pattern = if Utf8Path::new(pattern).is_absolute() {
+        pattern.to_owned()
+    } else {
+        format!("{}/{pattern}", Pattern::escape(root.as_str()))
+    };
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+
+    let mut files = vec![];
+    for path in glob::glob_with(&full_pattern, options)
+        .map_err(|e| eyre!("invalid glob pattern `{pattern}`: {e}"))?
+    {
+        let path = Utf8PathBuf::try_from(path?)?;
+        if !path.is_file() {
+            continue;
+        }
+        let rel_path = match path.strip_prefix(&base) {
+            Ok(rel_path) if !rel_path.as_str().is_empty() => rel_path.to_owned(),
+            // the pattern has no glob metacharacters
+            _ => path
+                .file_name()
+                .ok_or_else(|| eyre!("file has no name: {}", path.to_relative()))?
+                .into(),
+        };
+        let dest = dest_dir.join(rel_path);
+        files.push(DistFile {
+            src: path,
+            dest,
+            mode,
+            root_relative: false,
+        });
+    }
+    if files.is_empty() {
+        bail!("no files match the pattern: {pattern}");
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn root() -> (TempDir, Utf8PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8PathBuf::try_from(temp_dir.path().to_owned()).unwrap();
+        for path in ["README.md", "docs/a.md", "docs/guide/b.md", "docs/c.txt"] {
+            crate::fs::create_file(root.join(path)).unwrap();
+        }
+        (temp_dir, root)
+    }
+
+    fn dests(files: &[DistFile]) -> Vec<&str> {
+        files.iter().map(|file| file.dest().as_str()).collect()
+    }
+
+    #[test]
+    fn expand() {
+        let (_temp_dir, root) = root();
+        let cases = [
+            (
+                DistFileSpec::file(&root, "README.md", None, 0o644),
+                vec!["README.md"],
+            ),
+            (
+                DistFileSpec::file(&root, "docs/a.md", Some("doc/A.md".into()), 0o644),
+                vec!["doc/A.md"],
+            ),
+            (
+                DistFileSpec::Glob {
+                    pattern: "docs/**/*.md".to_owned(),
+                    dest_dir: "reference".into(),
+                    mode: 0o644,
+                },
+                vec!["reference/a.md", "reference/guide/b.md"],
+            ),
+            (
+                DistFileSpec::Glob {
+                    pattern: "docs/*.txt".to_owned(),
+                    dest_dir: "".into(),
+                    mode: 0o644,
+                },
+                vec!["c.txt"],
+            ),
+        ];
+        for (input, expected) in cases {
+            let message = format!("input: {input:?}");
+            assert_eq!(dests(&input.expand(&root).unwrap()), expected, "{message}");
+        }
+
+        let files = DistFileSpec::file(&root, "README.md", Some("/etc/app/app.md".into()), 0o600)
+            .expand(&root)
+            .unwrap();
+        assert_eq!(files[0].dest(), "etc/app/app.md");
+        assert_eq!(files[0].mode(), 0o600);
+        assert!(files[0].is_root_relative());
+        assert!(files[0].is_config());
+
+        for input in [
+            DistFileSpec::file(&root, "README.md", Some("../README.md".into()), 0o644),
+            DistFileSpec::Glob {
+                pattern: "docs/*.rs".to_owned(),
+                dest_dir: "".into(),
+                mode: 0o644,
+            },
+        ] {
+            let message = format!("input: {input:?}");
+            assert!(input.expand(&root).is_err(), "{message}");
+        }
+    }
+
+    #[test]
+    fn expand_documents() {
+        let (_temp_dir, root) = root();
+        let readme = || DistFileSpec::file(&root, "README.md", None, 0o644);
+
+        assert!(expand_all(&root, [readme()]).is_ok());
+        // root-relative
+        assert!(
+            expand_all(
+                &root,
+                [DistFileSpec::file(
+                    &root,
+                    "README.md",
+                    Some("/README.md".into()),
+                    0o644
+                )]
+            )
+            .is_err()
+        );
+        // same destination path
+        assert!(expand_all(&root, [readme(), readme()]).is_err());
+        assert!(
+            expand_all(
+                &root,
+                [
+                    DistFileSpec::file(&root, "docs/a.md", Some("README.md".into()), 0o644),
+                    readme(),
+                ]
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    fn expand_extras() {
+        let (_temp_dir, root) = root();
+        let extra = |dest: &str| DistFileSpec::file(&root, "README.md", Some(dest.into()), 0o644);
+
+        let files = expand_extra_files(
+            &root,
+            [extra("share/app/README.md"), extra("/etc/app/app.md")],
+        )
+        .unwrap();
+        assert_eq!(dests(&files), ["share/app/README.md", "etc/app/app.md"]);
+
+        // `etc/` without the leading `/`
+        assert!(expand_extra_files(&root, [extra("etc/app/app.md")]).is_err());
+        // same destination path, regardless of whether root-relative
+        assert!(expand_extra_files(&root, [extra("share/a.md"), extra("share/a.md")]).is_err());
+        assert!(expand_extra_files(&root, [extra("share/a.md"), extra("/share/a.md")]).is_err());
+    }
+}
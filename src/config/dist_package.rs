@@ -1,10 +1,16 @@
 #[cfg(any(
+    feature = "subcommand-dist-build-bin",
     feature = "subcommand-dist-build-license",
     feature = "subcommand-dist-build-doc"
 ))]
 use cargo_metadata::camino::Utf8PathBuf;
 use cargo_metadata::{Package, PackageName, camino::Utf8Path};
 
+#[cfg(any(
+    feature = "subcommand-dist-build-license",
+    feature = "subcommand-dist-build-doc"
+))]
+use super::dist_file::{self, DistFile, DistFileSpec};
 use super::{DistTargetConfig, DistTargetConfigBuilder};
 use crate::{Result, workspace::PackageExt};
 
@@ -65,10 +71,28 @@ pub struct DistPackageConfigBuilder<'a> {
     targets: Option<Vec<DistTargetConfig<'a>>>,
     #[cfg(feature = "subcommand-dist-build-bin")]
     cargo_build_options: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    cargo_profile: Option<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    c_header: Option<Utf8PathBuf>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    pkg_config: bool,
     #[cfg(feature = "subcommand-dist-build-license")]
-    license_files: Option<Vec<Utf8PathBuf>>,
+    license_files: Option<Vec<DistFileSpec>>,
     #[cfg(feature = "subcommand-dist-build-doc")]
-    documents: Option<Vec<Utf8PathBuf>>,
+    documents: Vec<DistFileSpec>,
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    extra_files: Vec<DistFileSpec>,
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    readme_absolute_links: bool,
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    readme_link_base: String,
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    readme_strip_sections: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    readme_strip_badges: bool,
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    readme_formats: Vec<ReadmeFormat>,
 }
 
 impl<'a> DistPackageConfigBuilder<'a> {
@@ -79,10 +103,28 @@ impl<'a> DistPackageConfigBuilder<'a> {
             targets: None,
             #[cfg(feature = "subcommand-dist-build-bin")]
             cargo_build_options: vec![],
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            cargo_profile: None,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            c_header: None,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            pkg_config: false,
             #[cfg(feature = "subcommand-dist-build-license")]
             license_files: None,
             #[cfg(feature = "subcommand-dist-build-doc")]
-            documents: None,
+            documents: vec![],
+            #[cfg(feature = "subcommand-dist-build-doc")]
+            extra_files: vec![],
+            #[cfg(feature = "subcommand-dist-build-readme")]
+            readme_absolute_links: false,
+            #[cfg(feature = "subcommand-dist-build-readme")]
+            readme_link_base: "{repository}/{kind}/v{version}".to_string(),
+            #[cfg(feature = "subcommand-dist-build-readme")]
+            readme_strip_sections: vec![],
+            #[cfg(feature = "subcommand-dist-build-readme")]
+            readme_strip_badges: false,
+            #[cfg(feature = "subcommand-dist-build-readme")]
+            readme_formats: vec![],
         }
     }
 
@@ -229,6 +271,8 @@ impl<'a> DistPackageConfigBuilder<'a> {
 
     /// Add a target of the package to the list of targets to be distributed.
     ///
+    /// A target with the same name already in the list is replaced.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -253,12 +297,18 @@ impl<'a> DistPackageConfigBuilder<'a> {
     /// # }
     /// ```
     pub fn target(mut self, target: DistTargetConfig<'a>) -> Self {
-        self.targets.get_or_insert(vec![]).push(target);
+        let targets = self.targets.get_or_insert(vec![]);
+        match targets.iter_mut().find(|t| t.name() == target.name()) {
+            Some(existing) => *existing = target,
+            None => targets.push(target),
+        }
         self
     }
 
     /// Add a targets of the package to the list of targets to be distributed.
     ///
+    /// Targets with the same name already in the list are replaced.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -285,7 +335,9 @@ impl<'a> DistPackageConfigBuilder<'a> {
     /// # }
     /// ```
     pub fn targets(mut self, targets: impl IntoIterator<Item = DistTargetConfig<'a>>) -> Self {
-        self.targets.get_or_insert(vec![]).extend(targets);
+        for target in targets {
+            self = self.target(target);
+        }
         self
     }
 
@@ -315,6 +367,95 @@ impl<'a> DistPackageConfigBuilder<'a> {
         self
     }
 
+    /// Sets the cargo profile used to build the package, overriding the one
+    /// given by
+    /// [`DistConfigBuilder::cargo_profile`](super::DistConfigBuilder::cargo_profile).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config.cargo_profile("dist").build()?;
+    /// assert_eq!(pkg_config.cargo_profile(), Some("dist"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn cargo_profile(mut self, profile: impl Into<String>) -> Self {
+        self.cargo_profile = Some(profile.into());
+        self
+    }
+
+    /// Sets the C header file to be distributed with the library targets of
+    /// the package.
+    ///
+    /// The header file (e.g. generated by [`cbindgen`]) is placed in
+    /// `include/` next to the library files placed in `lib/`.
+    /// If the given path is a relative path, it is resolved against the package
+    /// root directory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config.c_header("include/app.h").build()?;
+    /// assert!(pkg_config.c_header().unwrap().ends_with("include/app.h"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`cbindgen`]: https://github.com/mozilla/cbindgen
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn c_header(mut self, file: impl Into<Utf8PathBuf>) -> Self {
+        let file = file.into();
+        self.c_header = Some(if file.is_relative() {
+            self.metadata.root_directory().join(file)
+        } else {
+            file
+        });
+        self
+    }
+
+    /// Sets whether to generate a pkg-config file for the library targets of
+    /// the package.
+    ///
+    /// The pkg-config file `lib/pkgconfig/<package>.pc` is generated from the
+    /// package metadata, with paths relative to the location of the file.
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config.pkg_config(true).build()?;
+    /// assert!(pkg_config.pkg_config());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn pkg_config(mut self, generate: bool) -> Self {
+        self.pkg_config = generate;
+        self
+    }
+
     /// Adds a package license files to the list of files to be distributed.
     ///
     /// If the given path is a relative path, it is resolved against the package
@@ -341,17 +482,101 @@ impl<'a> DistPackageConfigBuilder<'a> {
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-license")))]
     pub fn license_files(mut self, files: impl IntoIterator<Item = Utf8PathBuf>) -> Self {
         let package_root = self.metadata.root_directory();
-        let files = files.into_iter().map(|file| {
-            if file.is_relative() {
-                package_root.join(file)
-            } else {
-                file
-            }
-        });
-        match &mut self.license_files {
-            Some(fs) => fs.extend(files),
-            lf @ None => *lf = Some(files.collect()),
-        }
+        let files = files
+            .into_iter()
+            .map(|file| DistFileSpec::file(package_root, file, None, FILE_MODE));
+        self.license_files.get_or_insert_default().extend(files);
+        self
+    }
+
+    /// Adds a package license file to the list of files to be distributed,
+    /// placed at the given destination path.
+    ///
+    /// If the given source path is a relative path, it is resolved against the
+    /// package root directory. The destination path is relative to the
+    /// directory the license files are placed in.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config
+    ///     .license_file_as("LICENSE-MIT", "licenses/MIT.txt")
+    ///     .build()?;
+    /// let file = &pkg_config.license_dist_files()[0];
+    /// assert!(file.src().ends_with("LICENSE-MIT"));
+    /// assert_eq!(file.dest(), "licenses/MIT.txt");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-license")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-license")))]
+    pub fn license_file_as(
+        mut self,
+        src: impl Into<Utf8PathBuf>,
+        dest: impl Into<Utf8PathBuf>,
+    ) -> Self {
+        let spec = DistFileSpec::file(
+            self.metadata.root_directory(),
+            src,
+            Some(dest.into()),
+            FILE_MODE,
+        );
+        self.license_files.get_or_insert_default().push(spec);
+        self
+    }
+
+    /// Adds the package license files matching the glob pattern to the list of
+    /// files to be distributed.
+    ///
+    /// If the given pattern is a relative path, it is resolved against the
+    /// package root directory. The matched files are placed in `dest_dir`
+    /// (relative to the directory the license files are placed in), preserving
+    /// their paths relative to the leading components of the pattern without
+    /// glob metacharacters. `*` does not match `/`, while `**` matches any
+    /// number of directories.
+    ///
+    /// # Errors
+    ///
+    /// [`build`](Self::build) returns an error if no files match the pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config.license_files_glob("LICENSE-*", "").build()?;
+    /// let dests = pkg_config
+    ///     .license_dist_files()
+    ///     .iter()
+    ///     .map(|file| file.dest().as_str())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(dests, ["LICENSE-APACHE", "LICENSE-MIT"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-license")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-license")))]
+    pub fn license_files_glob(
+        mut self,
+        pattern: impl Into<String>,
+        dest_dir: impl Into<Utf8PathBuf>,
+    ) -> Self {
+        let spec = DistFileSpec::Glob {
+            pattern: pattern.into(),
+            dest_dir: dest_dir.into(),
+            mode: FILE_MODE,
+        };
+        self.license_files.get_or_insert_default().push(spec);
         self
     }
 
@@ -378,17 +603,380 @@ impl<'a> DistPackageConfigBuilder<'a> {
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-doc")))]
     pub fn documents(mut self, files: impl IntoIterator<Item = Utf8PathBuf>) -> Self {
         let package_root = self.metadata.root_directory();
-        let files = files.into_iter().map(|file| {
-            if file.is_relative() {
-                package_root.join(file)
-            } else {
-                file
-            }
+        let files = files
+            .into_iter()
+            .map(|file| DistFileSpec::file(package_root, file, None, FILE_MODE));
+        self.documents.extend(files);
+        self
+    }
+
+    /// Adds a package documentation file to the list of files to be
+    /// distributed, placed at the given destination path.
+    ///
+    /// If the given source path is a relative path, it is resolved against the
+    /// package root directory. The destination path is relative to the
+    /// document directory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config
+    ///     .document_as("CHANGELOG.md", "NEWS.md")
+    ///     .build()?;
+    /// let file = &pkg_config.document_dist_files()[0];
+    /// assert!(file.src().ends_with("CHANGELOG.md"));
+    /// assert_eq!(file.dest(), "NEWS.md");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-doc")))]
+    pub fn document_as(
+        mut self,
+        src: impl Into<Utf8PathBuf>,
+        dest: impl Into<Utf8PathBuf>,
+    ) -> Self {
+        let spec = DistFileSpec::file(
+            self.metadata.root_directory(),
+            src,
+            Some(dest.into()),
+            FILE_MODE,
+        );
+        self.documents.push(spec);
+        self
+    }
+
+    /// Adds the package documentation files matching the glob pattern to the
+    /// list of files to be distributed.
+    ///
+    /// If the given pattern is a relative path, it is resolved against the
+    /// package root directory. The matched files are placed in `dest_dir`
+    /// (relative to the document directory), preserving their paths relative
+    /// to the leading components of the pattern without glob metacharacters.
+    /// `*` does not match `/`, while `**` matches any number of directories.
+    ///
+    /// # Errors
+    ///
+    /// [`build`](Self::build) returns an error if no files match the pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config
+    ///     .documents_glob("doc/cargo-xtask-dist*.md", "reference")
+    ///     .build()?;
+    /// assert!(
+    ///     pkg_config
+    ///         .document_dist_files()
+    ///         .iter()
+    ///         .any(|file| file.dest() == "reference/cargo-xtask-dist-build.md")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-doc")))]
+    pub fn documents_glob(
+        mut self,
+        pattern: impl Into<String>,
+        dest_dir: impl Into<Utf8PathBuf>,
+    ) -> Self {
+        self.documents.push(DistFileSpec::Glob {
+            pattern: pattern.into(),
+            dest_dir: dest_dir.into(),
+            mode: FILE_MODE,
         });
-        match &mut self.documents {
-            Some(ds) => ds.extend(files),
-            ds @ None => *ds = Some(files.collect()),
-        }
+        self
+    }
+
+    /// Adds an extra file to be distributed, placed at the given destination
+    /// path with the given Unix permission bits.
+    ///
+    /// If the given source path is a relative path, it is resolved against the
+    /// package root directory. The destination path is relative to the
+    /// `extra/<package>/` directory of the archives, and to the installation
+    /// prefix (e.g. `/usr`) of the system packages.
+    ///
+    /// A destination path with a leading `/` (e.g. `/etc/app/app.toml`) is
+    /// relative to the root directory of the system packages instead, and
+    /// placed without the leading `/` in the archives. Root-relative files
+    /// under `/etc` are marked as configuration files of the system packages.
+    /// Destination paths under `etc/` without the leading `/` are rejected.
+    ///
+    /// # Errors
+    ///
+    /// [`build`](Self::build) returns an error if multiple extra files of the
+    /// package have the same destination path.
+    ///
+    /// Reproducible archives only record whether the files are executable
+    /// (`0o755`) or not (`0o644`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config
+    ///     .extra_file("Cargo.toml", "share/app/sample.toml", 0o644)
+    ///     .extra_file("Cargo.toml", "/etc/app/app.toml", 0o600)
+    ///     .build()?;
+    /// let file = &pkg_config.extra_files()[0];
+    /// assert_eq!(file.dest(), "share/app/sample.toml");
+    /// assert_eq!(file.mode(), 0o644);
+    /// assert!(!file.is_root_relative());
+    /// let file = &pkg_config.extra_files()[1];
+    /// assert_eq!(file.dest(), "etc/app/app.toml");
+    /// assert!(file.is_root_relative());
+    /// assert!(file.is_config());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-doc")))]
+    pub fn extra_file(
+        mut self,
+        src: impl Into<Utf8PathBuf>,
+        dest: impl Into<Utf8PathBuf>,
+        mode: u32,
+    ) -> Self {
+        let spec = DistFileSpec::file(self.metadata.root_directory(), src, Some(dest.into()), mode);
+        self.extra_files.push(spec);
+        self
+    }
+
+    /// Adds the extra files matching the glob pattern to be distributed, with
+    /// the given Unix permission bits.
+    ///
+    /// The pattern and `dest_dir` are handled as in
+    /// [`documents_glob`](Self::documents_glob), and the destination paths as
+    /// in [`extra_file`](Self::extra_file).
+    ///
+    /// # Errors
+    ///
+    /// [`build`](Self::build) returns an error if no files match the pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config
+    ///     .extra_files_glob("examples/*/Cargo.toml", "share/app/examples", 0o644)
+    ///     .build()?;
+    /// assert!(
+    ///     pkg_config
+    ///         .extra_files()
+    ///         .iter()
+    ///         .any(|file| file.dest() == "share/app/examples/bin-main-with-command/Cargo.toml")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-doc")))]
+    pub fn extra_files_glob(
+        mut self,
+        pattern: impl Into<String>,
+        dest_dir: impl Into<Utf8PathBuf>,
+        mode: u32,
+    ) -> Self {
+        self.extra_files.push(DistFileSpec::Glob {
+            pattern: pattern.into(),
+            dest_dir: dest_dir.into(),
+            mode,
+        });
+        self
+    }
+
+    /// Sets whether to rewrite the relative links and images in the README
+    /// file of the package to absolute URLs.
+    ///
+    /// Relative links are broken once the README file is distributed outside
+    /// of the repository. The rewritten URLs are built from
+    /// [`readme_link_base`](Self::readme_link_base) and the path of the
+    /// linked file relative to the workspace root, which is assumed to be the
+    /// root of the repository.
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config.readme_absolute_links(true).build()?;
+    /// assert!(pkg_config.readme_absolute_links());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+    pub fn readme_absolute_links(mut self, rewrite: bool) -> Self {
+        self.readme_absolute_links = rewrite;
+        self
+    }
+
+    /// Sets the template of the base URL the relative links in the README file
+    /// are rewritten against.
+    ///
+    /// The following placeholders are replaced:
+    ///
+    /// * `{repository}` - the `repository` of the package, without the
+    ///   trailing `/` or `.git`
+    /// * `{package}` - the name of the package
+    /// * `{version}` - the version of the package
+    /// * `{kind}` - `raw` for images, `blob` for the other links
+    ///
+    /// Defaults to `{repository}/{kind}/v{version}`, which points to the files
+    /// at the release tag on GitHub.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    /// let version = &workspace.root_package().unwrap().version;
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config
+    ///     .readme_link_base("{repository}/-/{kind}/{package}-{version}")
+    ///     .build()?;
+    /// assert_eq!(
+    ///     pkg_config.readme_link_base_url(true)?,
+    ///     format!("https://github.com/gifnksm/cli-xtask/-/raw/cli-xtask-{version}")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+    pub fn readme_link_base(mut self, template: impl Into<String>) -> Self {
+        self.readme_link_base = template.into();
+        self
+    }
+
+    /// Adds the titles of the sections to be removed from the README file of
+    /// the package.
+    ///
+    /// A section consists of a heading whose text matches one of the titles
+    /// (ignoring ASCII case) and the contents up to the next heading of the
+    /// same or a higher level.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config
+    ///     .readme_strip_sections(["Installation", "Contribution"])
+    ///     .build()?;
+    /// assert_eq!(
+    ///     pkg_config.readme_strip_sections(),
+    ///     ["Installation", "Contribution"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+    pub fn readme_strip_sections(
+        mut self,
+        titles: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.readme_strip_sections
+            .extend(titles.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets whether to remove the badges from the README file of the package.
+    ///
+    /// Paragraphs consisting only of images, optionally wrapped in links, are
+    /// removed.
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config.readme_strip_badges(true).build()?;
+    /// assert!(pkg_config.readme_strip_badges());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+    pub fn readme_strip_badges(mut self, strip: bool) -> Self {
+        self.readme_strip_badges = strip;
+        self
+    }
+
+    /// Sets the formats the README file of the package is rendered into, in
+    /// addition to the markdown file.
+    ///
+    /// The rendered files are placed next to the markdown file, with the
+    /// extension replaced (e.g. `README.txt` and `README.html`).
+    /// This replaces the formats set previously. Defaults to no formats.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{
+    ///     config::{DistConfigBuilder, ReadmeFormat},
+    ///     workspace,
+    /// };
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config
+    ///     .readme_formats([ReadmeFormat::PlainText, ReadmeFormat::Html])
+    ///     .build()?;
+    /// assert_eq!(
+    ///     pkg_config.readme_formats(),
+    ///     [ReadmeFormat::PlainText, ReadmeFormat::Html]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+    pub fn readme_formats(mut self, formats: impl IntoIterator<Item = ReadmeFormat>) -> Self {
+        self.readme_formats = formats.into_iter().collect();
         self
     }
 
@@ -405,16 +993,48 @@ impl<'a> DistPackageConfigBuilder<'a> {
                 .map(DistTargetConfigBuilder::build)
                 .collect::<Result<Vec<_>>>()?,
         };
+        #[cfg(feature = "subcommand-dist-build-license")]
+        let license_files = collect_license_files(self.metadata, self.license_files)?;
+        #[cfg(feature = "subcommand-dist-build-doc")]
+        let documents = dist_file::expand_all(self.metadata.root_directory(), self.documents)?;
         Ok(DistPackageConfig {
             name: self.name,
             metadata: self.metadata,
             targets,
             #[cfg(feature = "subcommand-dist-build-bin")]
             cargo_build_options: self.cargo_build_options,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            cargo_profile: self.cargo_profile,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            c_header: self.c_header,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            pkg_config: self.pkg_config,
             #[cfg(feature = "subcommand-dist-build-license")]
-            license_files: collect_license_files(self.metadata, self.license_files)?,
+            license_file_paths: license_files
+                .iter()
+                .map(|file| file.src().to_owned())
+                .collect(),
+            #[cfg(feature = "subcommand-dist-build-license")]
+            license_files,
+            #[cfg(feature = "subcommand-dist-build-doc")]
+            document_paths: documents.iter().map(|file| file.src().to_owned()).collect(),
+            #[cfg(feature = "subcommand-dist-build-doc")]
+            documents,
             #[cfg(feature = "subcommand-dist-build-doc")]
-            documents: self.documents.unwrap_or_default(),
+            extra_files: dist_file::expand_extra_files(
+                self.metadata.root_directory(),
+                self.extra_files,
+            )?,
+            #[cfg(feature = "subcommand-dist-build-readme")]
+            readme_absolute_links: self.readme_absolute_links,
+            #[cfg(feature = "subcommand-dist-build-readme")]
+            readme_link_base: self.readme_link_base,
+            #[cfg(feature = "subcommand-dist-build-readme")]
+            readme_strip_sections: self.readme_strip_sections,
+            #[cfg(feature = "subcommand-dist-build-readme")]
+            readme_strip_badges: self.readme_strip_badges,
+            #[cfg(feature = "subcommand-dist-build-readme")]
+            readme_formats: self.readme_formats,
         })
     }
 }
@@ -427,10 +1047,32 @@ pub struct DistPackageConfig<'a> {
     targets: Vec<DistTargetConfig<'a>>,
     #[cfg(feature = "subcommand-dist-build-bin")]
     cargo_build_options: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    cargo_profile: Option<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    c_header: Option<Utf8PathBuf>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    pkg_config: bool,
     #[cfg(feature = "subcommand-dist-build-license")]
-    license_files: Vec<Utf8PathBuf>,
+    license_file_paths: Vec<Utf8PathBuf>,
+    #[cfg(feature = "subcommand-dist-build-license")]
+    license_files: Vec<DistFile>,
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    document_paths: Vec<Utf8PathBuf>,
     #[cfg(feature = "subcommand-dist-build-doc")]
-    documents: Vec<Utf8PathBuf>,
+    documents: Vec<DistFile>,
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    extra_files: Vec<DistFile>,
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    readme_absolute_links: bool,
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    readme_link_base: String,
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    readme_strip_sections: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    readme_strip_badges: bool,
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    readme_formats: Vec<ReadmeFormat>,
 }
 
 impl<'a> DistPackageConfig<'a> {
@@ -467,6 +1109,29 @@ impl<'a> DistPackageConfig<'a> {
         &self.cargo_build_options
     }
 
+    /// Returns the cargo profile used to build the package, if it overrides
+    /// the one of the distribution.
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn cargo_profile(&self) -> Option<&str> {
+        self.cargo_profile.as_deref()
+    }
+
+    /// Returns the C header file to be distributed with the library targets.
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn c_header(&self) -> Option<&Utf8Path> {
+        self.c_header.as_deref()
+    }
+
+    /// Returns `true` if a pkg-config file is generated for the library
+    /// targets.
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
+    pub fn pkg_config(&self) -> bool {
+        self.pkg_config
+    }
+
     /// Returns the list of license files to be distributed.
     ///
     /// License files can be added by
@@ -479,9 +1144,22 @@ impl<'a> DistPackageConfig<'a> {
     /// If no license files are added and the `license-file` field is not
     /// present, the file matches the pattern `/^LICENSE(?:-|_|\.|$)/i` in the
     /// root directory of the package.
+    ///
+    /// See [`license_dist_files`](Self::license_dist_files) for the
+    /// destination paths of the files.
     #[cfg(feature = "subcommand-dist-build-license")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-license")))]
     pub fn license_files(&self) -> &[Utf8PathBuf] {
+        &self.license_file_paths
+    }
+
+    /// Returns the license files to be distributed, with their destination
+    /// paths and Unix permission bits.
+    ///
+    /// The files are the same as [`license_files`](Self::license_files).
+    #[cfg(feature = "subcommand-dist-build-license")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-license")))]
+    pub fn license_dist_files(&self) -> &[DistFile] {
         &self.license_files
     }
 
@@ -490,13 +1168,168 @@ impl<'a> DistPackageConfig<'a> {
     /// Documentation files can be added by
     /// [`DistPackageConfigBuilder::documents`] function.
     /// If no documentation files are added, this function returns empty list.
+    ///
+    /// See [`document_dist_files`](Self::document_dist_files) for the
+    /// destination paths of the files.
     #[cfg(feature = "subcommand-dist-build-doc")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-doc")))]
     pub fn documents(&self) -> &[Utf8PathBuf] {
+        &self.document_paths
+    }
+
+    /// Returns the documentation files to be distributed, with their
+    /// destination paths and Unix permission bits.
+    ///
+    /// The files are the same as [`documents`](Self::documents).
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-doc")))]
+    pub fn document_dist_files(&self) -> &[DistFile] {
         &self.documents
     }
+
+    /// Returns the list of extra files to be distributed.
+    ///
+    /// Extra files can be added by [`DistPackageConfigBuilder::extra_file`]
+    /// function.
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-doc")))]
+    pub fn extra_files(&self) -> &[DistFile] {
+        &self.extra_files
+    }
+
+    /// Returns `true` if the relative links in the README file are rewritten
+    /// to absolute URLs.
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+    pub fn readme_absolute_links(&self) -> bool {
+        self.readme_absolute_links
+    }
+
+    /// Returns the template of the base URL the relative links in the README
+    /// file are rewritten against.
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+    pub fn readme_link_base(&self) -> &str {
+        &self.readme_link_base
+    }
+
+    /// Returns the base URL the relative links (or images, if `image` is
+    /// `true`) in the README file are rewritten against.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the template contains an unknown placeholder, or
+    /// if `{repository}` is used and the `repository` of the package is not
+    /// set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    /// let version = &workspace.root_package().unwrap().version;
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace)?;
+    /// let pkg_config = pkg_config.build()?;
+    /// assert_eq!(
+    ///     pkg_config.readme_link_base_url(false)?,
+    ///     format!("https://github.com/gifnksm/cli-xtask/blob/v{version}")
+    /// );
+    /// assert_eq!(
+    ///     pkg_config.readme_link_base_url(true)?,
+    ///     format!("https://github.com/gifnksm/cli-xtask/raw/v{version}")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+    pub fn readme_link_base_url(&self, image: bool) -> Result<String> {
+        let template = &self.readme_link_base;
+        let repository = self.metadata.repository.as_deref();
+
+        let mut missing_repository = false;
+        let res = super::dist::render_template(template, |key| {
+            let value = match key {
+                "repository" => {
+                    let Some(repository) = repository else {
+                        missing_repository = true;
+                        return Some(String::new());
+                    };
+                    let repository = repository.trim_end_matches('/');
+                    let repository = repository.strip_suffix(".git").unwrap_or(repository);
+                    repository.to_owned()
+                }
+                "package" => self.name.to_string(),
+                "version" => self.metadata.version.to_string(),
+                "kind" => if image { "raw" } else { "blob" }.to_owned(),
+                _ => return None,
+            };
+            Some(value)
+        })?;
+        if missing_repository {
+            eyre::bail!(
+                "`repository` of the package is not set, required by `{{repository}}` in template: {template}"
+            );
+        }
+        Ok(res)
+    }
+
+    /// Returns the titles of the sections removed from the README file.
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+    pub fn readme_strip_sections(&self) -> &[String] {
+        &self.readme_strip_sections
+    }
+
+    /// Returns `true` if the badges are removed from the README file.
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+    pub fn readme_strip_badges(&self) -> bool {
+        self.readme_strip_badges
+    }
+
+    /// Returns the formats the README file is rendered into, in addition to
+    /// the markdown file.
+    #[cfg(feature = "subcommand-dist-build-readme")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+    pub fn readme_formats(&self) -> &[ReadmeFormat] {
+        &self.readme_formats
+    }
+}
+
+/// Format the README files are rendered into.
+#[cfg(feature = "subcommand-dist-build-readme")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ReadmeFormat {
+    /// Plain text, written to the file with the `txt` extension.
+    PlainText,
+    /// HTML document, written to the file with the `html` extension.
+    Html,
+}
+
+#[cfg(feature = "subcommand-dist-build-readme")]
+impl ReadmeFormat {
+    /// Returns the extension of the files rendered in the format.
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            Self::PlainText => "txt",
+            Self::Html => "html",
+        }
+    }
 }
 
+/// Unix permission bits of the documentation and license files.
+#[cfg(any(
+    feature = "subcommand-dist-build-license",
+    feature = "subcommand-dist-build-doc"
+))]
+const FILE_MODE: u32 = 0o644;
+
 fn collect_targets<'a>(package: &'a Package, kind: &str) -> Vec<DistTargetConfigBuilder<'a>> {
     package
         .targets
@@ -509,19 +1342,20 @@ fn collect_targets<'a>(package: &'a Package, kind: &str) -> Vec<DistTargetConfig
 #[cfg(feature = "subcommand-dist-build-license")]
 fn collect_license_files(
     package: &Package,
-    files: Option<Vec<Utf8PathBuf>>,
-) -> Result<Vec<Utf8PathBuf>> {
+    files: Option<Vec<DistFileSpec>>,
+) -> Result<Vec<DistFile>> {
     use std::sync::LazyLock;
 
     use regex::{Regex, RegexBuilder};
     let src_dir = package.root_directory();
 
     if let Some(files) = files {
-        return Ok(files);
+        return dist_file::expand_all(src_dir, files);
     }
 
     if let Some(license_file) = &package.license_file {
-        return Ok(vec![src_dir.join(license_file)]);
+        let spec = DistFileSpec::file(src_dir, license_file, None, FILE_MODE);
+        return spec.expand(src_dir);
     }
 
     let mut files = vec![];
@@ -546,8 +1380,8 @@ fn collect_license_files(
         if !RE.is_match(src_name) {
             continue;
         }
-        files.push(src_file.to_owned());
+        files.push(DistFileSpec::file(src_dir, src_file, None, FILE_MODE));
     }
 
-    Ok(files)
+    dist_file::expand_all(src_dir, files)
 }
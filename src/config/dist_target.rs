@@ -14,6 +14,14 @@ pub struct DistTargetConfigBuilder<'a> {
     command: Option<clap::Command>,
     #[cfg(feature = "subcommand-dist-build-bin")]
     cargo_build_options: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-man")]
+    man_section: String,
+    #[cfg(feature = "subcommand-dist-build-man")]
+    man_manual: Option<String>,
+    #[cfg(feature = "subcommand-dist-build-man")]
+    man_date: ManDate,
+    #[cfg(feature = "subcommand-dist-build-man")]
+    man_extra_sections: Vec<(String, String)>,
 }
 
 impl<'a> DistTargetConfigBuilder<'a> {
@@ -24,6 +32,14 @@ impl<'a> DistTargetConfigBuilder<'a> {
             command: None,
             #[cfg(feature = "subcommand-dist-build-bin")]
             cargo_build_options: vec![],
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_section: "1".to_string(),
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_manual: None,
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_date: ManDate::default(),
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_extra_sections: vec![],
         }
     }
 
@@ -39,6 +55,14 @@ impl<'a> DistTargetConfigBuilder<'a> {
             command: None,
             #[cfg(feature = "subcommand-dist-build-bin")]
             cargo_build_options: vec![],
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_section: "1".to_string(),
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_manual: None,
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_date: ManDate::default(),
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_extra_sections: vec![],
         })
     }
 
@@ -60,6 +84,111 @@ impl<'a> DistTargetConfigBuilder<'a> {
         self
     }
 
+    /// Sets the section of the man pages of the target.
+    ///
+    /// Defaults to `1` (user commands).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_package_name(workspace, "xtask")?;
+    /// let target = pkg_config.binary_by_name("xtask")?.man_section("8").build()?;
+    /// assert_eq!(target.man_section(), "8");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-man")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-man")))]
+    pub fn man_section(mut self, section: impl Into<String>) -> Self {
+        self.man_section = section.into();
+        self
+    }
+
+    /// Sets the title of the manual the man pages of the target belong to.
+    ///
+    /// Defaults to `<Package> Command Manual`, where `<Package>` is the
+    /// capitalized package name.
+    #[cfg(feature = "subcommand-dist-build-man")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-man")))]
+    pub fn man_manual(mut self, manual: impl Into<String>) -> Self {
+        self.man_manual = Some(manual.into());
+        self
+    }
+
+    /// Sets the source of the date recorded in the man pages of the target.
+    ///
+    /// Defaults to [`ManDate::Today`].
+    /// Use [`ManDate::CommitDate`] or [`ManDate::SourceDateEpoch`] to generate
+    /// reproducible man pages.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{
+    ///     config::{DistConfigBuilder, ManDate},
+    ///     workspace,
+    /// };
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_package_name(workspace, "xtask")?;
+    /// let target = pkg_config
+    ///     .binary_by_name("xtask")?
+    ///     .man_date(ManDate::Fixed("2024-01-01".to_string()))
+    ///     .build()?;
+    /// assert_eq!(target.man_date(), &ManDate::Fixed("2024-01-01".to_string()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-man")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-man")))]
+    pub fn man_date(mut self, date: ManDate) -> Self {
+        self.man_date = date;
+        self
+    }
+
+    /// Adds a section appended to the man page of the target.
+    ///
+    /// `content` is written as is, so it must be written in roff (e.g.
+    /// `.TP`, `.B`). The section is added only to the man page of the target
+    /// command, not to the ones of its subcommands.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> cli_xtask::Result<()> {
+    /// use cli_xtask::{config::DistConfigBuilder, workspace};
+    ///
+    /// let workspace = workspace::current();
+    ///
+    /// let (dist_config, pkg_config) = DistConfigBuilder::from_package_name(workspace, "xtask")?;
+    /// let target = pkg_config
+    ///     .binary_by_name("xtask")?
+    ///     .man_extra_section("ENVIRONMENT", ".TP\n.B RUST_LOG\nLog level filter.")
+    ///     .man_extra_section("SEE ALSO", ".BR cargo (1)")
+    ///     .build()?;
+    /// assert_eq!(target.man_extra_sections().len(), 2);
+    /// assert_eq!(target.man_extra_sections()[1].0, "SEE ALSO");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "subcommand-dist-build-man")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-man")))]
+    pub fn man_extra_section(
+        mut self,
+        title: impl Into<String>,
+        content: impl Into<String>,
+    ) -> Self {
+        self.man_extra_sections.push((title.into(), content.into()));
+        self
+    }
+
     /// Builds a [`DistTargetConfig`] from the current configuration.
     ///
     /// # Errors
@@ -72,6 +201,14 @@ impl<'a> DistTargetConfigBuilder<'a> {
             command: self.command,
             #[cfg(feature = "subcommand-dist-build-bin")]
             cargo_build_options: self.cargo_build_options,
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_section: self.man_section,
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_manual: self.man_manual,
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_date: self.man_date,
+            #[cfg(feature = "subcommand-dist-build-man")]
+            man_extra_sections: self.man_extra_sections,
         })
     }
 }
@@ -84,6 +221,14 @@ pub struct DistTargetConfig<'a> {
     command: Option<clap::Command>,
     #[cfg(feature = "subcommand-dist-build-bin")]
     cargo_build_options: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-man")]
+    man_section: String,
+    #[cfg(feature = "subcommand-dist-build-man")]
+    man_manual: Option<String>,
+    #[cfg(feature = "subcommand-dist-build-man")]
+    man_date: ManDate,
+    #[cfg(feature = "subcommand-dist-build-man")]
+    man_extra_sections: Vec<(String, String)>,
 }
 
 impl DistTargetConfig<'_> {
@@ -108,4 +253,52 @@ impl DistTargetConfig<'_> {
     pub fn cargo_build_options(&self) -> &[String] {
         &self.cargo_build_options
     }
+
+    /// Returns the section of the man pages of the target.
+    #[cfg(feature = "subcommand-dist-build-man")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-man")))]
+    pub fn man_section(&self) -> &str {
+        &self.man_section
+    }
+
+    /// Returns the title of the manual the man pages of the target belong to,
+    /// if configured.
+    #[cfg(feature = "subcommand-dist-build-man")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-man")))]
+    pub fn man_manual(&self) -> Option<&str> {
+        self.man_manual.as_deref()
+    }
+
+    /// Returns the source of the date recorded in the man pages of the target.
+    #[cfg(feature = "subcommand-dist-build-man")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-man")))]
+    pub fn man_date(&self) -> &ManDate {
+        &self.man_date
+    }
+
+    /// Returns the sections appended to the man page of the target, as pairs
+    /// of the section title and its roff content.
+    #[cfg(feature = "subcommand-dist-build-man")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-man")))]
+    pub fn man_extra_sections(&self) -> &[(String, String)] {
+        &self.man_extra_sections
+    }
+}
+
+/// Source of the date recorded in the man pages.
+#[cfg(feature = "subcommand-dist-build-man")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-man")))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ManDate {
+    /// The date the man pages are generated.
+    #[default]
+    Today,
+    /// The committer date of the `HEAD` commit of the workspace repository.
+    CommitDate,
+    /// The date of the `SOURCE_DATE_EPOCH` environment variable, or the date
+    /// the man pages are generated if it is not set.
+    SourceDateEpoch,
+    /// The given date, written as is.
+    Fixed(String),
 }
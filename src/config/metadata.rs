@@ -0,0 +1,578 @@
+//! Configuration read from the `[workspace.metadata.cli-xtask]` and
+//! `[package.metadata.cli-xtask]` tables of the manifests.
+
+use std::collections::BTreeMap;
+
+use cargo_metadata::{Metadata, Package};
+use eyre::eyre;
+use serde::{Deserialize, de::DeserializeOwned};
+
+use super::{DistConfigBuilder, DistPackageConfigBuilder};
+use crate::Result;
+
+/// `[workspace.metadata.cli-xtask.dist]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct DistMetadata {
+    name: Option<String>,
+    packages: Option<Vec<String>>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[serde(default)]
+    cargo_build_options: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    cargo_profile: Option<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[serde(default)]
+    target_triples: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    strip_executables: Option<bool>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    split_debuginfo: Option<bool>,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_format: Option<String>,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_compressions: Option<Vec<String>>,
+    #[cfg(feature = "subcommand-dist-archive")]
+    reproducible_archive: Option<bool>,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_prefix: Option<String>,
+    #[cfg(feature = "subcommand-dist-archive")]
+    archive_name: Option<String>,
+    #[cfg(feature = "subcommand-dist-build-recipes")]
+    download_url: Option<String>,
+    #[cfg(feature = "subcommand-dist-build-license")]
+    third_party_licenses: Option<bool>,
+    /// Keys not known, or used by the disabled features
+    #[serde(flatten)]
+    unknown: BTreeMap<String, serde_json::Value>,
+}
+
+/// `[package.metadata.cli-xtask.dist]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct DistPackageMetadata {
+    binaries: Option<Vec<String>>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    #[serde(default)]
+    cargo_build_options: Vec<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    cargo_profile: Option<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    c_header: Option<String>,
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    pkg_config: Option<bool>,
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    #[serde(default)]
+    documents: Vec<FileEntry>,
+    #[cfg(feature = "subcommand-dist-build-license")]
+    license_files: Option<Vec<FileEntry>>,
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    #[serde(default)]
+    extra_files: Vec<ExtraFileEntry>,
+    /// Keys not known, or used by the disabled features
+    #[serde(flatten)]
+    unknown: BTreeMap<String, serde_json::Value>,
+}
+
+/// Document or license file, given as a path or a glob pattern with an
+/// optional destination.
+#[cfg(any(
+    feature = "subcommand-dist-build-doc",
+    feature = "subcommand-dist-build-license"
+))]
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum FileEntry {
+    Src(String),
+    Mapping(FileMapping),
+}
+
+#[cfg(any(
+    feature = "subcommand-dist-build-doc",
+    feature = "subcommand-dist-build-license"
+))]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct FileMapping {
+    src: String,
+    dest: Option<String>,
+}
+
+#[cfg(any(
+    feature = "subcommand-dist-build-doc",
+    feature = "subcommand-dist-build-license"
+))]
+impl FileEntry {
+    fn into_parts(self) -> (String, Option<String>) {
+        match self {
+            Self::Src(src) => (src, None),
+            Self::Mapping(FileMapping { src, dest }) => (src, dest),
+        }
+    }
+}
+
+/// Extra file, given as a path or a glob pattern with its destination.
+#[cfg(feature = "subcommand-dist-build-doc")]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct ExtraFileEntry {
+    src: String,
+    dest: String,
+    #[serde(default = "default_mode")]
+    mode: u32,
+}
+
+#[cfg(feature = "subcommand-dist-build-doc")]
+fn default_mode() -> u32 {
+    0o644
+}
+
+/// Creates the builders configured by the metadata tables of the workspace.
+pub(super) fn dist_builders(
+    workspace: &Metadata,
+) -> Result<(DistConfigBuilder<'_>, Vec<DistPackageConfigBuilder<'_>>)> {
+    let path = "workspace.metadata.cli-xtask.dist";
+    let metadata = read_table::<DistMetadata>(&workspace.workspace_metadata, &format!("`{path}`"))?;
+
+    let packages = match &metadata.packages {
+        Some(names) => {
+            let workspace_packages = workspace.workspace_packages();
+            names
+                .iter()
+                .map(|name| {
+                    workspace_packages
+                        .iter()
+                        .copied()
+                        .find(|package| package.name == name.as_str())
+                        .ok_or_else(|| eyre!("no package found: {name} (in `{path}.packages`)"))
+                })
+                .collect::<Result<Vec<_>>>()?
+        }
+        None => match workspace.root_package() {
+            Some(package) => vec![package],
+            None => workspace.workspace_default_packages(),
+        },
+    };
+
+    let name = match (&metadata.name, packages.first()) {
+        (Some(name), _) => name.clone(),
+        (None, Some(package)) => format!("{}-v{}", package.name, package.version),
+        (None, None) => return Err(eyre!("no package to distribute, set `{path}.name`")),
+    };
+
+    let dist = apply_dist(DistConfigBuilder::new(name, workspace), metadata)?;
+    let packages = packages
+        .into_iter()
+        .map(|package| {
+            let builder = DistPackageConfigBuilder::new(package);
+            apply_package(builder, package)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((dist, packages))
+}
+
+fn read_table<T>(value: &serde_json::Value, table_name: &str) -> Result<T>
+where
+    T: DeserializeOwned + Default,
+{
+    let Some(table) = value.get("cli-xtask").and_then(|value| value.get("dist")) else {
+        return Ok(T::default());
+    };
+    let table =
+        serde_json::from_value(table.clone()).map_err(|e| eyre!("invalid {table_name}: {e}"))?;
+    Ok(table)
+}
+
+fn warn_unknown(unknown: &BTreeMap<String, serde_json::Value>, table_name: &str) {
+    for key in unknown.keys() {
+        tracing::warn!(
+            "ignoring `{key}` in {table_name}: unknown key, or the feature using it is disabled"
+        );
+    }
+}
+
+fn apply_dist<'a>(
+    builder: DistConfigBuilder<'a>,
+    metadata: DistMetadata,
+) -> Result<DistConfigBuilder<'a>> {
+    warn_unknown(&metadata.unknown, "`workspace.metadata.cli-xtask.dist`");
+
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    let builder = {
+        let mut builder = builder
+            .cargo_build_options(metadata.cargo_build_options)
+            .target_triples(metadata.target_triples);
+        if let Some(profile) = metadata.cargo_profile {
+            builder = builder.cargo_profile(profile);
+        }
+        if let Some(strip) = metadata.strip_executables {
+            builder = builder.strip_executables(strip);
+        }
+        if let Some(split) = metadata.split_debuginfo {
+            builder = builder.split_debuginfo(split);
+        }
+        builder
+    };
+
+    #[cfg(feature = "subcommand-dist-archive")]
+    let builder = {
+        use crate::archive::{ArchiveFormat, Compression};
+
+        let mut builder = builder;
+        if let Some(format) = metadata.archive_format {
+            let format =
+                <ArchiveFormat as clap::ValueEnum>::from_str(&format, true).map_err(|e| {
+                    eyre!("invalid `workspace.metadata.cli-xtask.dist.archive-format`: {e}")
+                })?;
+            builder = builder.archive_format(format);
+        }
+        if let Some(compressions) = metadata.archive_compressions {
+            let compressions = compressions
+                .iter()
+                .map(|compression| compression.parse::<Compression>())
+                .collect::<Result<Vec<_>>>()?;
+            builder = builder.archive_compressions(compressions);
+        }
+        if let Some(reproducible) = metadata.reproducible_archive {
+            builder = builder.reproducible_archive(reproducible);
+        }
+        if let Some(template) = metadata.archive_prefix {
+            builder = builder.archive_prefix(template);
+        }
+        if let Some(template) = metadata.archive_name {
+            builder = builder.archive_name(template);
+        }
+        builder
+    };
+
+    #[cfg(feature = "subcommand-dist-build-recipes")]
+    let builder = match metadata.download_url {
+        Some(template) => builder.download_url(template),
+        None => builder,
+    };
+
+    #[cfg(feature = "subcommand-dist-build-license")]
+    let builder = match metadata.third_party_licenses {
+        Some(enabled) => builder.third_party_licenses(enabled),
+        None => builder,
+    };
+
+    Ok(builder)
+}
+
+fn apply_package<'a>(
+    builder: DistPackageConfigBuilder<'a>,
+    package: &'a Package,
+) -> Result<DistPackageConfigBuilder<'a>> {
+    let table_name = format!(
+        "`package.metadata.cli-xtask.dist` of package `{}`",
+        package.name
+    );
+    let metadata = read_table::<DistPackageMetadata>(&package.metadata, &table_name)?;
+    warn_unknown(&metadata.unknown, &table_name);
+
+    let builder = match metadata.binaries {
+        Some(binaries) => {
+            let targets = binaries
+                .iter()
+                .map(|name| builder.binary_by_name(name)?.build())
+                .collect::<Result<Vec<_>>>()?;
+            builder.targets(targets)
+        }
+        None => builder,
+    };
+
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    let builder = {
+        let mut builder = builder.cargo_build_options(metadata.cargo_build_options);
+        if let Some(profile) = metadata.cargo_profile {
+            builder = builder.cargo_profile(profile);
+        }
+        if let Some(file) = metadata.c_header {
+            builder = builder.c_header(file);
+        }
+        if let Some(generate) = metadata.pkg_config {
+            builder = builder.pkg_config(generate);
+        }
+        builder
+    };
+
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    let builder = {
+        let mut builder = builder;
+        for entry in metadata.documents {
+            builder = match entry.into_parts() {
+                (src, dest) if is_glob(&src) => {
+                    builder.documents_glob(src, dest.unwrap_or_default())
+                }
+                (src, Some(dest)) => builder.document_as(src, dest),
+                (src, None) => builder.documents([src.into()]),
+            };
+        }
+        for entry in metadata.extra_files {
+            builder = if is_glob(&entry.src) {
+                builder.extra_files_glob(entry.src, entry.dest, entry.mode)
+            } else {
+                builder.extra_file(entry.src, entry.dest, entry.mode)
+            };
+        }
+        builder
+    };
+
+    #[cfg(feature = "subcommand-dist-build-license")]
+    let builder = match metadata.license_files {
+        Some(entries) => {
+            // listing the files disables the detection of the license files,
+            // even if the list is empty
+            let mut builder = builder.license_files([]);
+            for entry in entries {
+                builder = match entry.into_parts() {
+                    (src, dest) if is_glob(&src) => {
+                        builder.license_files_glob(src, dest.unwrap_or_default())
+                    }
+                    (src, Some(dest)) => builder.license_file_as(src, dest),
+                    (src, None) => builder.license_files([src.into()]),
+                };
+            }
+            builder
+        }
+        None => builder,
+    };
+
+    Ok(builder)
+}
+
+/// Returns `true` if the path contains glob metacharacters.
+#[cfg(any(
+    feature = "subcommand-dist-build-doc",
+    feature = "subcommand-dist-build-license"
+))]
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn dist_metadata(table: serde_json::Value) -> Result<DistMetadata> {
+        read_table(&json!({ "cli-xtask": { "dist": table } }), "test")
+    }
+
+    fn package_metadata(table: serde_json::Value) -> Result<DistPackageMetadata> {
+        read_table(&json!({ "cli-xtask": { "dist": table } }), "test")
+    }
+
+    #[test]
+    fn builders() {
+        let mut workspace = crate::workspace::current().clone();
+        let root = workspace.root_package().unwrap().clone();
+
+        let (dist, packages) = dist_builders(&workspace).unwrap();
+        let config = dist
+            .packages(packages.into_iter().map(|package| package.build().unwrap()))
+            .build()
+            .unwrap();
+        assert_eq!(config.name(), format!("{}-v{}", root.name, root.version));
+        assert_eq!(config.packages()[0].name(), &root.name);
+
+        workspace.workspace_metadata = json!({
+            "cli-xtask": { "dist": { "name": "app", "packages": ["xtask"] } }
+        });
+        let (dist, packages) = dist_builders(&workspace).unwrap();
+        let config = dist
+            .packages(packages.into_iter().map(|package| package.build().unwrap()))
+            .build()
+            .unwrap();
+        assert_eq!(config.name(), "app");
+        assert_eq!(config.packages()[0].name().as_str(), "xtask");
+
+        workspace.workspace_metadata = json!({
+            "cli-xtask": { "dist": { "packages": ["no-such-package"] } }
+        });
+        assert!(dist_builders(&workspace).is_err());
+    }
+
+    #[test]
+    fn missing_table() {
+        let metadata = read_table::<DistMetadata>(&json!(null), "test").unwrap();
+        assert_eq!(metadata.name, None);
+        assert!(metadata.unknown.is_empty());
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let metadata = dist_metadata(json!({ "name": "app", "nmae": "app" })).unwrap();
+        assert_eq!(metadata.name.as_deref(), Some("app"));
+        assert_eq!(metadata.unknown.keys().collect::<Vec<_>>(), ["nmae"]);
+
+        let metadata = package_metadata(json!({ "binaries": ["app"], "binary": "app" })).unwrap();
+        assert_eq!(metadata.binaries, Some(vec!["app".to_owned()]));
+        assert_eq!(metadata.unknown.keys().collect::<Vec<_>>(), ["binary"]);
+    }
+
+    #[test]
+    fn invalid_value() {
+        let err = dist_metadata(json!({ "name": 1 })).unwrap_err();
+        assert!(err.to_string().starts_with("invalid test: "), "{err}");
+    }
+
+    #[cfg(any(
+        feature = "subcommand-dist-build-doc",
+        feature = "subcommand-dist-build-license"
+    ))]
+    #[test]
+    fn file_entry() {
+        let cases = [
+            (json!("README.md"), ("README.md", None)),
+            (
+                json!({ "src": "docs/*.md", "dest": "guide" }),
+                ("docs/*.md", Some("guide")),
+            ),
+            (json!({ "src": "NOTICE" }), ("NOTICE", None)),
+        ];
+        for (input, (src, dest)) in cases {
+            let entry = serde_json::from_value::<FileEntry>(input.clone()).unwrap();
+            let parts = entry.into_parts();
+            assert_eq!(
+                (parts.0.as_str(), parts.1.as_deref()),
+                (src, dest),
+                "input: {input}"
+            );
+        }
+
+        for input in [
+            json!({ "dest": "guide" }),
+            json!({ "src": "a", "path": "b" }),
+        ] {
+            assert!(
+                serde_json::from_value::<FileEntry>(input.clone()).is_err(),
+                "input: {input}"
+            );
+        }
+    }
+
+    #[cfg(any(
+        feature = "subcommand-dist-build-doc",
+        feature = "subcommand-dist-build-license"
+    ))]
+    #[test]
+    fn glob() {
+        let cases = [
+            ("README.md", false),
+            ("docs/guide.md", false),
+            ("LICENSE-*", true),
+            ("docs/?.md", true),
+            ("man/[a-z].1", true),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(is_glob(input), expected, "input: {input}");
+        }
+    }
+
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    #[test]
+    fn extra_file_entry() {
+        let metadata = package_metadata(json!({
+            "extra-files": [
+                { "src": "app.toml", "dest": "/etc/app/app.toml" },
+                { "src": "hook.sh", "dest": "share/app/hook.sh", "mode": 0o755 },
+            ],
+        }))
+        .unwrap();
+        let entries = metadata
+            .extra_files
+            .iter()
+            .map(|entry| (entry.src.as_str(), entry.dest.as_str(), entry.mode))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                ("app.toml", "/etc/app/app.toml", 0o644),
+                ("hook.sh", "share/app/hook.sh", 0o755),
+            ]
+        );
+
+        // the destination of extra files is required
+        assert!(package_metadata(json!({ "extra-files": ["app.toml"] })).is_err());
+    }
+
+    #[cfg(feature = "subcommand-dist-build-license")]
+    #[test]
+    fn license_files() {
+        let workspace = crate::workspace::current();
+        let mut package = workspace.root_package().unwrap().clone();
+
+        let detected = apply_package(DistPackageConfigBuilder::new(&package), &package)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(!detected.license_files().is_empty());
+
+        // an empty list disables the detection of the license files
+        package.metadata = json!({ "cli-xtask": { "dist": { "license-files": [] } } });
+        let disabled = apply_package(DistPackageConfigBuilder::new(&package), &package)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(disabled.license_files().is_empty());
+
+        package.metadata = json!({
+            "cli-xtask": { "dist": { "license-files": [{ "src": "LICENSE-MIT", "dest": "COPYING" }] } }
+        });
+        let listed = apply_package(DistPackageConfigBuilder::new(&package), &package)
+            .unwrap()
+            .build()
+            .unwrap();
+        let dests = listed
+            .license_dist_files()
+            .iter()
+            .map(|file| file.dest().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(dests, ["COPYING"]);
+    }
+
+    #[cfg(feature = "subcommand-dist-archive")]
+    #[test]
+    fn archive_options() {
+        use crate::archive::{ArchiveFormat, Compression, CompressionCodec};
+
+        let workspace = crate::workspace::current();
+        let metadata = dist_metadata(json!({
+            "archive-format": "ZIP",
+            "archive-compressions": ["xz", "zstd:19"],
+        }))
+        .unwrap();
+        let config = apply_dist(DistConfigBuilder::new("app", workspace), metadata)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.archive_format("x86_64-unknown-linux-gnu"),
+            ArchiveFormat::Zip
+        );
+        assert_eq!(
+            config.archive_compressions(),
+            [
+                Compression::new(CompressionCodec::Xz, None),
+                Compression::new(CompressionCodec::Zstd, Some(19)),
+            ]
+        );
+
+        let cases = [
+            json!({ "archive-format": "rar" }),
+            json!({ "archive-compressions": ["lzma"] }),
+            json!({ "archive-compressions": ["gzip:100"] }),
+        ];
+        for input in cases {
+            let metadata = dist_metadata(input.clone()).unwrap();
+            assert!(
+                apply_dist(DistConfigBuilder::new("app", workspace), metadata).is_err(),
+                "input: {input}"
+            );
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! Dependency graph of the workspace packages.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use cargo_metadata::{Metadata, Node, Package, PackageId};
+use eyre::eyre;
+
+use crate::Result;
+
+/// Name of the file listing the licenses of the dependencies.
+#[cfg(feature = "subcommand-dist-build-license")]
+pub(crate) const THIRD_PARTY_LICENSES: &str = "THIRD_PARTY_LICENSES";
+
+/// Returns the metadata of the workspace with the dependency graph resolved
+/// for the target triple and the features the package is built with.
+///
+/// The features are selected by the cargo build options used by
+/// `dist-build-bin` to build the package and its targets.
+#[cfg(any(
+    feature = "subcommand-dist-build-license",
+    feature = "subcommand-dist-build-sbom"
+))]
+pub(crate) fn package_metadata(
+    config: &crate::config::DistConfig,
+    package: &crate::config::DistPackageConfig,
+    target_triple: &str,
+) -> Result<Metadata> {
+    use cargo_metadata::MetadataCommand;
+
+    let options = vec!["--filter-platform".to_owned(), target_triple.to_owned()];
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    let options = {
+        let build_options = config
+            .cargo_build_options()
+            .iter()
+            .chain(package.cargo_build_options())
+            .chain(
+                package
+                    .targets()
+                    .iter()
+                    .flat_map(|target| target.cargo_build_options()),
+            )
+            .map(String::as_str);
+        let mut options = options;
+        options.extend(feature_options(build_options));
+        options
+    };
+    #[cfg(not(feature = "subcommand-dist-build-bin"))]
+    let _ = config;
+
+    // features are enabled on the package selected by the manifest path
+    let metadata = MetadataCommand::new()
+        .manifest_path(&package.metadata().manifest_path)
+        .other_options(options)
+        .exec()?;
+    Ok(metadata)
+}
+
+/// Extracts the options selecting the features from the cargo build options.
+#[cfg(all(
+    feature = "subcommand-dist-build-bin",
+    any(
+        feature = "subcommand-dist-build-license",
+        feature = "subcommand-dist-build-sbom"
+    )
+))]
+fn feature_options<'a>(build_options: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut options = vec![];
+    let mut build_options = build_options.into_iter();
+    while let Some(option) = build_options.next() {
+        let features = match option {
+            "--all-features" | "--no-default-features" => {
+                options.push(option.to_owned());
+                continue;
+            }
+            "--features" | "-F" => build_options.next(),
+            _ => option
+                .strip_prefix("--features=")
+                .or_else(|| option.strip_prefix("-F")),
+        };
+        if let Some(features) = features {
+            options.extend(["--features".to_owned(), features.to_owned()]);
+        }
+    }
+    options
+}
+
+/// Returns the dependency graph of the `roots` through normal dependencies,
+/// mapping each package reachable from the `roots` (including themselves) to
+/// the packages it directly depends on.
+///
+/// Procedural macro packages and their dependencies are excluded, as they are
+/// not linked into the artifacts.
+#[cfg(any(
+    feature = "subcommand-dist-build-license",
+    feature = "subcommand-dist-build-sbom"
+))]
+pub(crate) fn normal_dependency_graph<'a>(
+    metadata: &'a Metadata,
+    roots: &[&'a PackageId],
+) -> Result<BTreeMap<&'a PackageId, Vec<&'a PackageId>>> {
+    use cargo_metadata::{DependencyKind, TargetKind};
+
+    let nodes = resolve_nodes(metadata)?;
+    let packages = packages(metadata);
+
+    let mut graph = BTreeMap::new();
+    let mut queue = roots.iter().copied().collect::<VecDeque<_>>();
+    while let Some(id) = queue.pop_front() {
+        if graph.contains_key(id) {
+            continue;
+        }
+        let node = nodes
+            .get(id)
+            .ok_or_else(|| eyre!("package not found in dependency graph: {id}"))?;
+        let mut deps = vec![];
+        for dep in &node.deps {
+            let is_normal = dep
+                .dep_kinds
+                .iter()
+                .any(|kind| kind.kind == DependencyKind::Normal);
+            let is_proc_macro = packages.get(&dep.pkg).is_some_and(|package| {
+                package
+                    .targets
+                    .iter()
+                    .any(|target| target.kind.contains(&TargetKind::ProcMacro))
+            });
+            if is_normal && !is_proc_macro {
+                deps.push(&dep.pkg);
+                queue.push_back(&dep.pkg);
+            }
+        }
+        graph.insert(id, deps);
+    }
+    Ok(graph)
+}
+
+/// Returns the packages the `roots` depend on through normal dependencies,
+/// directly or indirectly, excluding the workspace members.
+///
+/// Packages are sorted by their names and versions.
+#[cfg(feature = "subcommand-dist-build-license")]
+pub(crate) fn normal_dependencies<'a>(
+    metadata: &'a Metadata,
+    roots: &[&'a PackageId],
+) -> Result<Vec<&'a Package>> {
+    let packages = packages(metadata);
+    let mut dependencies = normal_dependency_graph(metadata, roots)?
+        .into_keys()
+        .filter(|id| !metadata.workspace_members.contains(id))
+        .map(|id| {
+            packages
+                .get(id)
+                .copied()
+                .ok_or_else(|| eyre!("package not found in metadata: {id}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    dependencies.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    Ok(dependencies)
+}
+
+/// Shortest dependency paths from the workspace members to the packages in
+/// the dependency graph, through any kind of dependencies.
+#[cfg(feature = "subcommand-license-check")]
+#[derive(Debug)]
+pub(crate) struct DependencyPaths<'a> {
+    packages: BTreeMap<&'a PackageId, &'a Package>,
+    /// Package depending on the key package on the shortest path
+    parents: BTreeMap<&'a PackageId, &'a PackageId>,
+}
+
+#[cfg(feature = "subcommand-license-check")]
+impl<'a> DependencyPaths<'a> {
+    /// Computes the dependency paths in the resolved dependency graph.
+    pub(crate) fn new(metadata: &'a Metadata) -> Result<Self> {
+        let nodes = resolve_nodes(metadata)?;
+
+        let mut parents = BTreeMap::new();
+        let mut queue = metadata.workspace_members.iter().collect::<VecDeque<_>>();
+        while let Some(id) = queue.pop_front() {
+            let node = nodes
+                .get(id)
+                .ok_or_else(|| eyre!("package not found in dependency graph: {id}"))?;
+            for dep in &node.deps {
+                if metadata.workspace_members.contains(&dep.pkg) || parents.contains_key(&dep.pkg) {
+                    continue;
+                }
+                parents.insert(&dep.pkg, id);
+                queue.push_back(&dep.pkg);
+            }
+        }
+
+        Ok(Self {
+            packages: packages(metadata),
+            parents,
+        })
+    }
+
+    /// Returns the packages the dependency path to the package goes through,
+    /// starting from a workspace member and ending with the package itself.
+    pub(crate) fn path(&self, id: &'a PackageId) -> Vec<&'a Package> {
+        let mut path = vec![];
+        let mut id = Some(id);
+        while let Some(current) = id {
+            path.extend(self.packages.get(current).copied());
+            id = self.parents.get(current).copied();
+        }
+        path.reverse();
+        path
+    }
+}
+
+fn resolve_nodes(metadata: &Metadata) -> Result<BTreeMap<&PackageId, &Node>> {
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .ok_or_else(|| eyre!("dependency graph is not resolved"))?;
+    Ok(resolve.nodes.iter().map(|node| (&node.id, node)).collect())
+}
+
+fn packages(metadata: &Metadata) -> BTreeMap<&PackageId, &Package> {
+    metadata
+        .packages
+        .iter()
+        .map(|package| (&package.id, package))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(any(
+        feature = "subcommand-dist-build-license",
+        feature = "subcommand-dist-build-sbom"
+    ))]
+    fn normal_dependency_graph() {
+        let metadata = crate::workspace::current();
+        let root = metadata.root_package().unwrap();
+        let package = |name: &str| {
+            metadata
+                .packages
+                .iter()
+                .find(|package| package.name.as_str() == name)
+                .unwrap()
+        };
+
+        let graph = super::normal_dependency_graph(metadata, &[&root.id]).unwrap();
+        assert!(graph[&root.id].contains(&&package("eyre").id));
+        assert!(graph.contains_key(&package("clap_builder").id));
+        // procedural macros and dev-dependencies
+        for name in ["clap_derive", "tempfile"] {
+            assert!(!graph.contains_key(&package(name).id), "name: {name}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "subcommand-dist-build-license")]
+    fn normal_dependencies() {
+        let metadata = crate::workspace::current();
+        let root = metadata.root_package().unwrap();
+
+        let dependencies = super::normal_dependencies(metadata, &[&root.id]).unwrap();
+        let names = dependencies
+            .iter()
+            .map(|package| package.name.as_str())
+            .collect::<Vec<_>>();
+        // normal dependencies, directly or indirectly
+        assert!(names.contains(&"eyre"), "names: {names:?}");
+        assert!(names.contains(&"clap_builder"), "names: {names:?}");
+        // procedural macros, dev-dependencies and workspace members
+        for name in ["clap_derive", "tempfile", "cli-xtask", "xtask"] {
+            assert!(!names.contains(&name), "name: {name}");
+        }
+        assert!(
+            dependencies.is_sorted_by_key(|package| (&package.name, &package.version)),
+            "names: {names:?}"
+        );
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "subcommand-dist-build-bin",
+        any(
+            feature = "subcommand-dist-build-license",
+            feature = "subcommand-dist-build-sbom"
+        )
+    ))]
+    fn feature_options() {
+        let cases: [(&[&str], &[&str]); 5] = [
+            (&["--locked"], &[]),
+            (&["--features", "a,b"], &["--features", "a,b"]),
+            (
+                &["--features=a", "-Fb"],
+                &["--features", "a", "--features", "b"],
+            ),
+            (
+                &["--no-default-features", "-F", "a"],
+                &["--no-default-features", "--features", "a"],
+            ),
+            (&["--all-features", "--release"], &["--all-features"]),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                super::feature_options(input.iter().copied()),
+                expected,
+                "input: {input:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,370 @@
+//! Manifest describing the distribution archives.
+//!
+//! [`cargo xtask dist-archive`] writes a `dist-manifest.json` file into the
+//! [`dist_target_directory`] alongside the archives and their `.sha256`
+//! checksum files, so that release tooling can consume the artifacts without
+//! parsing their file names.
+//! When the manifest is rewritten, the entries of the archives created for
+//! other distributions or other package versions are removed.
+//!
+//! [`cargo xtask dist-archive`]: crate::subcommand::DistArchive
+//! [`dist_target_directory`]: crate::config::DistConfig::dist_target_directory
+//!
+//! # Examples
+//!
+//! ```json
+//! {
+//!   "archives": [
+//!     {
+//!       "file": "app-v0.1.0-x86_64-unknown-linux-gnu.tar.gz",
+//!       "name": "app-v0.1.0",
+//!       "target": "x86_64-unknown-linux-gnu",
+//!       "format": "tar",
+//!       "compression": "gzip",
+//!       "size": 1234567,
+//!       "digests": {
+//!         "sha256": "0123456789abcdef..."
+//!       },
+//!       "packages": [
+//!         {
+//!           "name": "app",
+//!           "version": "0.1.0",
+//!           "profile": "release",
+//!           "targets": [
+//!             {
+//!               "name": "app",
+//!               "kind": ["bin"]
+//!             }
+//!           ]
+//!         }
+//!       ],
+//!       "sboms": [
+//!         {
+//!           "path": "app-v0.1.0-x86_64-unknown-linux-gnu/app.cdx.json",
+//!           "format": "cyclonedx-json",
+//!           "package": "app",
+//!           "digests": {
+//!             "sha256": "fedcba9876543210..."
+//!           }
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+
+use std::{
+    fs::File,
+    io::{self, Write},
+};
+
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{Result, config::DistConfig};
+
+/// File name of the distribution manifest.
+pub const FILE_NAME: &str = "dist-manifest.json";
+
+/// Manifest describing the distribution archives.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub struct DistManifest {
+    /// Archives in the distribution target directory.
+    #[serde(default)]
+    pub archives: Vec<ArchiveEntry>,
+}
+
+/// Archive listed in the [`DistManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub struct ArchiveEntry {
+    /// File name of the archive.
+    pub file: String,
+    /// Name of the distribution.
+    pub name: String,
+    /// Target triple of the archive, or `None` for the archive containing
+    /// only architecture-independent artifacts.
+    pub target: Option<String>,
+    /// Format of the archive (`tar` or `zip`).
+    pub format: String,
+    /// Compression codec of the archive.
+    pub compression: String,
+    /// Size of the archive in bytes.
+    pub size: u64,
+    /// Digests of the archive.
+    pub digests: Digests,
+    /// Whether the archive contains the debug info split from the executables
+    /// instead of the executables themselves.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub symbols: bool,
+    /// Packages contained in the archive.
+    pub packages: Vec<PackageEntry>,
+    /// Software bill of materials (SBOM) documents contained in the archive.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sboms: Vec<SbomEntry>,
+}
+
+/// Software bill of materials (SBOM) document contained in an
+/// [`ArchiveEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub struct SbomEntry {
+    /// Path of the document in the archive.
+    pub path: String,
+    /// Format of the document (`cyclonedx-json` or `spdx-json`).
+    pub format: String,
+    /// Name of the package the document describes.
+    pub package: String,
+    /// Digests of the document.
+    pub digests: Digests,
+}
+
+/// Digests of a file listed in the [`DistManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub struct Digests {
+    /// Hex-encoded SHA-256 digest.
+    pub sha256: String,
+}
+
+/// Package contained in an [`ArchiveEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub struct PackageEntry {
+    /// Name of the package.
+    pub name: String,
+    /// Version of the package.
+    pub version: String,
+    /// Cargo profile used to build the executables of the package, or `None`
+    /// if the archive doesn't contain any build artifacts of the package.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Targets (binaries, libraries, etc.) of the package.
+    pub targets: Vec<TargetEntry>,
+}
+
+/// Target of a [`PackageEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub struct TargetEntry {
+    /// Name of the target.
+    pub name: String,
+    /// Kinds of the target (e.g. `bin`).
+    pub kind: Vec<String>,
+}
+
+impl DistManifest {
+    /// Returns the path of the manifest file for the given distribution.
+    pub fn path(config: &DistConfig) -> Utf8PathBuf {
+        config.dist_target_directory().join(FILE_NAME)
+    }
+
+    /// Reads the manifest from the given path.
+    ///
+    /// Returns an empty manifest if the file does not exist.
+    pub fn read(path: impl AsRef<Utf8Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let file = File::open(path)?;
+        let manifest = serde_json::from_reader(io::BufReader::new(file))?;
+        Ok(manifest)
+    }
+
+    /// Writes the manifest to the given path.
+    pub fn write(&self, path: impl AsRef<Utf8Path>) -> Result<()> {
+        let mut file = crate::fs::create_file(path)?;
+        serde_json::to_writer_pretty(&mut file, self)?;
+        writeln!(file)?;
+        Ok(())
+    }
+
+    /// Adds the archive to the manifest, replacing the existing entry with
+    /// the same file name.
+    pub fn insert_archive(&mut self, archive: ArchiveEntry) {
+        self.archives.retain(|a| a.file != archive.file);
+        self.archives.push(archive);
+        self.archives.sort_by(|a, b| a.file.cmp(&b.file));
+    }
+}
+
+impl ArchiveEntry {
+    /// Returns `true` if the archive was created for the distribution with
+    /// the given name and packages in their current versions.
+    pub(crate) fn is_current(&self, name: &str, packages: &[PackageEntry]) -> bool {
+        self.name == name
+            && self.packages.len() == packages.len()
+            && self
+                .packages
+                .iter()
+                .zip(packages)
+                .all(|(a, b)| a.name == b.name && a.version == b.version)
+    }
+}
+
+impl Digests {
+    /// Computes the digests of the given file.
+    pub fn from_file(path: impl AsRef<Utf8Path>) -> Result<Self> {
+        let mut hasher = Sha256::new();
+        io::copy(&mut File::open(path.as_ref())?, &mut hasher)?;
+        let sha256 = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        Ok(Self { sha256 })
+    }
+}
+
+impl PackageEntry {
+    /// Creates the entries of the packages in the distribution.
+    pub fn from_config(config: &DistConfig) -> Vec<Self> {
+        config
+            .packages()
+            .iter()
+            .map(|package| Self {
+                name: package.name().to_string(),
+                version: package.metadata().version.to_string(),
+                profile: None,
+                targets: package
+                    .targets()
+                    .iter()
+                    .map(|target| TargetEntry {
+                        name: target.name().to_string(),
+                        kind: target
+                            .metadata()
+                            .kind
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archive(file: &str, name: &str, version: &str) -> ArchiveEntry {
+        ArchiveEntry {
+            file: file.to_owned(),
+            name: name.to_owned(),
+            target: None,
+            format: "tar".to_owned(),
+            compression: "gzip".to_owned(),
+            size: 0,
+            digests: Digests {
+                sha256: String::new(),
+            },
+            symbols: false,
+            packages: vec![package("app", version)],
+            sboms: vec![],
+        }
+    }
+
+    fn package(name: &str, version: &str) -> PackageEntry {
+        PackageEntry {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            profile: None,
+            targets: vec![],
+        }
+    }
+
+    #[test]
+    fn insert_archive() {
+        let mut manifest = DistManifest::default();
+        manifest.insert_archive(archive("b.tar.gz", "app", "0.1.0"));
+        manifest.insert_archive(archive("a.tar.gz", "app", "0.1.0"));
+        manifest.insert_archive(archive("b.tar.gz", "app", "0.2.0"));
+        let files = manifest
+            .archives
+            .iter()
+            .map(|a| (a.file.as_str(), a.packages[0].version.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(files, [("a.tar.gz", "0.1.0"), ("b.tar.gz", "0.2.0")]);
+    }
+
+    #[test]
+    fn is_current() {
+        let packages = [package("app", "0.2.0")];
+        let cases = [
+            (archive("app.tar.gz", "app", "0.2.0"), true),
+            (archive("app.tar.gz", "app", "0.1.0"), false),
+            (archive("other.tar.gz", "other", "0.2.0"), false),
+        ];
+        for (archive, expected) in cases {
+            assert_eq!(
+                archive.is_current("app", &packages),
+                expected,
+                "archive: {archive:?}"
+            );
+        }
+
+        let mut archive = archive("app.tar.gz", "app", "0.2.0");
+        archive.packages.push(package("lib", "0.2.0"));
+        assert!(!archive.is_current("app", &packages));
+    }
+
+    #[test]
+    fn package_profile() {
+        let mut package = package("app", "0.1.0");
+        let json = serde_json::to_value(&package).unwrap();
+        assert_eq!(json.get("profile"), None);
+        assert_eq!(
+            serde_json::from_value::<PackageEntry>(json)
+                .unwrap()
+                .profile,
+            None
+        );
+
+        package.profile = Some("dist".to_owned());
+        let json = serde_json::to_value(&package).unwrap();
+        assert_eq!(json["profile"], "dist");
+        assert_eq!(
+            serde_json::from_value::<PackageEntry>(json)
+                .unwrap()
+                .profile
+                .as_deref(),
+            Some("dist")
+        );
+    }
+
+    #[test]
+    fn archive_sboms() {
+        let mut archive = archive("app.tar.gz", "app", "0.1.0");
+        let json = serde_json::to_value(&archive).unwrap();
+        assert_eq!(json.get("sboms"), None);
+        assert_eq!(
+            serde_json::from_value::<ArchiveEntry>(json).unwrap().sboms,
+            []
+        );
+
+        archive.sboms.push(SbomEntry {
+            path: "app/app.cdx.json".to_owned(),
+            format: "cyclonedx-json".to_owned(),
+            package: "app".to_owned(),
+            digests: Digests {
+                sha256: "0123".to_owned(),
+            },
+        });
+        let json = serde_json::to_value(&archive).unwrap();
+        assert_eq!(json["sboms"][0]["format"], "cyclonedx-json");
+        assert_eq!(
+            serde_json::from_value::<ArchiveEntry>(json).unwrap(),
+            archive
+        );
+    }
+}
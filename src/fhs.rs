@@ -0,0 +1,407 @@
+//! Layout of the distribution artifacts following the Filesystem Hierarchy
+//! Standard.
+
+use cargo_metadata::{
+    TargetKind,
+    camino::{Utf8Path, Utf8PathBuf},
+};
+use eyre::{ensure, eyre};
+
+use crate::{
+    Result,
+    config::{DistConfig, DistPackageConfig},
+    fs::ToRelative,
+};
+
+/// Staged artifact to be installed into the FHS layout.
+#[derive(Debug, Clone)]
+pub(crate) struct FhsEntry {
+    /// Path of the staged artifact in the dist working directory.
+    pub(crate) src: Utf8PathBuf,
+    /// Destination path relative to the installation prefix (e.g. `/usr`).
+    ///
+    /// Root-relative extra files are relative to the root directory of the
+    /// system packages, and to the installation prefix of `dist-install`.
+    pub(crate) dest: Utf8PathBuf,
+    /// Unix permission bits of the installed file.
+    pub(crate) mode: u32,
+    /// Kind of the artifact.
+    #[cfg_attr(
+        not(any(
+            feature = "subcommand-dist-build-deb",
+            feature = "subcommand-dist-build-recipes",
+            feature = "subcommand-dist-build-rpm"
+        )),
+        allow(dead_code)
+    )]
+    pub(crate) kind: FhsEntryKind,
+}
+
+/// Kind of the staged artifact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FhsEntryKind {
+    /// Executable installed into `bin/`.
+    Executable,
+    /// Library or pkg-config file installed into `lib/`.
+    Library,
+    /// C header file installed into `include/`.
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    Header,
+    /// Man page installed into `share/man/`.
+    ManPage,
+    /// Shell completion file.
+    Completion,
+    /// README or other document installed into `share/doc/<package>/`.
+    Document,
+    /// License file installed into `share/doc/<package>/`.
+    #[cfg(feature = "subcommand-dist-build-license")]
+    License,
+    /// Extra file installed into the configured destination.
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    Extra,
+    /// Extra file installed into the configured destination relative to the
+    /// root directory of the system packages.
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    RootExtra,
+    /// Configuration file installed into the configured destination under
+    /// `/etc` of the system packages.
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    Config,
+}
+
+impl FhsEntry {
+    /// Returns the installed path of the artifact in the system packages,
+    /// relative to the root directory.
+    ///
+    /// Artifacts are installed into `/usr`, except for the root-relative
+    /// extra files.
+    #[cfg(any(
+        feature = "subcommand-dist-build-deb",
+        feature = "subcommand-dist-build-recipes",
+        feature = "subcommand-dist-build-rpm"
+    ))]
+    pub(crate) fn system_path(&self) -> Utf8PathBuf {
+        if self.is_root_relative() {
+            self.dest.clone()
+        } else {
+            Utf8Path::new("usr").join(&self.dest)
+        }
+    }
+
+    /// Returns `true` if the artifact is a root-relative extra file.
+    #[cfg(any(
+        feature = "subcommand-dist-build-deb",
+        feature = "subcommand-dist-build-recipes",
+        feature = "subcommand-dist-build-rpm"
+    ))]
+    pub(crate) fn is_root_relative(&self) -> bool {
+        #[cfg(feature = "subcommand-dist-build-doc")]
+        return matches!(self.kind, FhsEntryKind::RootExtra | FhsEntryKind::Config);
+        #[cfg(not(feature = "subcommand-dist-build-doc"))]
+        return false;
+    }
+
+    /// Returns `true` if the artifact is a configuration file, which is
+    /// preserved by the system package managers when modified by the user.
+    #[cfg(any(
+        feature = "subcommand-dist-build-deb",
+        feature = "subcommand-dist-build-recipes"
+    ))]
+    pub(crate) fn is_config(&self) -> bool {
+        #[cfg(feature = "subcommand-dist-build-doc")]
+        return self.kind == FhsEntryKind::Config;
+        #[cfg(not(feature = "subcommand-dist-build-doc"))]
+        return false;
+    }
+}
+
+/// Options to select the destination directories that differ between
+/// distributions.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FhsOptions {
+    /// Directory of zsh completion files, relative to the prefix.
+    pub(crate) zsh_completion_dir: &'static str,
+}
+
+impl Default for FhsOptions {
+    fn default() -> Self {
+        Self {
+            zsh_completion_dir: "share/zsh/site-functions",
+        }
+    }
+}
+
+/// Collects the staged artifacts of the package for the given target triple
+/// and maps them to their FHS destination paths.
+///
+/// * executables are installed into `bin/`
+/// * library files and pkg-config files are installed into `lib/`, and C
+///   header files are installed into `include/`
+/// * man pages are installed into `share/man/man<section>/`
+/// * bash, zsh and fish completion files are installed into their
+///   vendor completion directories
+/// * README, documents, license files and third-party license files are
+///   installed into `share/doc/<package>/`
+/// * extra files are installed into their destination paths, and
+///   root-relative extra files are installed relative to the root directory
+///   of the system packages
+///
+/// Artifacts other than executables are optional, and skipped if they are not
+/// staged.
+pub(crate) fn package_entries(
+    config: &DistConfig,
+    package: &DistPackageConfig,
+    target_triple: &str,
+    options: FhsOptions,
+) -> Result<Vec<FhsEntry>> {
+    let bin_dir = config.dist_working_directory(Some(target_triple));
+    let noarch_dir = config.dist_working_directory(None);
+    let package_dir = |dir: &str| {
+        if config.packages().len() > 1 {
+            noarch_dir.join(dir).join(package.name().as_ref())
+        } else {
+            noarch_dir.clone()
+        }
+    };
+    let doc_dir = Utf8Path::new("share/doc").join(package.name().as_ref());
+
+    let mut entries = vec![];
+    let mut push_if_exists =
+        |src: Utf8PathBuf, dest: Utf8PathBuf, mode: u32, kind: FhsEntryKind| {
+            if src.is_file() {
+                entries.push(FhsEntry {
+                    src,
+                    dest,
+                    mode,
+                    kind,
+                });
+            }
+        };
+
+    for target in package.targets() {
+        if !target.metadata().kind.contains(&TargetKind::Bin) {
+            continue;
+        }
+        let exe_name = if target_triple.contains("windows") {
+            format!("{}.exe", target.name())
+        } else {
+            target.name().to_owned()
+        };
+        let src = bin_dir.join(&exe_name);
+        ensure!(
+            src.is_file(),
+            "executable not found: {} (run `cargo xtask dist-build-bin` first)",
+            src.to_relative()
+        );
+        push_if_exists(
+            src,
+            Utf8Path::new("bin").join(&exe_name),
+            0o755,
+            FhsEntryKind::Executable,
+        );
+    }
+
+    let lib_dir = bin_dir.join("lib");
+    for target in package.targets() {
+        let is_library = target.metadata().kind.iter().any(|kind| {
+            matches!(
+                kind,
+                TargetKind::CDyLib | TargetKind::DyLib | TargetKind::StaticLib
+            )
+        });
+        if !is_library || !lib_dir.is_dir() {
+            continue;
+        }
+        let lib_name = target.name().replace('-', "_");
+        let mut libs = vec![];
+        for entry in lib_dir.read_dir_utf8()? {
+            let path = entry?.into_path();
+            let file_name = path.file_name().unwrap_or_default();
+            let stem = file_name.strip_prefix("lib").unwrap_or(file_name);
+            if stem
+                .strip_prefix(&lib_name)
+                .is_some_and(|rest| rest.starts_with('.'))
+                && crate::cargo::is_library_file(&path)
+            {
+                let dest = Utf8Path::new("lib").join(file_name);
+                libs.push((path, dest));
+            }
+        }
+        libs.sort();
+        for (src, dest) in libs {
+            push_if_exists(src, dest, 0o644, FhsEntryKind::Library);
+        }
+    }
+    let pc_name = format!("{}.pc", package.name());
+    push_if_exists(
+        lib_dir.join("pkgconfig").join(&pc_name),
+        Utf8Path::new("lib/pkgconfig").join(&pc_name),
+        0o644,
+        FhsEntryKind::Library,
+    );
+    #[cfg(feature = "subcommand-dist-build-bin")]
+    if let Some(header) = package.c_header().and_then(|header| header.file_name()) {
+        push_if_exists(
+            bin_dir.join("include").join(header),
+            Utf8Path::new("include").join(header),
+            0o644,
+            FhsEntryKind::Header,
+        );
+    }
+
+    let man_dir = noarch_dir.join("man");
+    let completion_dir = noarch_dir.join("completion");
+    for target in package.targets() {
+        let Some(cmd) = target.command() else {
+            continue;
+        };
+
+        if man_dir.is_dir() {
+            let command_name = cmd.get_name().replace(' ', "-");
+            let mut man_pages = vec![];
+            for entry in man_dir.read_dir_utf8()? {
+                let path = entry?.into_path();
+                let file_name = path.file_name().unwrap_or_default();
+                let Some((stem, section)) = file_name.rsplit_once('.') else {
+                    continue;
+                };
+                let is_command_page = stem == command_name
+                    || stem
+                        .strip_prefix(&command_name)
+                        .is_some_and(|rest| rest.starts_with('-'));
+                if is_command_page && section.starts_with(|c: char| c.is_ascii_digit()) {
+                    let dest = Utf8Path::new("share/man")
+                        .join(format!("man{}", &section[..1]))
+                        .join(file_name);
+                    man_pages.push((path, dest));
+                }
+            }
+            man_pages.sort();
+            for (src, dest) in man_pages {
+                push_if_exists(src, dest, 0o644, FhsEntryKind::ManPage);
+            }
+        }
+
+        let bin_name = target.name();
+        for (src_name, dest_dir, dest_name) in [
+            (
+                format!("{bin_name}.bash"),
+                "share/bash-completion/completions",
+                bin_name.to_owned(),
+            ),
+            (
+                format!("_{bin_name}"),
+                options.zsh_completion_dir,
+                format!("_{bin_name}"),
+            ),
+            (
+                format!("{bin_name}.fish"),
+                "share/fish/vendor_completions.d",
+                format!("{bin_name}.fish"),
+            ),
+        ] {
+            push_if_exists(
+                completion_dir.join(src_name),
+                Utf8Path::new(dest_dir).join(dest_name),
+                0o644,
+                FhsEntryKind::Completion,
+            );
+        }
+    }
+
+    if let Some(readme) = &package.metadata().readme {
+        let file_name = readme
+            .file_name()
+            .ok_or_else(|| eyre!("readme file has no name: {readme}"))?;
+        push_if_exists(
+            package_dir("readme").join(readme),
+            doc_dir.join(file_name),
+            0o644,
+            FhsEntryKind::Document,
+        );
+        #[cfg(feature = "subcommand-dist-build-readme")]
+        for format in package.readme_formats() {
+            let readme = readme.with_extension(format.extension());
+            let file_name = Utf8Path::new(file_name).with_extension(format.extension());
+            push_if_exists(
+                package_dir("readme").join(readme),
+                doc_dir.join(file_name),
+                0o644,
+                FhsEntryKind::Document,
+            );
+        }
+    }
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    for file in package.document_dist_files() {
+        let src_dir = if config.packages().len() > 1 {
+            noarch_dir.join("doc").join(package.name().as_ref())
+        } else {
+            noarch_dir.join("doc")
+        };
+        push_if_exists(
+            src_dir.join(file.dest()),
+            doc_dir.join(file.dest()),
+            file.mode(),
+            FhsEntryKind::Document,
+        );
+    }
+    #[cfg(feature = "subcommand-dist-build-doc")]
+    for file in package.extra_files() {
+        let kind = if file.is_config() {
+            FhsEntryKind::Config
+        } else if file.is_root_relative() {
+            FhsEntryKind::RootExtra
+        } else {
+            FhsEntryKind::Extra
+        };
+        push_if_exists(
+            noarch_dir
+                .join("extra")
+                .join(package.name().as_ref())
+                .join(file.dest()),
+            file.dest().to_owned(),
+            file.mode(),
+            kind,
+        );
+    }
+    #[cfg(feature = "subcommand-dist-build-license")]
+    for file in package.license_dist_files() {
+        push_if_exists(
+            package_dir("license").join(file.dest()),
+            doc_dir.join(file.dest()),
+            file.mode(),
+            FhsEntryKind::License,
+        );
+    }
+    #[cfg(feature = "subcommand-dist-build-license")]
+    for file_name in [
+        crate::dependency::THIRD_PARTY_LICENSES.to_owned(),
+        format!("{}.html", crate::dependency::THIRD_PARTY_LICENSES),
+    ] {
+        push_if_exists(
+            bin_dir.join(&file_name),
+            doc_dir.join(&file_name),
+            0o644,
+            FhsEntryKind::License,
+        );
+    }
+
+    Ok(entries)
+}
+
+/// Returns the timestamp to record in the packages.
+///
+/// `SOURCE_DATE_EPOCH` is used if set, so that the packages can be built
+/// reproducibly. Otherwise, the current time is used.
+#[cfg(any(
+    feature = "subcommand-dist-build-deb",
+    feature = "subcommand-dist-build-rpm"
+))]
+pub(crate) fn source_date_epoch() -> Result<u64> {
+    match crate::time::source_date_epoch()? {
+        Some(timestamp) => Ok(timestamp),
+        None => Ok(std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs()),
+    }
+}
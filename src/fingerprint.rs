@@ -0,0 +1,314 @@
+//! Fingerprints of the inputs of the `dist-build-*` steps.
+//!
+//! Each step records the fingerprint of its inputs (source files, clap command
+//! definitions and configurations) and the files it generated into the
+//! `.fingerprint` directory in the dist base working directory, and skips the
+//! generation if the inputs are unchanged since the last run.
+
+use std::{
+    hash::{Hash, Hasher},
+    io::{BufRead, BufReader, Write},
+};
+
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use sha2::{Digest, Sha256};
+
+use crate::{Result, config::DistConfig};
+
+/// Name of the directory storing the fingerprints.
+pub(crate) const DIR_NAME: &str = ".fingerprint";
+
+/// Fingerprint of the inputs of a step.
+#[derive(Debug)]
+pub(crate) struct Fingerprint {
+    step: &'static str,
+    hasher: Sha256Hasher,
+}
+
+/// [`Hasher`] feeding the hashed values into SHA-256.
+///
+/// Fingerprints are written to the disk, so the algorithm of `DefaultHasher`,
+/// which may change between Rust releases, cannot be used.
+#[derive(Debug, Default)]
+struct Sha256Hasher(Sha256);
+
+impl Hasher for Sha256Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+        u64::from_le_bytes(digest[..8].try_into().unwrap())
+    }
+}
+
+impl Fingerprint {
+    /// Creates an empty fingerprint of the given step.
+    pub(crate) fn new(step: &'static str) -> Self {
+        let mut hasher = Sha256Hasher::default();
+        // outputs may change between versions of cli-xtask
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+        Self { step, hasher }
+    }
+
+    /// Adds the value to the inputs.
+    pub(crate) fn add(&mut self, value: impl Hash) {
+        value.hash(&mut self.hasher);
+    }
+
+    /// Adds the path and the contents of the file to the inputs.
+    ///
+    /// Missing files are also recorded, so that creating them invalidates the
+    /// fingerprint.
+    #[cfg(any(
+        feature = "subcommand-dist-build-doc",
+        feature = "subcommand-dist-build-license",
+        feature = "subcommand-dist-build-readme"
+    ))]
+    pub(crate) fn add_file(&mut self, path: &Utf8Path) -> Result<()> {
+        use std::io::ErrorKind;
+
+        path.hash(&mut self.hasher);
+        match std::fs::read(path) {
+            Ok(contents) => Some(contents).hash(&mut self.hasher),
+            Err(e) if e.kind() == ErrorKind::NotFound => None::<Vec<u8>>.hash(&mut self.hasher),
+            Err(e) => return Err(e.into()),
+        }
+        Ok(())
+    }
+
+    /// Adds the definition of the command and its subcommands to the inputs.
+    #[cfg(any(
+        feature = "subcommand-dist-build-cli-doc",
+        feature = "subcommand-dist-build-completion",
+        feature = "subcommand-dist-build-man"
+    ))]
+    pub(crate) fn add_command(&mut self, cmd: &clap::Command) {
+        // `clap::Command` doesn't implement `Hash`, and its `Debug` output
+        // contains function pointers, so the rendered help is used instead
+        let mut cmd = cmd.clone();
+        self.add(cmd.get_name());
+        self.add(cmd.get_version());
+        self.add(cmd.get_long_version());
+        self.add(cmd.get_author());
+        self.add(cmd.render_long_help().to_string());
+        for subcommand in cmd.get_subcommands() {
+            self.add_command(subcommand);
+        }
+    }
+
+    fn path(&self, config: &DistConfig) -> Utf8PathBuf {
+        config
+            .dist_base_working_directory()
+            .join(DIR_NAME)
+            .join(self.step)
+    }
+
+    fn value(&self) -> String {
+        self.hasher
+            .0
+            .clone()
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+
+    /// Reads the fingerprint and the output files recorded by the last run.
+    fn read_record(&self, config: &DistConfig) -> Result<Option<(String, Vec<Utf8PathBuf>)>> {
+        read_record(config, &self.path(config))
+    }
+
+    /// Returns `true` if the inputs are unchanged since the last run, and all
+    /// the files generated by the last run still exist.
+    pub(crate) fn is_fresh(&self, config: &DistConfig) -> Result<bool> {
+        let Some((value, outputs)) = self.read_record(config)? else {
+            return Ok(false);
+        };
+        Ok(value == self.value() && outputs.iter().all(|output| output.is_file()))
+    }
+
+    /// Removes the files generated by the last run and the recorded
+    /// fingerprint.
+    pub(crate) fn remove_outputs(&self, config: &DistConfig) -> Result<()> {
+        if let Some((_, outputs)) = self.read_record(config)? {
+            for output in outputs {
+                if output.is_file() {
+                    std::fs::remove_file(output)?;
+                }
+            }
+            std::fs::remove_file(self.path(config))?;
+        }
+        Ok(())
+    }
+
+    /// Records the fingerprint and the files generated by the step.
+    pub(crate) fn save(&self, config: &DistConfig, outputs: &[Utf8PathBuf]) -> Result<()> {
+        let base_dir = config.dist_base_working_directory();
+        let mut file = crate::fs::create_file(self.path(config))?;
+        writeln!(file, "{}", self.value())?;
+        for output in outputs {
+            writeln!(file, "{}", output.strip_prefix(base_dir)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the fingerprint and the output files recorded in `path`.
+fn read_record(config: &DistConfig, path: &Utf8Path) -> Result<Option<(String, Vec<Utf8PathBuf>)>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let mut lines = BufReader::new(std::fs::File::open(path)?).lines();
+    let Some(value) = lines.next().transpose()? else {
+        return Ok(None);
+    };
+    let base_dir = config.dist_base_working_directory();
+    let outputs = lines
+        .map(|line| Ok(base_dir.join(line?)))
+        .collect::<Result<_>>()?;
+    Ok(Some((value, outputs)))
+}
+
+/// Removes all the recorded fingerprints, so that all the steps are run
+/// regardless of their inputs.
+pub(crate) fn remove_all(config: &DistConfig) -> Result<()> {
+    crate::fs::remove_dir(config.dist_base_working_directory().join(DIR_NAME))
+}
+
+/// Prepares the dist base working directory for a new distribution.
+///
+/// Artifacts of the previous distribution are removed, except the
+/// architecture-independent ones recorded in the fingerprints, which are kept
+/// to be reused unless `force` is `true`.
+#[cfg(feature = "subcommand-dist")]
+pub(crate) fn cleanup_working_directory(config: &DistConfig, force: bool) -> Result<()> {
+    use std::collections::HashSet;
+
+    use walkdir::WalkDir;
+
+    let working_dir = config.dist_base_working_directory();
+    if force || !working_dir.is_dir() {
+        return crate::fs::create_or_cleanup_dir(working_dir);
+    }
+
+    let mut outputs = HashSet::new();
+    let fingerprint_dir = working_dir.join(DIR_NAME);
+    if fingerprint_dir.is_dir() {
+        for entry in fingerprint_dir.read_dir_utf8()? {
+            if let Some((_, files)) = read_record(config, entry?.path())? {
+                outputs.extend(files);
+            }
+        }
+    }
+
+    for entry in working_dir.read_dir_utf8()? {
+        let entry = entry?;
+        if entry.file_name() == DIR_NAME {
+            continue;
+        }
+        if entry.file_name() == "noarch" {
+            // files generated by a step which is no longer run are removed
+            for entry in WalkDir::new(entry.path()).contents_first(true) {
+                let entry = entry?;
+                let path = <&Utf8Path>::try_from(entry.path())?;
+                if entry.file_type().is_dir() {
+                    // fails if the directory is not empty
+                    let _ = std::fs::remove_dir(path);
+                } else if !outputs.contains(path) {
+                    std::fs::remove_file(path)?;
+                }
+            }
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            crate::fs::remove_dir(entry.path())?;
+        } else {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::DistConfigBuilder, workspace};
+
+    #[test]
+    fn freshness() {
+        let config = DistConfigBuilder::new("fingerprint-test", workspace::current())
+            .build()
+            .unwrap();
+        let base_dir = config.dist_base_working_directory();
+        crate::fs::create_or_cleanup_dir(base_dir).unwrap();
+
+        let fingerprint = |input: &str| {
+            let mut fingerprint = Fingerprint::new("test");
+            fingerprint.add(input);
+            fingerprint
+        };
+        let output = base_dir.join("noarch/output.txt");
+
+        // no record
+        assert!(!fingerprint("a").is_fresh(&config).unwrap());
+
+        crate::fs::create_file(&output).unwrap();
+        fingerprint("a")
+            .save(&config, std::slice::from_ref(&output))
+            .unwrap();
+        assert!(fingerprint("a").is_fresh(&config).unwrap());
+        // inputs changed
+        assert!(!fingerprint("b").is_fresh(&config).unwrap());
+
+        // outputs removed
+        std::fs::remove_file(&output).unwrap();
+        assert!(!fingerprint("a").is_fresh(&config).unwrap());
+
+        crate::fs::create_file(&output).unwrap();
+        fingerprint("b").remove_outputs(&config).unwrap();
+        assert!(!output.exists());
+        assert!(!fingerprint("a").is_fresh(&config).unwrap());
+
+        fingerprint("a").save(&config, &[]).unwrap();
+        assert!(fingerprint("a").is_fresh(&config).unwrap());
+        remove_all(&config).unwrap();
+        assert!(!fingerprint("a").is_fresh(&config).unwrap());
+
+        crate::fs::remove_dir(base_dir).unwrap();
+    }
+
+    #[cfg(feature = "subcommand-dist")]
+    #[test]
+    fn cleanup() {
+        let config = DistConfigBuilder::new("fingerprint-cleanup-test", workspace::current())
+            .build()
+            .unwrap();
+        let base_dir = config.dist_base_working_directory();
+        crate::fs::create_or_cleanup_dir(base_dir).unwrap();
+
+        let recorded = base_dir.join("noarch/man/app.1");
+        let unrecorded = base_dir.join("noarch/doc/README.md");
+        let target = base_dir.join("x86_64-unknown-linux-gnu/app");
+        for path in [&recorded, &unrecorded, &target] {
+            crate::fs::create_file(path).unwrap();
+        }
+        Fingerprint::new("man")
+            .save(&config, std::slice::from_ref(&recorded))
+            .unwrap();
+
+        cleanup_working_directory(&config, false).unwrap();
+        assert!(recorded.is_file());
+        assert!(!unrecorded.exists());
+        assert!(!unrecorded.parent().unwrap().exists());
+        assert!(!target.parent().unwrap().exists());
+        assert!(base_dir.join(DIR_NAME).is_dir());
+
+        cleanup_working_directory(&config, true).unwrap();
+        assert!(!recorded.exists());
+        assert!(!base_dir.join(DIR_NAME).exists());
+
+        crate::fs::remove_dir(base_dir).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+//! Manifest recording the files installed by `cargo xtask dist-install`.
+
+use std::io::{BufRead, BufReader};
+
+use cargo_metadata::camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use eyre::ensure;
+
+use crate::{Result, fs::ToRelative};
+
+/// Manifest recording the files installed for a package.
+///
+/// The manifest is stored as `share/cli-xtask/<package>.manifest` in the
+/// installation prefix, and lists the installed files relative to the prefix,
+/// one per line, including the manifest itself.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InstallManifest {
+    pub(crate) files: Vec<Utf8PathBuf>,
+}
+
+/// Returns the directory to install files into, which is `prefix` relocated
+/// under `destdir` if specified.
+pub(crate) fn install_root(prefix: &Utf8Path, destdir: Option<&Utf8Path>) -> Utf8PathBuf {
+    let Some(destdir) = destdir else {
+        return prefix.to_owned();
+    };
+    let relative = prefix
+        .components()
+        .filter(|c| !matches!(c, Utf8Component::Prefix(_) | Utf8Component::RootDir))
+        .collect::<Utf8PathBuf>();
+    destdir.join(relative)
+}
+
+impl InstallManifest {
+    /// Returns the path of the manifest relative to the prefix.
+    pub(crate) fn path(package_name: &str) -> Utf8PathBuf {
+        Utf8Path::new("share/cli-xtask").join(format!("{package_name}.manifest"))
+    }
+
+    /// Reads the manifest of the package installed in `root`.
+    ///
+    /// Returns `None` if the package is not installed.
+    pub(crate) fn read(root: &Utf8Path, package_name: &str) -> Result<Option<Self>> {
+        let path = root.join(Self::path(package_name));
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let mut files = vec![];
+        for line in BufReader::new(std::fs::File::open(&path)?).lines() {
+            let line = line?;
+            let file = Utf8PathBuf::from(line.trim());
+            if file.as_str().is_empty() {
+                continue;
+            }
+            ensure!(
+                file.components()
+                    .all(|c| matches!(c, Utf8Component::Normal(_))),
+                "invalid path in install manifest {}: {file}",
+                path.to_relative()
+            );
+            files.push(file);
+        }
+        Ok(Some(Self { files }))
+    }
+
+    /// Writes the manifest of the package installed in `root`.
+    #[cfg(feature = "subcommand-dist-install")]
+    pub(crate) fn write(&self, root: &Utf8Path, package_name: &str) -> Result<()> {
+        use std::io::Write as _;
+
+        let mut file = crate::fs::create_file(root.join(Self::path(package_name)))?;
+        for path in &self.files {
+            writeln!(file, "{path}")?;
+        }
+        Ok(())
+    }
+
+    /// Removes the installed files listed in the manifest, and directories
+    /// left empty by the removal.
+    pub(crate) fn remove_files(
+        &self,
+        root: &Utf8Path,
+        filter: impl Fn(&Utf8Path) -> bool,
+    ) -> Result<()> {
+        for file in self.files.iter().filter(|file| filter(file)) {
+            let path = root.join(file);
+            if path.is_file() || path.is_symlink() {
+                tracing::info!("removing {}", path.to_relative());
+                std::fs::remove_file(&path)?;
+            }
+            for dir in path.ancestors().skip(1) {
+                if dir == root || !dir.starts_with(root) {
+                    break;
+                }
+                // stop at the first non-empty directory
+                if std::fs::remove_dir(dir).is_err() {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn root() {
+        let cases = [
+            ("/usr/local", None, "/usr/local"),
+            ("/usr/local", Some("/tmp/stage"), "/tmp/stage/usr/local"),
+            ("usr", Some("stage"), "stage/usr"),
+        ];
+        for (prefix, destdir, expected) in cases {
+            assert_eq!(
+                install_root(Utf8Path::new(prefix), destdir.map(Utf8Path::new)),
+                expected,
+                "prefix: {prefix}, destdir: {destdir:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "subcommand-dist-install")]
+    #[test]
+    fn round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8Path::from_path(temp_dir.path()).unwrap();
+        let manifest = InstallManifest {
+            files: vec![
+                "bin/app".into(),
+                "share/doc/app/README.md".into(),
+                InstallManifest::path("app"),
+            ],
+        };
+        for file in &manifest.files[..2] {
+            crate::fs::create_file(root.join(file)).unwrap();
+        }
+        std::fs::create_dir_all(root.join("share/doc/other")).unwrap();
+
+        assert!(InstallManifest::read(root, "app").unwrap().is_none());
+        manifest.write(root, "app").unwrap();
+        let read = InstallManifest::read(root, "app").unwrap().unwrap();
+        assert_eq!(read.files, manifest.files);
+
+        read.remove_files(root, |file| file != "bin/app").unwrap();
+        assert!(root.join("bin/app").is_file());
+        assert!(!root.join("share/doc/app").exists());
+        assert!(!root.join("share/cli-xtask").exists());
+        assert!(root.join("share/doc/other").is_dir());
+        assert!(InstallManifest::read(root, "app").unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = Utf8Path::from_path(temp_dir.path()).unwrap();
+        for file in ["/etc/passwd", "../outside", "bin/../../outside"] {
+            std::fs::create_dir_all(root.join("share/cli-xtask")).unwrap();
+            std::fs::write(root.join(InstallManifest::path("app")), file).unwrap();
+            assert!(InstallManifest::read(root, "app").is_err(), "file: {file}");
+        }
+    }
+}
@@ -8,7 +8,8 @@
 //! This crate provides the following utilities:
 //!
 //! * **[`cargo xtask dist`]** and related subcommands
-//!   * Builds a distributable tar.gz package for your bin crate.
+//!   * Builds a distributable tar.gz or zip package for your bin crate, with
+//!     checksum files and a JSON manifest describing the archives.
 //! * **[`cargo xtask lint`]** and related subcommands
 //!   * Runs the lints for your bin/lib crate.
 //!   * Integrated with [`rustdoc`], [`rustfmt`], [`clippy`],
@@ -110,6 +111,9 @@
 //! * **`error-handler`** - Enables functions for error handling in
 //!   [`error_handler`] module.
 //! * **`logger`** - Enables functions for logging in [`logger`] module.
+//! * **`config-metadata`** - Enables reading the configuration used by
+//!   [`Xtask::main`] from the `[workspace.metadata.cli-xtask]` and
+//!   `[package.metadata.cli-xtask]` tables of `Cargo.toml`.
 //!
 //! ## Subcommand features
 //!
@@ -141,19 +145,31 @@
 //! * **`subcommand-dist`** - Enables [`cargo xtask dist`].
 //! * **`subcommand-dist-archive`** - Enables [`cargo xtask dist-archive`].
 //! * **`subcommand-dist-build-bin`** - Enables [`cargo xtask dist-build-bin`].
+//! * **`subcommand-dist-build-cli-doc`** - Enables [`cargo xtask
+//!   dist-build-cli-doc`].
 //! * **`subcommand-dist-build-completion`** - Enables [`cargo xtask
 //!   dist-build-completion`].
+//! * **`subcommand-dist-build-deb`** - Enables [`cargo xtask dist-build-deb`].
 //! * **`subcommand-dist-build-doc`** - Enables [`cargo xtask dist-build-doc`].
 //! * **`subcommand-dist-build-license`** - Enables [`cargo xtask
 //!   dist-build-license`].
 //! * **`subcommand-dist-build-man`** - Enables [`cargo xtask dist-build-man`].
 //! * **`subcommand-dist-build-readme`** - Enables [`cargo xtask
 //!   dist-build-readme`].
+//! * **`subcommand-dist-build-recipes`** - Enables [`cargo xtask
+//!   dist-build-recipes`].
+//! * **`subcommand-dist-build-rpm`** - Enables [`cargo xtask dist-build-rpm`].
+//! * **`subcommand-dist-build-sbom`** - Enables [`cargo xtask
+//!   dist-build-sbom`].
 //! * **`subcommand-dist-clean`** - Enables [`cargo xtask dist-clean`].
+//! * **`subcommand-dist-install`** - Enables [`cargo xtask dist-install`].
+//! * **`subcommand-dist-uninstall`** - Enables [`cargo xtask
+//!   dist-uninstall`].
 //! * **`subcommand-doc`** - Enables [`cargo xtask doc`].
 //! * **`subcommand-docsrs`** - Enables [`cargo xtask docsrs`].
 //! * **`subcommand-exec`** - Enables [`cargo xtask exec`].
 //! * **`subcommand-fmt`** - Enables [`cargo xtask fmt`].
+//! * **`subcommand-license-check`** - Enables [`cargo xtask license-check`].
 //! * **`subcommand-lint`** - Enables [`cargo xtask lint`].
 //! * **`subcommand-pre-release`** - Enables [`cargo xtask pre-release`].
 //! * **`subcommand-test`** - Enables [`cargo xtask test`].
@@ -170,6 +186,9 @@
 //!
 //! * **`archive`** - Enables [`archive`] module which provides the
 //!   functionality to create the archive file for distribution.
+//! * **`archive-xz`** - Enables xz compression of the archive file.
+//! * **`archive-zstd`** - Enables zstd compression of the archive file.
+//! * **`archive-bzip2`** - Enables bzip2 compression of the archive file.
 //!
 //! # Minimum supported Rust version (MSRV)
 //!
@@ -204,16 +223,24 @@
 //! [`cargo xtask dist`]: subcommand::Dist
 //! [`cargo xtask dist-archive`]: subcommand::DistArchive
 //! [`cargo xtask dist-build-bin`]: subcommand::DistBuildBin
+//! [`cargo xtask dist-build-cli-doc`]: subcommand::DistBuildCliDoc
 //! [`cargo xtask dist-build-completion`]: subcommand::DistBuildCompletion
+//! [`cargo xtask dist-build-deb`]: subcommand::DistBuildDeb
 //! [`cargo xtask dist-build-doc`]: subcommand::DistBuildDoc
 //! [`cargo xtask dist-build-license`]: subcommand::DistBuildLicense
 //! [`cargo xtask dist-build-man`]: subcommand::DistBuildMan
 //! [`cargo xtask dist-build-readme`]: subcommand::DistBuildReadme
+//! [`cargo xtask dist-build-recipes`]: subcommand::DistBuildRecipes
+//! [`cargo xtask dist-build-rpm`]: subcommand::DistBuildRpm
+//! [`cargo xtask dist-build-sbom`]: subcommand::DistBuildSbom
 //! [`cargo xtask dist-clean`]: subcommand::DistClean
+//! [`cargo xtask dist-install`]: subcommand::DistInstall
+//! [`cargo xtask dist-uninstall`]: subcommand::DistUninstall
 //! [`cargo xtask doc`]: subcommand::Doc
 //! [`cargo xtask docsrs`]: subcommand::Docsrs
 //! [`cargo xtask exec`]: subcommand::Exec
 //! [`cargo xtask fmt`]: subcommand::Fmt
+//! [`cargo xtask license-check`]: subcommand::LicenseCheck
 //! [`cargo xtask lint`]: subcommand::Lint
 //! [`cargo xtask pre-release`]: subcommand::PreRelease
 //! [`cargo xtask sync-rdme`]: subcommand::SyncRdme
@@ -236,6 +263,9 @@ use std::{any::Any, fmt};
 
 pub use cargo_metadata::{self, camino};
 pub use clap;
+#[cfg(feature = "subcommand-dist-build-completion")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-completion")))]
+pub use clap_complete;
 #[cfg(feature = "error-handler")]
 #[cfg_attr(docsrs, doc(cfg(feature = "error-handler")))]
 pub use color_eyre;
@@ -259,12 +289,69 @@ pub mod logger;
 pub mod archive;
 
 pub mod args;
+#[cfg(any(
+    feature = "subcommand-dist-archive",
+    feature = "subcommand-dist-build-bin"
+))]
+mod build_info;
 pub mod cargo;
 mod command;
+#[cfg(any(
+    feature = "subcommand-dist-build-cli-doc",
+    feature = "subcommand-dist-build-man"
+))]
+mod command_tree;
+#[cfg(feature = "subcommand-dist-build-completion")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-completion")))]
+pub mod completion;
 pub mod config;
+#[cfg(any(
+    feature = "subcommand-dist-build-license",
+    feature = "subcommand-dist-build-sbom",
+    feature = "subcommand-license-check"
+))]
+mod dependency;
+#[cfg(feature = "subcommand-dist-archive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-archive")))]
+pub mod dist_manifest;
+#[cfg(any(
+    feature = "subcommand-dist-build-deb",
+    feature = "subcommand-dist-build-recipes",
+    feature = "subcommand-dist-build-rpm",
+    feature = "subcommand-dist-install"
+))]
+mod fhs;
+#[cfg(dist_fingerprint)]
+mod fingerprint;
 pub mod fs;
+#[cfg(any(
+    feature = "subcommand-dist-install",
+    feature = "subcommand-dist-uninstall"
+))]
+mod install_manifest;
 pub mod process;
+#[cfg(feature = "subcommand-dist-build-readme")]
+mod readme;
+#[cfg(any(
+    feature = "subcommand-dist-archive",
+    feature = "subcommand-dist-build-sbom"
+))]
+mod sbom;
+#[cfg(any(
+    feature = "subcommand-dist-build-deb",
+    feature = "subcommand-dist-build-sbom",
+    feature = "subcommand-license-check"
+))]
+mod spdx;
 pub mod subcommand;
+#[cfg(any(
+    feature = "archive",
+    feature = "subcommand-dist-build-deb",
+    feature = "subcommand-dist-build-man",
+    feature = "subcommand-dist-build-rpm",
+    feature = "subcommand-dist-build-sbom"
+))]
+mod time;
 pub mod workspace;
 
 pub use self::command::Xtask;
@@ -0,0 +1,553 @@
+//! Transformations of the README files of the packages.
+
+use std::{collections::HashSet, ops::Range};
+
+use pulldown_cmark::{CodeBlockKind, Event, LinkType, Options, Parser, Tag, TagEnd};
+
+fn parser(source: &str) -> Parser<'_> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    Parser::new_ext(source, options)
+}
+
+/// Removes the sections with the given titles and, if `badges` is `true`,
+/// the paragraphs consisting only of images.
+pub(crate) fn strip(source: &str, sections: &[String], badges: bool) -> String {
+    let mut removed = vec![];
+
+    // heading level and start offset of the section being removed
+    let mut section: Option<(usize, usize)> = None;
+    // heading level, start offset and text of the heading being read
+    let mut heading: Option<(usize, usize, String)> = None;
+    // start offset of the paragraph being read, and whether it is a badge
+    // paragraph so far
+    let mut paragraph: Option<(usize, bool)> = None;
+    let mut has_image = false;
+    let mut image_depth = 0;
+
+    for (event, range) in parser(source).into_offset_iter() {
+        match &event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                let level = *level as usize;
+                if let Some((section_level, start)) = section
+                    && level <= section_level
+                {
+                    removed.push(start..range.start);
+                    section = None;
+                }
+                heading = Some((level, range.start, String::new()));
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, start, text)) = heading.take()
+                    && section.is_none()
+                    && sections
+                        .iter()
+                        .any(|title| title.trim().eq_ignore_ascii_case(text.trim()))
+                {
+                    section = Some((level, start));
+                }
+            }
+            Event::Text(text) | Event::Code(text) if heading.is_some() => {
+                if let Some((_, _, heading)) = &mut heading {
+                    heading.push_str(text);
+                }
+            }
+            _ => {}
+        }
+
+        if !badges {
+            continue;
+        }
+        match event {
+            Event::Start(Tag::Paragraph) => {
+                paragraph = Some((range.start, true));
+                has_image = false;
+            }
+            Event::End(TagEnd::Paragraph) => {
+                if let Some((start, true)) = paragraph.take()
+                    && has_image
+                {
+                    removed.push(start..block_end(source, range.end));
+                }
+            }
+            Event::Start(Tag::Image { .. }) => {
+                image_depth += 1;
+                has_image = true;
+            }
+            Event::End(TagEnd::Image) => image_depth -= 1,
+            Event::Start(Tag::Link { .. })
+            | Event::End(TagEnd::Link)
+            | Event::SoftBreak
+            | Event::HardBreak => {}
+            Event::Text(_) if image_depth > 0 => {}
+            Event::Text(text) if text.trim().is_empty() => {}
+            _ => {
+                if let Some((_, is_badge)) = &mut paragraph {
+                    *is_badge = false;
+                }
+            }
+        }
+    }
+    if let Some((_, start)) = section {
+        removed.push(start..source.len());
+    }
+
+    replace_ranges(
+        source,
+        removed.into_iter().map(|range| (range, String::new())),
+    )
+}
+
+/// Rewrites the relative destinations of the links and images to absolute
+/// URLs.
+///
+/// The destinations are resolved against `base_dir`, the directory of the
+/// README file relative to the repository root, and appended to `link_base`
+/// (or `image_base` for images).
+pub(crate) fn rewrite_links(
+    source: &str,
+    base_dir: &str,
+    link_base: &str,
+    image_base: &str,
+) -> String {
+    let url = |dest: &str, image: bool| {
+        let base = if image { image_base } else { link_base };
+        let path = resolve_path(base_dir, dest);
+        format!("{}/{path}", base.trim_end_matches('/'))
+    };
+
+    let mut iter = parser(source).into_offset_iter();
+    let events = iter.by_ref().collect::<Vec<_>>();
+    let definitions = iter.reference_definitions();
+
+    let mut replaced = vec![];
+    let mut image_definitions = HashSet::new();
+    for (event, range) in events {
+        let (image, link_type, dest, id) = match event {
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                id,
+                ..
+            }) => (false, link_type, dest_url, id),
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                id,
+                ..
+            }) => (true, link_type, dest_url, id),
+            _ => continue,
+        };
+        match link_type {
+            LinkType::Inline => {
+                if !is_relative_url(&dest) {
+                    continue;
+                }
+                // the destination follows the last `](` of the link
+                let Some(pos) = source[range.clone()].rfind("](") else {
+                    continue;
+                };
+                let start = range.start + pos + 2;
+                if let Some(dest_range) = destination(&source[start..range.end]) {
+                    let dest_range = start + dest_range.start..start + dest_range.end;
+                    replaced.push((dest_range, url(&dest, image)));
+                }
+            }
+            LinkType::Reference | LinkType::Collapsed | LinkType::Shortcut if image => {
+                if let Some(definition) = definitions.get(&id) {
+                    image_definitions.insert(definition.span.start);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (_label, definition) in definitions.iter() {
+        if !is_relative_url(&definition.dest) {
+            continue;
+        }
+        let span = definition.span.clone();
+        let Some(pos) = source[span.clone()].find("]:") else {
+            continue;
+        };
+        let start = span.start + pos + 2;
+        if let Some(dest_range) = destination(&source[start..span.end]) {
+            let dest_range = start + dest_range.start..start + dest_range.end;
+            let image = image_definitions.contains(&span.start);
+            replaced.push((dest_range, url(&definition.dest, image)));
+        }
+    }
+
+    replace_ranges(source, replaced)
+}
+
+/// Renders the markdown into plain text.
+pub(crate) fn to_plain_text(source: &str) -> String {
+    let mut writer = PlainTextWriter::default();
+    for event in parser(source) {
+        writer.event(event);
+    }
+    let mut output = writer.output.trim_end().to_owned();
+    output.push('\n');
+    output
+}
+
+/// Renders the markdown into a standalone HTML document.
+pub(crate) fn to_html(source: &str, title: &str) -> String {
+    let mut body = String::new();
+    pulldown_cmark::html::push_html(&mut body, parser(source));
+
+    let title = title
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n{body}</body>\n</html>\n"
+    )
+}
+
+/// Returns `true` if the URL is relative to the location of the document.
+///
+/// URLs with schemes, protocol-relative URLs and fragments of the document
+/// itself are not relative.
+fn is_relative_url(url: &str) -> bool {
+    if url.is_empty() || url.starts_with('#') || url.starts_with("//") {
+        return false;
+    }
+    let has_scheme = url.split_once(':').is_some_and(|(scheme, _)| {
+        let mut chars = scheme.chars();
+        chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    !has_scheme
+}
+
+/// Resolves the relative URL against `base_dir`, returning the path from the
+/// repository root with the query and the fragment of the URL.
+fn resolve_path(base_dir: &str, url: &str) -> String {
+    let (path, suffix) = url.split_at(url.find(['?', '#']).unwrap_or(url.len()));
+    let (base_dir, path) = match path.strip_prefix('/') {
+        Some(path) => ("", path),
+        None => (base_dir, path),
+    };
+
+    let mut components = vec![];
+    for component in base_dir.split('/').chain(path.split('/')) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            _ => components.push(component),
+        }
+    }
+    let mut resolved = components.join("/");
+    if path.ends_with('/') && !resolved.is_empty() {
+        resolved.push('/');
+    }
+    resolved.push_str(suffix);
+    resolved
+}
+
+/// Returns the range of the link destination at the start of `s`, skipping
+/// the leading whitespaces.
+///
+/// For destinations enclosed in `<` and `>`, the range excludes the brackets.
+fn destination(s: &str) -> Option<Range<usize>> {
+    let start = s.len() - s.trim_start().len();
+    let rest = &s[start..];
+    if let Some(inner) = rest.strip_prefix('<') {
+        let end = inner.find('>')?;
+        return Some(start + 1..start + 1 + end);
+    }
+
+    let mut depth = 0;
+    let mut escaped = false;
+    for (i, c) in rest.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(start..start + i),
+            ')' => depth -= 1,
+            _ if c.is_whitespace() => return Some(start..start + i),
+            _ => {}
+        }
+    }
+    Some(start..s.len())
+}
+
+/// Returns the offset of the first non-blank line after the line containing
+/// `pos`.
+fn block_end(source: &str, pos: usize) -> usize {
+    let mut end = source[pos..]
+        .find('\n')
+        .map_or(source.len(), |i| pos + i + 1);
+    while let Some(len) = source[end..].find('\n')
+        && source[end..end + len].trim().is_empty()
+    {
+        end += len + 1;
+    }
+    end
+}
+
+fn replace_ranges(
+    source: &str,
+    replacements: impl IntoIterator<Item = (Range<usize>, String)>,
+) -> String {
+    let mut replacements = replacements.into_iter().collect::<Vec<_>>();
+    replacements.sort_by_key(|(range, _)| (range.start, range.end));
+
+    let mut output = String::new();
+    let mut pos = 0;
+    for (range, replacement) in replacements {
+        // skip the replacements overlapping with the previous one
+        if range.start < pos {
+            continue;
+        }
+        output.push_str(&source[pos..range.start]);
+        output.push_str(&replacement);
+        pos = range.end;
+    }
+    output.push_str(&source[pos..]);
+    output
+}
+
+#[derive(Debug, Default)]
+struct PlainTextWriter {
+    output: String,
+    /// Prefix of the lines in the current block (e.g. indentation of list
+    /// items)
+    indent: String,
+    /// Next numbers of the ordered lists, or `None` for the unordered lists
+    lists: Vec<Option<u64>>,
+    /// Destinations of the links being written
+    links: Vec<String>,
+    image_depth: usize,
+    at_line_start: bool,
+}
+
+impl PlainTextWriter {
+    fn write(&mut self, s: &str) {
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.newline();
+            }
+            if line.is_empty() {
+                continue;
+            }
+            if self.at_line_start {
+                self.output.push_str(&self.indent);
+                self.at_line_start = false;
+            }
+            self.output.push_str(line);
+        }
+    }
+
+    fn newline(&mut self) {
+        self.output.push('\n');
+        self.at_line_start = true;
+    }
+
+    fn end_line(&mut self) {
+        if !self.output.is_empty() && !self.at_line_start {
+            self.newline();
+        }
+    }
+
+    fn end_block(&mut self) {
+        self.end_line();
+        if !self.output.is_empty() && !self.output.ends_with("\n\n") {
+            self.newline();
+        }
+    }
+
+    fn event(&mut self, event: Event<'_>) {
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::Heading { .. } | Tag::Paragraph | Tag::Table(_) => {}
+                Tag::CodeBlock(kind) => {
+                    if let CodeBlockKind::Fenced(_) = kind {
+                        self.end_line();
+                    }
+                    self.indent.push_str("    ");
+                }
+                Tag::BlockQuote(_) => self.indent.push_str("  "),
+                Tag::List(first) => {
+                    self.end_line();
+                    self.lists.push(first);
+                }
+                Tag::Item => {
+                    self.end_line();
+                    let marker = match self.lists.last_mut() {
+                        Some(Some(n)) => {
+                            *n += 1;
+                            format!("{}. ", *n - 1)
+                        }
+                        _ => "* ".to_owned(),
+                    };
+                    self.write(&marker);
+                    self.indent.push_str(&" ".repeat(marker.len()));
+                }
+                Tag::Link { dest_url, .. } => self.links.push(dest_url.into_string()),
+                Tag::Image { .. } => self.image_depth += 1,
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                TagEnd::Heading(_) | TagEnd::Paragraph | TagEnd::Table => self.end_block(),
+                TagEnd::CodeBlock => {
+                    self.truncate_indent(4);
+                    self.end_block();
+                }
+                TagEnd::BlockQuote(_) => {
+                    self.truncate_indent(2);
+                    self.end_block();
+                }
+                TagEnd::List(_) => {
+                    self.lists.pop();
+                    if self.lists.is_empty() {
+                        self.end_block();
+                    }
+                }
+                TagEnd::Item => {
+                    let marker_len = match self.lists.last() {
+                        Some(Some(n)) => format!("{}. ", n - 1).len(),
+                        _ => 2,
+                    };
+                    self.truncate_indent(marker_len);
+                    self.end_line();
+                }
+                TagEnd::TableHead | TagEnd::TableRow => self.end_line(),
+                TagEnd::TableCell => self.write("\t"),
+                TagEnd::Link => {
+                    if let Some(dest) = self.links.pop()
+                        && !dest.is_empty()
+                        && !is_relative_url(&dest)
+                        && !dest.starts_with('#')
+                        && !self.output.ends_with(dest.as_str())
+                    {
+                        self.write(&format!(" <{dest}>"));
+                    }
+                }
+                TagEnd::Image => self.image_depth -= 1,
+                _ => {}
+            },
+            Event::Text(text) | Event::Code(text) => self.write(&text),
+            Event::SoftBreak | Event::HardBreak => self.newline(),
+            Event::Rule => {
+                self.write("----");
+                self.end_block();
+            }
+            Event::TaskListMarker(checked) => self.write(if checked { "[x] " } else { "[ ] " }),
+            Event::FootnoteReference(label) => self.write(&format!("[{label}]")),
+            _ => {}
+        }
+    }
+
+    fn truncate_indent(&mut self, len: usize) {
+        self.indent.truncate(self.indent.len().saturating_sub(len));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_sections_and_badges() {
+        let source = "\
+# app
+
+[![CI](https://example.com/ci.svg)](https://example.com/ci)
+[![docs](https://example.com/docs.svg)](https://example.com/docs)
+
+Text with ![an image](image.png).
+
+## Installation
+
+Run the installer.
+
+### Details
+
+More details.
+
+## Usage
+
+Run `app`.
+";
+        let expected = "\
+# app
+
+Text with ![an image](image.png).
+
+## Usage
+
+Run `app`.
+";
+        assert_eq!(strip(source, &["installation".to_owned()], true), expected);
+        assert_eq!(strip(source, &[], false), source);
+    }
+
+    #[test]
+    fn rewrite() {
+        let source = "\
+[guide](docs/guide.md#usage) [top](#top) [site](https://example.com)
+![logo](../assets/logo.png \"Logo\") [root](/LICENSE) [<sp>](<my file.md>)
+![ref image][logo] [ref link]
+
+[logo]: ./assets/logo.svg
+[ref link]: CHANGELOG.md
+";
+        let expected = "\
+[guide](https://example.com/blob/v1/app/docs/guide.md#usage) [top](#top) [site](https://example.com)
+![logo](https://example.com/raw/v1/assets/logo.png \"Logo\") [root](https://example.com/blob/v1/LICENSE) [<sp>](<https://example.com/blob/v1/app/my file.md>)
+![ref image][logo] [ref link]
+
+[logo]: https://example.com/raw/v1/app/assets/logo.svg
+[ref link]: https://example.com/blob/v1/app/CHANGELOG.md
+";
+        assert_eq!(
+            rewrite_links(
+                source,
+                "app",
+                "https://example.com/blob/v1",
+                "https://example.com/raw/v1/"
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn plain_text() {
+        let source = "\
+# Title
+
+Some *text* with [a link](https://example.com) and `code`.
+
+* item 1
+  continued
+* item 2
+  1. nested
+
+```
+let a = 1;
+```
+";
+        let expected = "\
+Title
+
+Some text with a link <https://example.com> and code.
+
+* item 1
+  continued
+* item 2
+  1. nested
+
+    let a = 1;
+";
+        assert_eq!(to_plain_text(source), expected);
+    }
+}
@@ -0,0 +1,32 @@
+//! Software bill of materials (SBOM) documents of the distributed packages.
+
+/// Format of an SBOM document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SbomFormat {
+    /// CycloneDX JSON
+    CycloneDx,
+    /// SPDX JSON
+    Spdx,
+}
+
+impl SbomFormat {
+    /// All the formats the SBOM documents are written in.
+    pub(crate) const ALL: [Self; 2] = [Self::CycloneDx, Self::Spdx];
+
+    /// Returns the name of the format recorded in the dist manifest.
+    #[cfg(feature = "subcommand-dist-archive")]
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::CycloneDx => "cyclonedx-json",
+            Self::Spdx => "spdx-json",
+        }
+    }
+
+    /// Returns the file name of the SBOM document of the package.
+    pub(crate) fn file_name(self, package_name: &str) -> String {
+        match self {
+            Self::CycloneDx => format!("{package_name}.cdx.json"),
+            Self::Spdx => format!("{package_name}.spdx.json"),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! SPDX license expressions.
+
+use std::{fmt, str::FromStr};
+
+use eyre::{bail, eyre};
+
+use crate::{Error, Result};
+
+/// Parsed SPDX license expression.
+///
+/// `WITH` binds tighter than `AND`, which binds tighter than `OR`. The legacy
+/// `/` separator used by old crates is treated as `OR`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Expression {
+    /// License identifier, with an optional exception
+    License {
+        id: String,
+        exception: Option<String>,
+    },
+    /// Both of the expressions apply
+    And(Box<Expression>, Box<Expression>),
+    /// Either of the expressions applies
+    Or(Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    /// Returns `true` if the expression is satisfied when the licenses
+    /// accepted by `accept` are acceptable.
+    ///
+    /// `accept` is called with each license identifier and its exception.
+    #[cfg(feature = "subcommand-license-check")]
+    pub(crate) fn evaluate(&self, accept: &mut impl FnMut(&str, Option<&str>) -> bool) -> bool {
+        match self {
+            Self::License { id, exception } => accept(id, exception.as_deref()),
+            Self::And(lhs, rhs) => lhs.evaluate(accept) && rhs.evaluate(accept),
+            Self::Or(lhs, rhs) => lhs.evaluate(accept) || rhs.evaluate(accept),
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::License {
+                id,
+                exception: None,
+            } => write!(f, "{id}"),
+            Self::License {
+                id,
+                exception: Some(exception),
+            } => write!(f, "{id} WITH {exception}"),
+            Self::And(lhs, rhs) => {
+                for (i, expr) in [lhs, rhs].into_iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " AND ")?;
+                    }
+                    match **expr {
+                        Self::Or(..) => write!(f, "({expr})")?,
+                        _ => write!(f, "{expr}")?,
+                    }
+                }
+                Ok(())
+            }
+            Self::Or(lhs, rhs) => write!(f, "{lhs} OR {rhs}"),
+        }
+    }
+}
+
+impl FromStr for Expression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(s),
+            pos: 0,
+        };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            bail!("unexpected `{token}` in license expression `{s}`");
+        }
+        Ok(expr)
+    }
+}
+
+fn tokenize(s: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    let mut start = None;
+    for (i, c) in s.char_indices() {
+        if c.is_whitespace() || matches!(c, '(' | ')' | '/') {
+            if let Some(start) = start.take() {
+                tokens.push(&s[start..i]);
+            }
+            match c {
+                '(' => tokens.push("("),
+                ')' => tokens.push(")"),
+                '/' => tokens.push("OR"),
+                _ => {}
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(start) = start {
+        tokens.push(&s[start..]);
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn next_if(&mut self, token: &str) -> bool {
+        let matched = self.tokens.get(self.pos) == Some(&token);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn next_id(&mut self) -> Result<String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .ok_or_else(|| eyre!("unexpected end of license expression"))?;
+        let is_id = token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '+' | ':'));
+        if !is_id || matches!(*token, "(" | ")" | "AND" | "OR" | "WITH") {
+            bail!("expected license identifier, found `{token}`");
+        }
+        self.pos += 1;
+        Ok(token.to_string())
+    }
+
+    fn parse_or(&mut self) -> Result<Expression> {
+        let mut expr = self.parse_and()?;
+        while self.next_if("OR") {
+            expr = Expression::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression> {
+        let mut expr = self.parse_term()?;
+        while self.next_if("AND") {
+            expr = Expression::And(Box::new(expr), Box::new(self.parse_term()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_term(&mut self) -> Result<Expression> {
+        if self.next_if("(") {
+            let expr = self.parse_or()?;
+            if !self.next_if(")") {
+                bail!("unclosed parenthesis in license expression");
+            }
+            return Ok(expr);
+        }
+        let id = self.next_id()?;
+        let exception = if self.next_if("WITH") {
+            Some(self.next_id()?)
+        } else {
+            None
+        };
+        Ok(Expression::License { id, exception })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let cases = [
+            ("MIT", "MIT"),
+            ("MIT OR Apache-2.0", "MIT OR Apache-2.0"),
+            ("MIT/Apache-2.0", "MIT OR Apache-2.0"),
+            (
+                "(MIT OR Apache-2.0) AND Unicode-3.0",
+                "(MIT OR Apache-2.0) AND Unicode-3.0",
+            ),
+            (
+                "Apache-2.0 WITH LLVM-exception OR MIT AND BSD-3-Clause",
+                "Apache-2.0 WITH LLVM-exception OR MIT AND BSD-3-Clause",
+            ),
+            ("LicenseRef-ring", "LicenseRef-ring"),
+        ];
+        for (input, expected) in cases {
+            let expr = input.parse::<Expression>().unwrap();
+            assert_eq!(expr.to_string(), expected, "input: {input}");
+        }
+
+        for input in ["", "MIT OR", "(MIT", "MIT Apache-2.0", "MIT WITH", "AND"] {
+            assert!(input.parse::<Expression>().is_err(), "input: {input}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "subcommand-license-check")]
+    fn evaluate() {
+        let mut accept = |id: &str, exception: Option<&str>| {
+            matches!(
+                (id, exception),
+                ("MIT", None) | ("Apache-2.0", Some("LLVM-exception"))
+            )
+        };
+        let cases = [
+            ("MIT", true),
+            ("GPL-3.0", false),
+            ("MIT OR GPL-3.0", true),
+            ("MIT AND GPL-3.0", false),
+            ("(MIT OR GPL-3.0) AND Apache-2.0 WITH LLVM-exception", true),
+            ("Apache-2.0", false),
+        ];
+        for (input, expected) in cases {
+            let expr = input.parse::<Expression>().unwrap();
+            assert_eq!(expr.evaluate(&mut accept), expected, "input: {input}");
+        }
+    }
+}
@@ -36,6 +36,13 @@ mod dist_build_bin;
 #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
 pub use self::dist_build_bin::DistBuildBin;
 
+#[cfg(feature = "subcommand-dist-build-cli-doc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-cli-doc")))]
+mod dist_build_cli_doc;
+#[cfg(feature = "subcommand-dist-build-cli-doc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-cli-doc")))]
+pub use self::dist_build_cli_doc::DistBuildCliDoc;
+
 #[cfg(feature = "subcommand-dist-build-completion")]
 #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-completion")))]
 mod dist_build_completion;
@@ -43,6 +50,13 @@ mod dist_build_completion;
 #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-completion")))]
 pub use self::dist_build_completion::DistBuildCompletion;
 
+#[cfg(feature = "subcommand-dist-build-deb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-deb")))]
+mod dist_build_deb;
+#[cfg(feature = "subcommand-dist-build-deb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-deb")))]
+pub use self::dist_build_deb::DistBuildDeb;
+
 #[cfg(feature = "subcommand-dist-build-doc")]
 #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-doc")))]
 mod dist_build_doc;
@@ -71,6 +85,27 @@ mod dist_build_readme;
 #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
 pub use self::dist_build_readme::DistBuildReadme;
 
+#[cfg(feature = "subcommand-dist-build-recipes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-recipes")))]
+mod dist_build_recipes;
+#[cfg(feature = "subcommand-dist-build-recipes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-recipes")))]
+pub use self::dist_build_recipes::DistBuildRecipes;
+
+#[cfg(feature = "subcommand-dist-build-rpm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-rpm")))]
+mod dist_build_rpm;
+#[cfg(feature = "subcommand-dist-build-rpm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-rpm")))]
+pub use self::dist_build_rpm::DistBuildRpm;
+
+#[cfg(feature = "subcommand-dist-build-sbom")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-sbom")))]
+mod dist_build_sbom;
+#[cfg(feature = "subcommand-dist-build-sbom")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-sbom")))]
+pub use self::dist_build_sbom::DistBuildSbom;
+
 #[cfg(feature = "subcommand-dist-clean")]
 #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-clean")))]
 mod dist_clean;
@@ -78,6 +113,20 @@ mod dist_clean;
 #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-clean")))]
 pub use self::dist_clean::DistClean;
 
+#[cfg(feature = "subcommand-dist-install")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-install")))]
+mod dist_install;
+#[cfg(feature = "subcommand-dist-install")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-install")))]
+pub use self::dist_install::DistInstall;
+
+#[cfg(feature = "subcommand-dist-uninstall")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-uninstall")))]
+mod dist_uninstall;
+#[cfg(feature = "subcommand-dist-uninstall")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-uninstall")))]
+pub use self::dist_uninstall::DistUninstall;
+
 #[cfg(feature = "subcommand-dist")]
 #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist")))]
 mod dist;
@@ -113,6 +162,13 @@ mod fmt;
 #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-fmt")))]
 pub use self::fmt::Fmt;
 
+#[cfg(feature = "subcommand-license-check")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-license-check")))]
+mod license_check;
+#[cfg(feature = "subcommand-license-check")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subcommand-license-check")))]
+pub use self::license_check::LicenseCheck;
+
 #[cfg(feature = "subcommand-lint")]
 #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-lint")))]
 mod lint;
@@ -233,12 +289,22 @@ pub enum Subcommand {
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-bin")))]
     DistBuildBin(DistBuildBin),
 
+    /// Build the command line reference in markdown for distribution.
+    #[cfg(feature = "subcommand-dist-build-cli-doc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-cli-doc")))]
+    DistBuildCliDoc(DistBuildCliDoc),
+
     /// Build the shell completion files for distribution.
     #[cfg(feature = "subcommand-dist-build-completion")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-completion")))]
     DistBuildCompletion(DistBuildCompletion),
 
-    /// Build the documentation for distribution.
+    /// Build the Debian packages for distribution.
+    #[cfg(feature = "subcommand-dist-build-deb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-deb")))]
+    DistBuildDeb(DistBuildDeb),
+
+    /// Build the documentation and extra files for distribution.
     #[cfg(feature = "subcommand-dist-build-doc")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-doc")))]
     DistBuildDoc(DistBuildDoc),
@@ -258,11 +324,36 @@ pub enum Subcommand {
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
     DistBuildReadme(DistBuildReadme),
 
+    /// Build the package manager recipes for distribution.
+    #[cfg(feature = "subcommand-dist-build-recipes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-recipes")))]
+    DistBuildRecipes(DistBuildRecipes),
+
+    /// Build the RPM packages for distribution.
+    #[cfg(feature = "subcommand-dist-build-rpm")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-rpm")))]
+    DistBuildRpm(DistBuildRpm),
+
+    /// Build the software bill of materials (SBOM) for distribution.
+    #[cfg(feature = "subcommand-dist-build-sbom")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-sbom")))]
+    DistBuildSbom(DistBuildSbom),
+
     /// Remove the artifacts and archives for distribution.
     #[cfg(feature = "subcommand-dist-clean")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-clean")))]
     DistClean(DistClean),
 
+    /// Install the artifacts for distribution into a prefix.
+    #[cfg(feature = "subcommand-dist-install")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-install")))]
+    DistInstall(DistInstall),
+
+    /// Uninstall the artifacts installed by `dist-install`.
+    #[cfg(feature = "subcommand-dist-uninstall")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-uninstall")))]
+    DistUninstall(DistUninstall),
+
     /// `cargo doc` with options useful for testing and continuous integration.
     #[cfg(feature = "subcommand-doc")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-doc")))]
@@ -284,6 +375,11 @@ pub enum Subcommand {
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-fmt")))]
     Fmt(Fmt),
 
+    /// Check the licenses of the dependencies against the license policy.
+    #[cfg(feature = "subcommand-license-check")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-license-check")))]
+    LicenseCheck(LicenseCheck),
+
     /// Run lint commands at once.
     #[cfg(feature = "subcommand-lint")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-lint")))]
@@ -345,9 +441,15 @@ impl Subcommand {
             #[cfg(feature = "subcommand-dist-build-bin")]
             Self::DistBuildBin(args) => args,
 
+            #[cfg(feature = "subcommand-dist-build-cli-doc")]
+            Self::DistBuildCliDoc(args) => args,
+
             #[cfg(feature = "subcommand-dist-build-completion")]
             Self::DistBuildCompletion(args) => args,
 
+            #[cfg(feature = "subcommand-dist-build-deb")]
+            Self::DistBuildDeb(args) => args,
+
             #[cfg(feature = "subcommand-dist-build-doc")]
             Self::DistBuildDoc(args) => args,
 
@@ -360,9 +462,24 @@ impl Subcommand {
             #[cfg(feature = "subcommand-dist-build-readme")]
             Self::DistBuildReadme(args) => args,
 
+            #[cfg(feature = "subcommand-dist-build-recipes")]
+            Self::DistBuildRecipes(args) => args,
+
+            #[cfg(feature = "subcommand-dist-build-rpm")]
+            Self::DistBuildRpm(args) => args,
+
+            #[cfg(feature = "subcommand-dist-build-sbom")]
+            Self::DistBuildSbom(args) => args,
+
             #[cfg(feature = "subcommand-dist-clean")]
             Self::DistClean(args) => args,
 
+            #[cfg(feature = "subcommand-dist-install")]
+            Self::DistInstall(args) => args,
+
+            #[cfg(feature = "subcommand-dist-uninstall")]
+            Self::DistUninstall(args) => args,
+
             #[cfg(feature = "subcommand-doc")]
             Self::Doc(args) => args,
 
@@ -375,6 +492,9 @@ impl Subcommand {
             #[cfg(feature = "subcommand-fmt")]
             Self::Fmt(args) => args,
 
+            #[cfg(feature = "subcommand-license-check")]
+            Self::LicenseCheck(args) => args,
+
             #[cfg(feature = "subcommand-lint")]
             Self::Lint(args) => args,
 
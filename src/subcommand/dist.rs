@@ -13,6 +13,11 @@ pub struct Dist {
     /// Arguments for the `dist-archive` subcommand.
     #[clap(flatten)]
     pub dist_archive_args: super::DistArchive,
+    /// Arguments for the `dist-build-recipes` subcommand.
+    #[cfg(feature = "subcommand-dist-build-recipes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-recipes")))]
+    #[clap(flatten)]
+    pub dist_build_recipes_args: super::DistBuildRecipes,
 }
 
 impl Run for Dist {
@@ -29,17 +34,28 @@ impl Dist {
             #[cfg(subcommand_dist_build)]
             dist_build_args,
             dist_archive_args,
+            #[cfg(feature = "subcommand-dist-build-recipes")]
+            dist_build_recipes_args,
         } = self;
         let dist_config = config.dist()?;
 
-        let working_dir = dist_config.dist_base_working_directory();
-        crate::fs::create_or_cleanup_dir(working_dir)?;
+        #[cfg(dist_fingerprint)]
+        crate::fingerprint::cleanup_working_directory(
+            dist_config,
+            dist_build_args.fingerprint_args.force,
+        )?;
+        #[cfg(not(dist_fingerprint))]
+        crate::fs::create_or_cleanup_dir(dist_config.dist_base_working_directory())?;
 
         #[cfg(subcommand_dist_build)]
         dist_build_args.run(config)?;
 
         dist_archive_args.run(config)?;
 
+        // recipes refer to the archives created above
+        #[cfg(feature = "subcommand-dist-build-recipes")]
+        dist_build_recipes_args.run(config)?;
+
         Ok(())
     }
 }
@@ -1,10 +1,38 @@
-use crate::{Result, Run, archive, config::Config};
+use std::io::Write;
+
+use cargo_metadata::camino::Utf8Path;
+
+use crate::{
+    Result, Run,
+    archive::{ArchiveFormat, ArchiveOptions, Compression},
+    build_info::BuildInfo,
+    config::{Config, DistConfig},
+    dist_manifest::{ArchiveEntry, Digests, DistManifest, PackageEntry, SbomEntry},
+    sbom::SbomFormat,
+};
 
 /// Arguments definition of the `dist-archive` subcommand.
 #[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-archive.md"))]
 #[derive(Debug, Clone, Default, clap::Args)]
 #[non_exhaustive]
-pub struct DistArchive {}
+pub struct DistArchive {
+    /// Archive format to use for all targets, overriding the configuration
+    #[clap(long, value_enum)]
+    pub format: Option<ArchiveFormat>,
+    /// Compression to use for the archives, overriding the configuration
+    ///
+    /// Specified as `CODEC[:LEVEL]` (e.g. `xz`, `zstd:19`).
+    /// Available codecs are `gzip`, `xz`, `zstd` and `bzip2`.
+    /// Can be specified multiple times to create archives in multiple formats.
+    #[clap(long, value_name = "CODEC[:LEVEL]")]
+    pub compression: Vec<Compression>,
+    /// Create reproducible archives, regardless of the configuration
+    ///
+    /// Entries are sorted, ownership and permissions are normalized and
+    /// modification times are set to `SOURCE_DATE_EPOCH`.
+    #[clap(long)]
+    pub reproducible: bool,
+}
 
 impl Run for DistArchive {
     fn run(&self, config: &Config) -> Result<()> {
@@ -16,7 +44,11 @@ impl DistArchive {
     /// Runs the `dist-archive` subcommand.
     #[tracing::instrument(name = "dist-archive", skip_all, err)]
     pub fn run(&self, config: &Config) -> Result<()> {
-        let Self {} = self;
+        let Self {
+            format,
+            compression,
+            reproducible,
+        } = self;
         let config = config.dist()?;
 
         if !config.dist_base_working_directory().is_dir() {
@@ -24,46 +56,198 @@ impl DistArchive {
             return Ok(());
         }
 
-        let dist_dir = config.dist_target_directory();
+        let manifest_path = DistManifest::path(config);
+        let mut manifest = DistManifest::read(&manifest_path)?;
+        // drop entries for archives of other distributions or versions, and
+        // for archives removed since the last run
+        let packages = PackageEntry::from_config(config);
+        manifest.archives.retain(|archive| {
+            archive.is_current(config.name(), &packages)
+                && config.dist_target_directory().join(&archive.file).is_file()
+        });
 
         let noarch_path = config.dist_base_working_directory().join("noarch");
         let noarch_path = noarch_path.is_dir().then_some(noarch_path);
 
         let mut created = false;
-        for dir in config.dist_base_working_directory().read_dir_utf8()? {
-            let dir = dir?;
-            if !dir.file_type()?.is_dir() {
-                continue;
-            }
-            let dir = dir.path();
-            if dir.file_name() == Some("noarch") {
-                continue;
-            }
-            let target_triple = dir.file_name().unwrap();
-            let archive_name = format!("{}-{}.tar.gz", config.name(), target_triple);
-            let archive_path = dist_dir.join(archive_name);
+        for target_triple in config.built_target_triples()? {
+            let target_triple = target_triple.as_str();
+            let dir = config.dist_working_directory(Some(target_triple));
+            let archive_format = format.unwrap_or_else(|| config.archive_format(target_triple));
 
             let mut targets = vec![];
-            for dir in [dir].into_iter().chain(noarch_path.as_deref()) {
+            for dir in [dir.as_path()].into_iter().chain(noarch_path.as_deref()) {
                 for entry in dir.read_dir_utf8()? {
                     targets.push(entry?.path().to_owned());
                 }
             }
-            archive::create(&archive_path, &targets)?;
+            for compression in archive_compressions(config, archive_format, compression) {
+                let entry = create_archive(
+                    config,
+                    Some(target_triple),
+                    archive_format,
+                    compression,
+                    *reproducible || config.reproducible_archive(),
+                    false,
+                    &targets,
+                )?;
+                manifest.insert_archive(entry);
+            }
+
+            let symbols_dir = config.dist_symbols_directory(target_triple);
+            if symbols_dir.is_dir() {
+                let mut symbols = vec![];
+                for entry in symbols_dir.read_dir_utf8()? {
+                    symbols.push(entry?.path().to_owned());
+                }
+                for compression in archive_compressions(config, archive_format, compression) {
+                    let entry = create_archive(
+                        config,
+                        Some(target_triple),
+                        archive_format,
+                        compression,
+                        *reproducible || config.reproducible_archive(),
+                        true,
+                        &symbols,
+                    )?;
+                    manifest.insert_archive(entry);
+                }
+            }
 
-            tracing::info!("Archive created successfully: {archive_path}");
             created = true;
         }
 
         if !created && let Some(noarch_path) = noarch_path {
-            let archive_name = format!("{}-noarch.tar.gz", config.name());
-            let archive_path = dist_dir.join(archive_name);
+            let archive_format = format.unwrap_or_else(|| config.archive_format("noarch"));
+            for compression in archive_compressions(config, archive_format, compression) {
+                let entry = create_archive(
+                    config,
+                    None,
+                    archive_format,
+                    compression,
+                    *reproducible || config.reproducible_archive(),
+                    false,
+                    [&noarch_path],
+                )?;
+                manifest.insert_archive(entry);
+            }
+        }
+
+        manifest.write(&manifest_path)?;
+        tracing::info!("Manifest written successfully: {manifest_path}");
+
+        Ok(())
+    }
+}
 
-            archive::create(&archive_path, [noarch_path].into_iter())?;
+fn archive_compressions(
+    config: &DistConfig,
+    format: ArchiveFormat,
+    compression: &[Compression],
+) -> Vec<Compression> {
+    let compressions = if compression.is_empty() {
+        config.archive_compressions()
+    } else {
+        compression
+    };
 
-            tracing::info!("Archive created successfully: {archive_path}");
+    match format {
+        ArchiveFormat::Tar => {
+            let mut res = Vec::<Compression>::new();
+            for compression in compressions {
+                if res.iter().all(|c| c.codec != compression.codec) {
+                    res.push(*compression);
+                }
+            }
+            res
         }
+        // zip archives are created only once, with the first compression
+        ArchiveFormat::Zip => compressions.iter().take(1).copied().collect(),
+    }
+}
 
-        Ok(())
+fn create_archive(
+    config: &DistConfig,
+    target_triple: Option<&str>,
+    format: ArchiveFormat,
+    compression: Compression,
+    reproducible: bool,
+    symbols: bool,
+    src: impl IntoIterator<Item = impl AsRef<Utf8Path>>,
+) -> Result<ArchiveEntry> {
+    let prefix = config.archive_prefix(target_triple.unwrap_or("noarch"))?;
+    let options = ArchiveOptions::new()
+        .format(format)
+        .compression(compression)
+        .reproducible(reproducible)
+        .prefix(&prefix);
+    let dist_dir = config.dist_target_directory();
+    let archive_name = format!(
+        "{}{}.{}",
+        config.archive_name(target_triple.unwrap_or("noarch"))?,
+        if symbols { "-symbols" } else { "" },
+        options.extension()
+    );
+    let archive_path = dist_dir.join(&archive_name);
+    options.create(&archive_path, src)?;
+    tracing::info!("Archive created successfully: {archive_path}");
+
+    let digests = Digests::from_file(&archive_path)?;
+    let checksum_path = dist_dir.join(format!("{archive_name}.sha256"));
+    let mut checksum_file = crate::fs::create_file(&checksum_path)?;
+    writeln!(checksum_file, "{}  {archive_name}", digests.sha256)?;
+
+    let mut packages = PackageEntry::from_config(config);
+    if let Some(target_triple) = target_triple {
+        if let Some(mut build_info) = BuildInfo::read(config, target_triple)? {
+            for package in &mut packages {
+                package.profile = build_info
+                    .packages
+                    .remove(&package.name)
+                    .map(|info| info.profile);
+            }
+        }
+    } else {
+        // architecture-independent archive doesn't contain any build artifacts
+        for package in &mut packages {
+            package.targets.clear();
+        }
+    }
+
+    let mut sboms = vec![];
+    if let Some(target_triple) = target_triple
+        && !symbols
+    {
+        let dir = config.dist_working_directory(Some(target_triple));
+        for package in config.packages() {
+            for format in SbomFormat::ALL {
+                let file_name = format.file_name(package.name());
+                let path = dir.join(&file_name);
+                if !path.is_file() {
+                    continue;
+                }
+                sboms.push(SbomEntry {
+                    path: Utf8Path::new(prefix.trim_matches('/'))
+                        .join(&file_name)
+                        .into_string(),
+                    format: format.name().to_owned(),
+                    package: package.name().to_string(),
+                    digests: Digests::from_file(&path)?,
+                });
+            }
+        }
     }
+
+    Ok(ArchiveEntry {
+        file: archive_name,
+        name: config.name().to_owned(),
+        target: target_triple.map(str::to_owned),
+        format: format.to_string(),
+        compression: compression.codec.to_string(),
+        size: archive_path.metadata()?.len(),
+        digests,
+        symbols,
+        packages,
+        sboms,
+    })
 }
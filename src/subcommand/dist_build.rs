@@ -11,35 +11,82 @@ pub struct DistBuild {
     #[clap(flatten)]
     pub dist_build_bin_args: super::DistBuildBin,
 
+    /// `dist-build-cli-doc` subcommand arguments.
+    ///
+    /// The options are not exposed, since they make sense only when the
+    /// subcommand is run alone.
+    #[cfg(feature = "subcommand-dist-build-cli-doc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-cli-doc")))]
+    #[clap(skip)]
+    pub dist_build_cli_doc_args: super::DistBuildCliDoc,
+
     /// `dist-build-completion` subcommand arguments.
     #[cfg(feature = "subcommand-dist-build-completion")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-completion")))]
-    #[clap(flatten)]
+    #[clap(skip)]
     pub dist_build_completion_args: super::DistBuildCompletion,
 
     /// `dist-build-doc` subcommand arguments.
     #[cfg(feature = "subcommand-dist-build-doc")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-doc")))]
-    #[clap(flatten)]
+    #[clap(skip)]
     pub dist_build_doc_args: super::DistBuildDoc,
 
     /// `dist-build-license` subcommand arguments.
     #[cfg(feature = "subcommand-dist-build-license")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-license")))]
-    #[clap(flatten)]
+    #[clap(skip)]
     pub dist_build_license_args: super::DistBuildLicense,
 
     /// `dist-build-man` subcommand arguments.
     #[cfg(feature = "subcommand-dist-build-man")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-man")))]
-    #[clap(flatten)]
+    #[clap(skip)]
     pub dist_build_man_args: super::DistBuildMan,
 
     /// `dist-build-readme` subcommand arguments.
     #[cfg(feature = "subcommand-dist-build-readme")]
     #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-readme")))]
-    #[clap(flatten)]
+    #[clap(skip)]
     pub dist_build_readme_args: super::DistBuildReadme,
+
+    /// `dist-build-sbom` subcommand arguments.
+    #[cfg(feature = "subcommand-dist-build-sbom")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-sbom")))]
+    #[clap(flatten)]
+    pub dist_build_sbom_args: super::DistBuildSbom,
+
+    /// `dist-build-deb` subcommand arguments.
+    #[cfg(feature = "subcommand-dist-build-deb")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-deb")))]
+    #[clap(flatten)]
+    pub dist_build_deb_args: super::DistBuildDeb,
+
+    /// `dist-build-rpm` subcommand arguments.
+    #[cfg(feature = "subcommand-dist-build-rpm")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subcommand-dist-build-rpm")))]
+    #[clap(flatten)]
+    pub dist_build_rpm_args: super::DistBuildRpm,
+
+    /// Arguments to control the reuse of the previously generated artifacts.
+    ///
+    /// The arguments of the steps reusing the previous artifacts are not
+    /// exposed, so that `--force` is defined only once and applies to all of
+    /// them.
+    #[cfg(dist_fingerprint)]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            feature = "subcommand-dist-build-cli-doc",
+            feature = "subcommand-dist-build-completion",
+            feature = "subcommand-dist-build-doc",
+            feature = "subcommand-dist-build-license",
+            feature = "subcommand-dist-build-man",
+            feature = "subcommand-dist-build-readme"
+        )))
+    )]
+    #[clap(flatten)]
+    pub fingerprint_args: crate::args::FingerprintArgs,
 }
 
 impl Run for DistBuild {
@@ -55,6 +102,8 @@ impl DistBuild {
         let Self {
             #[cfg(feature = "subcommand-dist-build-bin")]
             dist_build_bin_args,
+            #[cfg(feature = "subcommand-dist-build-cli-doc")]
+            dist_build_cli_doc_args,
             #[cfg(feature = "subcommand-dist-build-completion")]
             dist_build_completion_args,
             #[cfg(feature = "subcommand-dist-build-doc")]
@@ -65,11 +114,27 @@ impl DistBuild {
             dist_build_man_args,
             #[cfg(feature = "subcommand-dist-build-readme")]
             dist_build_readme_args,
+            #[cfg(feature = "subcommand-dist-build-sbom")]
+            dist_build_sbom_args,
+            #[cfg(feature = "subcommand-dist-build-deb")]
+            dist_build_deb_args,
+            #[cfg(feature = "subcommand-dist-build-rpm")]
+            dist_build_rpm_args,
+            #[cfg(dist_fingerprint)]
+            fingerprint_args,
         } = self;
 
+        #[cfg(dist_fingerprint)]
+        if fingerprint_args.force {
+            crate::fingerprint::remove_all(config.dist()?)?;
+        }
+
         #[cfg(feature = "subcommand-dist-build-bin")]
         dist_build_bin_args.run(config)?;
 
+        #[cfg(feature = "subcommand-dist-build-cli-doc")]
+        dist_build_cli_doc_args.run(config)?;
+
         #[cfg(feature = "subcommand-dist-build-completion")]
         dist_build_completion_args.run(config)?;
 
@@ -85,6 +150,16 @@ impl DistBuild {
         #[cfg(feature = "subcommand-dist-build-readme")]
         dist_build_readme_args.run(config)?;
 
+        #[cfg(feature = "subcommand-dist-build-sbom")]
+        dist_build_sbom_args.run(config)?;
+
+        // packages are built from the artifacts built above
+        #[cfg(feature = "subcommand-dist-build-deb")]
+        dist_build_deb_args.run(config)?;
+
+        #[cfg(feature = "subcommand-dist-build-rpm")]
+        dist_build_rpm_args.run(config)?;
+
         Ok(())
     }
 }
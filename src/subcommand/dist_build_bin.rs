@@ -1,4 +1,15 @@
-use crate::{Result, Run, cargo, config::Config};
+use std::{io::Write as _, process::Command};
+
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use eyre::bail;
+
+use crate::{
+    Result, Run,
+    build_info::{BuildInfo, PackageBuildInfo},
+    cargo::{self, BuildBackend},
+    config::{Config, DistConfig, DistPackageConfig},
+    process::CommandExt,
+};
 
 /// Arguments definition of the `dist-build-bin` subcommand.
 #[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-build-bin.md"))]
@@ -6,14 +17,47 @@ use crate::{Result, Run, cargo, config::Config};
 #[non_exhaustive]
 pub struct DistBuildBin {
     /// Target triple for the build
-    #[clap(long = "target")]
-    pub target_triple: Option<String>,
-    /// Use cross tool to build
+    ///
+    /// Can be specified multiple times to build for multiple targets.
+    /// Defaults to the target triples in the configuration, or the host target.
+    #[clap(long = "target", value_name = "TRIPLE")]
+    pub target_triples: Vec<String>,
+    /// Build backend to use (`cargo`, `cross`, `zigbuild` or a custom command)
+    ///
+    /// A custom command is given as a program followed by its arguments, and
+    /// must accept the same arguments as `cargo build`.
+    /// Defaults to the build backend in the configuration.
+    #[clap(long, value_name = "BACKEND", conflicts_with_all = ["use_cross", "use_cross_if_needed"])]
+    pub backend: Option<BuildBackend>,
+    /// Use cross tool to build (same as `--backend cross`)
     #[clap(long)]
     pub use_cross: bool,
     /// Use cross if target is different from default target
     #[clap(long)]
     pub use_cross_if_needed: bool,
+    /// Build the targets in parallel
+    #[clap(long)]
+    pub parallel: bool,
+    /// Cargo profile to build the executables with
+    ///
+    /// Defaults to the profile in the configuration, or `release`.
+    #[clap(long, value_name = "PROFILE-NAME")]
+    pub profile: Option<String>,
+    /// Strip debug info and symbols from the executables
+    #[clap(long)]
+    pub strip: bool,
+    /// Split debug info of the executables into a symbols archive, stripping
+    /// the executables
+    #[clap(long)]
+    pub split_debuginfo: bool,
+}
+
+/// How to handle the debug info of the executables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DebugInfo {
+    Keep,
+    Strip,
+    Split,
 }
 
 impl Run for DistBuildBin {
@@ -29,48 +73,421 @@ impl DistBuildBin {
         tracing::info!("Building executables...");
 
         let Self {
-            target_triple,
+            target_triples,
+            backend,
             use_cross,
             use_cross_if_needed,
+            parallel,
+            profile,
+            strip,
+            split_debuginfo,
         } = self;
         let config = config.dist()?;
 
+        let debuginfo = if *split_debuginfo || config.split_debuginfo() {
+            DebugInfo::Split
+        } else if *strip || config.strip_executables() {
+            DebugInfo::Strip
+        } else {
+            DebugInfo::Keep
+        };
+
         let default_target = env!("DEFAULT_TARGET");
-        let target_triple = target_triple.as_deref();
-        let use_cross = *use_cross
-            || (*use_cross_if_needed
-                && target_triple.map(|t| t != default_target).unwrap_or(false));
-
-        let bin_dir = config.dist_working_directory(Some(target_triple.unwrap_or(default_target)));
-        crate::fs::create_or_cleanup_dir(&bin_dir)?;
-
-        let common_build_options = config.cargo_build_options();
-        for package in config.packages() {
-            let package_build_options = package.cargo_build_options();
-            for target in package.targets() {
-                let target_build_options = target.cargo_build_options();
-                let build_options = common_build_options
+        let targets = select_targets(target_triples, config.target_triples());
+
+        let backend = |target_triple: Option<&str>| {
+            let name = target_triple.unwrap_or(default_target);
+            if let Some(backend) = backend {
+                backend
+            } else if *use_cross || (*use_cross_if_needed && name != default_target) {
+                &BuildBackend::Cross
+            } else {
+                config.build_backend(name)
+            }
+        };
+        // spans are not inherited by the spawned threads
+        let parent = tracing::Span::current();
+        let build = &|target_triple: Option<&str>| {
+            let name = target_triple.unwrap_or(default_target);
+            let _span = tracing::info_span!(parent: &parent, "target", name).entered();
+            let res = build_target(
+                config,
+                target_triple,
+                backend(target_triple),
+                profile.as_deref(),
+                debuginfo,
+            );
+            if res.is_err() {
+                // don't leave partial artifacts to be archived
+                crate::fs::remove_dir(config.dist_working_directory(Some(name)))?;
+                crate::fs::remove_dir(config.dist_symbols_directory(name))?;
+            }
+            res
+        };
+
+        let results = if *parallel && targets.len() > 1 {
+            std::thread::scope(|s| {
+                let handles = targets
                     .iter()
-                    .chain(package_build_options.iter())
-                    .chain(target_build_options.iter())
-                    .map(|s| s.as_str());
-                let artifacts = cargo::build(
-                    config.metadata(),
-                    Some(package.metadata()),
-                    Some(target.metadata()),
-                    Some("release"),
-                    build_options,
-                    use_cross,
-                    target_triple,
-                )?;
-                for src in artifacts {
-                    let src = src?;
-                    let dest = bin_dir.join(src.file_name().unwrap());
-                    crate::fs::copy(&src, dest)?;
+                    .map(|&target_triple| s.spawn(move || build(target_triple)))
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("build thread panicked"))
+                    .collect::<Vec<_>>()
+            })
+        } else {
+            targets
+                .iter()
+                .map(|&target_triple| build(target_triple))
+                .collect()
+        };
+
+        if targets.len() == 1 {
+            return results.into_iter().next().unwrap_or(Ok(()));
+        }
+
+        let mut failed = vec![];
+        for (target_triple, res) in targets.iter().zip(results) {
+            let target_triple = target_triple.unwrap_or(default_target);
+            match res {
+                Ok(()) => tracing::info!("{target_triple}: succeeded"),
+                Err(e) => {
+                    tracing::error!("{target_triple}: failed: {e:#}");
+                    failed.push(target_triple);
                 }
             }
         }
+        if !failed.is_empty() {
+            bail!(
+                "failed to build executables for {} of {} targets: {}",
+                failed.len(),
+                targets.len(),
+                failed.join(", ")
+            );
+        }
 
         Ok(())
     }
 }
+
+/// Returns the deduplicated target triples to build for.
+///
+/// The target triples given on the command line take precedence over the
+/// configured ones. `None` stands for the host target.
+fn select_targets<'a>(args: &'a [String], config: &'a [String]) -> Vec<Option<&'a str>> {
+    let target_triples = if !args.is_empty() { args } else { config };
+    let mut targets = vec![];
+    for target_triple in target_triples {
+        if !targets.contains(&Some(target_triple.as_str())) {
+            targets.push(Some(target_triple.as_str()));
+        }
+    }
+    if targets.is_empty() {
+        targets.push(None);
+    }
+    targets
+}
+
+fn build_target(
+    config: &DistConfig,
+    target_triple: Option<&str>,
+    backend: &BuildBackend,
+    profile: Option<&str>,
+    debuginfo: DebugInfo,
+) -> Result<()> {
+    let name = target_triple.unwrap_or(env!("DEFAULT_TARGET"));
+    let bin_dir = config.dist_working_directory(Some(name));
+    crate::fs::create_or_cleanup_dir(&bin_dir)?;
+    let format = DebugInfoFormat::from_target_triple(name);
+    let debuginfo = if format.is_none() && debuginfo != DebugInfo::Keep {
+        tracing::warn!("stripping executables is not supported for {name}, skipped");
+        DebugInfo::Keep
+    } else {
+        debuginfo
+    };
+    let symbols_dir = config.dist_symbols_directory(name);
+    if debuginfo == DebugInfo::Split {
+        crate::fs::create_or_cleanup_dir(&symbols_dir)?;
+    } else {
+        crate::fs::remove_dir(&symbols_dir)?;
+    }
+
+    let mut build_info = BuildInfo::default();
+    let common_build_options = config.cargo_build_options();
+    for package in config.packages() {
+        let profile = profile
+            .or(package.cargo_profile())
+            .unwrap_or(config.cargo_profile());
+        let package_build_options = package.cargo_build_options();
+        let mut lib_name = None;
+        for target in package.targets() {
+            let target_build_options = target.cargo_build_options();
+            let build_options = common_build_options
+                .iter()
+                .chain(package_build_options.iter())
+                .chain(target_build_options.iter())
+                .map(|s| s.as_str());
+            let artifacts = cargo::build(
+                config.metadata(),
+                Some(package.metadata()),
+                Some(target.metadata()),
+                Some(profile),
+                build_options,
+                backend,
+                target_triple,
+            )?;
+            for src in artifacts {
+                let src = src?;
+                if cargo::is_library_file(&src) {
+                    let dest = bin_dir.join("lib").join(src.file_name().unwrap());
+                    crate::fs::copy(&src, &dest)?;
+                    if lib_name.is_none() {
+                        lib_name = Some(target.name().replace('-', "_"));
+                    }
+                    continue;
+                }
+                let dest = bin_dir.join(src.file_name().unwrap());
+                crate::fs::copy(&src, &dest)?;
+                let Some(format) = format else {
+                    continue;
+                };
+                match debuginfo {
+                    DebugInfo::Keep => {}
+                    DebugInfo::Strip => strip_executable(config, format, &src, &dest, None)?,
+                    DebugInfo::Split => {
+                        strip_executable(config, format, &src, &dest, Some(&symbols_dir))?
+                    }
+                }
+            }
+        }
+        if let Some(lib_name) = &lib_name {
+            if let Some(header) = package.c_header() {
+                let dest = bin_dir.join("include").join(header.file_name().unwrap());
+                crate::fs::copy(header, dest)?;
+            }
+            if package.pkg_config() {
+                let dest = bin_dir
+                    .join("lib/pkgconfig")
+                    .join(format!("{}.pc", package.name()));
+                write_pkg_config(dest, package, lib_name)?;
+            }
+        }
+        build_info.packages.insert(
+            package.name().to_string(),
+            PackageBuildInfo {
+                profile: profile.to_owned(),
+            },
+        );
+    }
+    build_info.write(config, name)?;
+
+    Ok(())
+}
+
+/// Format of the debug info of the executables built for a target triple.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DebugInfoFormat {
+    /// `.pdb` file generated by the MSVC linker.
+    Pdb,
+    /// Mach-O executable, whose debug info is collected by `dsymutil`.
+    Dsym,
+    /// ELF executable, whose debug info is split by `objcopy`.
+    Elf,
+}
+
+impl DebugInfoFormat {
+    /// Returns the debug info format of the executables built for the target
+    /// triple, or `None` if stripping them is not supported.
+    fn from_target_triple(target_triple: &str) -> Option<Self> {
+        if target_triple.ends_with("-windows-msvc") {
+            Some(Self::Pdb)
+        } else if target_triple.contains("-apple-") {
+            Some(Self::Dsym)
+        } else if target_triple.contains("-linux")
+            || target_triple.contains("bsd")
+            || target_triple.contains("-dragonfly")
+        {
+            Some(Self::Elf)
+        } else {
+            None
+        }
+    }
+}
+
+/// Writes the pkg-config file of the library `lib_name` built from the
+/// package.
+fn write_pkg_config(path: Utf8PathBuf, package: &DistPackageConfig, lib_name: &str) -> Result<()> {
+    let metadata = package.metadata();
+    let mut file = crate::fs::create_file(path)?;
+    // `${pcfiledir}` makes the file relocatable, so that it is usable wherever
+    // the archive is extracted to
+    writeln!(file, "prefix=${{pcfiledir}}/../..")?;
+    writeln!(file, "libdir=${{prefix}}/lib")?;
+    writeln!(file, "includedir=${{prefix}}/include")?;
+    writeln!(file)?;
+    writeln!(file, "Name: {}", package.name())?;
+    let description = metadata
+        .description
+        .as_deref()
+        .map(|description| description.split_whitespace().collect::<Vec<_>>().join(" "))
+        .unwrap_or_else(|| package.name().to_string());
+    writeln!(file, "Description: {description}")?;
+    writeln!(file, "Version: {}", metadata.version)?;
+    if let Some(url) = metadata.homepage.as_ref().or(metadata.repository.as_ref()) {
+        writeln!(file, "URL: {url}")?;
+    }
+    writeln!(file, "Libs: -L${{libdir}} -l{lib_name}")?;
+    writeln!(file, "Cflags: -I${{includedir}}")?;
+    Ok(())
+}
+
+/// Strips the debug info and symbols from the executable `dest` copied from
+/// `src`, saving the debug info into `symbols_dir` if specified.
+fn strip_executable(
+    config: &DistConfig,
+    format: DebugInfoFormat,
+    src: &Utf8Path,
+    dest: &Utf8Path,
+    symbols_dir: Option<&Utf8Path>,
+) -> Result<()> {
+    let metadata = config.metadata();
+    let file_name = dest.file_name().unwrap();
+
+    match format {
+        DebugInfoFormat::Pdb => {
+            // debug info is already separated into the `.pdb` file by the linker
+            let Some(symbols_dir) = symbols_dir else {
+                return Ok(());
+            };
+            // cargo replaces `-` in the crate name with `_` for the `.pdb` file
+            let stem = src.file_stem().unwrap();
+            let pdb = [stem.to_owned(), stem.replace('-', "_")]
+                .into_iter()
+                .map(|stem| src.with_file_name(format!("{stem}.pdb")))
+                .find(|pdb| pdb.is_file());
+            match pdb {
+                Some(pdb) => crate::fs::copy(&pdb, symbols_dir.join(pdb.file_name().unwrap()))?,
+                None => tracing::warn!("debug info file not found for {src}"),
+            }
+        }
+        DebugInfoFormat::Dsym => {
+            if let Some(symbols_dir) = symbols_dir {
+                Command::new("dsymutil")
+                    .arg(dest)
+                    .arg("-o")
+                    .arg(symbols_dir.join(format!("{file_name}.dSYM")))
+                    .workspace_spawn(metadata)?;
+            }
+            Command::new("strip")
+                .args(["-x"])
+                .arg(dest)
+                .workspace_spawn(metadata)?;
+        }
+        DebugInfoFormat::Elf => {
+            let objcopy = std::env::var("OBJCOPY").unwrap_or_else(|_| "objcopy".to_string());
+            let mut cmd = Command::new(&objcopy);
+            cmd.arg("--strip-all");
+            if let Some(symbols_dir) = symbols_dir {
+                let debug_file = symbols_dir.join(format!("{file_name}.debug"));
+                Command::new(&objcopy)
+                    .arg("--only-keep-debug")
+                    .arg(dest)
+                    .arg(&debug_file)
+                    .workspace_spawn(metadata)?;
+                // let debuggers find the debug info file placed next to the executable
+                cmd.arg(format!("--add-gnu-debuglink={debug_file}"));
+            }
+            cmd.arg(dest).workspace_spawn(metadata)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets() {
+        let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        type Case<'a> = (&'a [&'a str], &'a [&'a str], &'a [Option<&'a str>]);
+        let cases: &[Case] = &[
+            (&[], &[], &[None]),
+            (&["a"], &[], &[Some("a")]),
+            (&[], &["b", "c"], &[Some("b"), Some("c")]),
+            (&["a"], &["b", "c"], &[Some("a")]),
+            (&["a", "b", "a"], &[], &[Some("a"), Some("b")]),
+            (&[], &["c", "c"], &[Some("c")]),
+        ];
+        for (args, config, expected) in cases {
+            let (args, config) = (strings(args), strings(config));
+            assert_eq!(
+                select_targets(&args, &config),
+                *expected,
+                "args: {args:?}, config: {config:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn debuginfo_format() {
+        let cases = [
+            ("x86_64-pc-windows-msvc", Some(DebugInfoFormat::Pdb)),
+            ("aarch64-apple-darwin", Some(DebugInfoFormat::Dsym)),
+            ("x86_64-unknown-linux-gnu", Some(DebugInfoFormat::Elf)),
+            ("armv7-unknown-linux-musleabihf", Some(DebugInfoFormat::Elf)),
+            ("aarch64-linux-android", Some(DebugInfoFormat::Elf)),
+            ("x86_64-unknown-freebsd", Some(DebugInfoFormat::Elf)),
+            ("x86_64-unknown-netbsd", Some(DebugInfoFormat::Elf)),
+            ("x86_64-unknown-openbsd", Some(DebugInfoFormat::Elf)),
+            ("x86_64-unknown-dragonfly", Some(DebugInfoFormat::Elf)),
+            ("x86_64-pc-windows-gnu", None),
+            ("aarch64-apple-ios", Some(DebugInfoFormat::Dsym)),
+            ("wasm32-unknown-unknown", None),
+            ("wasm32-wasip1", None),
+        ];
+        for (target_triple, expected) in cases {
+            assert_eq!(
+                DebugInfoFormat::from_target_triple(target_triple),
+                expected,
+                "target_triple: {target_triple}"
+            );
+        }
+    }
+
+    #[test]
+    fn pkg_config() {
+        let (_, package) =
+            crate::config::DistConfigBuilder::from_root_package(crate::workspace::current())
+                .unwrap();
+        let package = package.build().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = Utf8PathBuf::try_from(dir.path().join("app.pc")).unwrap();
+        write_pkg_config(path.clone(), &package, "app_core").unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines = content.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[..4],
+            [
+                "prefix=${pcfiledir}/../..",
+                "libdir=${prefix}/lib",
+                "includedir=${prefix}/include",
+                "",
+            ]
+        );
+        assert!(lines.contains(&"Name: cli-xtask"), "{content}");
+        let version = format!("Version: {}", env!("CARGO_PKG_VERSION"));
+        assert!(lines.contains(&version.as_str()), "{content}");
+        assert!(lines.contains(&"Libs: -L${libdir} -lapp_core"), "{content}");
+        assert!(lines.contains(&"Cflags: -I${includedir}"), "{content}");
+        // the description is folded into a single line
+        let description = lines
+            .iter()
+            .find(|line| line.starts_with("Description: "))
+            .unwrap();
+        assert!(!description.ends_with(' '), "{content}");
+    }
+}
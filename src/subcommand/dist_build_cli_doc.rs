@@ -0,0 +1,253 @@
+use std::{collections::BTreeMap, io::Write as _};
+
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use eyre::bail;
+
+use crate::{
+    Result, Run, args::FingerprintArgs, command_tree::iterate_commands, config::Config,
+    fingerprint::Fingerprint, fs::ToRelative,
+};
+
+/// Arguments definition of the `dist-build-cli-doc` subcommand.
+#[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-build-cli-doc.md"))]
+#[derive(Debug, Clone, Default, clap::Args)]
+#[non_exhaustive]
+pub struct DistBuildCliDoc {
+    /// Directory to write the reference pages into, instead of the dist
+    /// working directory
+    #[clap(long, value_name = "DIR")]
+    pub output: Option<Utf8PathBuf>,
+    /// Check that the reference pages in the directory are up to date,
+    /// instead of writing them
+    ///
+    /// The directory is given by `--output`, or by the configuration.
+    #[clap(long)]
+    pub check: bool,
+
+    /// Arguments to control the reuse of the previously generated artifacts.
+    #[clap(flatten)]
+    pub fingerprint_args: FingerprintArgs,
+}
+
+impl Run for DistBuildCliDoc {
+    fn run(&self, config: &Config) -> Result<()> {
+        self.run(config)
+    }
+}
+
+impl DistBuildCliDoc {
+    /// Runs the `dist-build-cli-doc` subcommand.
+    #[tracing::instrument(name = "dist-build-cli-doc", skip_all, err)]
+    pub fn run(&self, config: &Config) -> Result<()> {
+        let Self {
+            output,
+            check,
+            fingerprint_args,
+        } = self;
+
+        if *check {
+            // `lint` runs this subcommand regardless of the configuration
+            let dir = output.as_deref().or_else(|| {
+                config
+                    .dist()
+                    .ok()
+                    .and_then(|config| config.cli_doc_directory())
+            });
+            let Some(dir) = dir else {
+                tracing::info!("No command line reference directory to check");
+                return Ok(());
+            };
+            tracing::info!("Checking command line reference pages...");
+            return check_pages(&render_pages(config)?, dir);
+        }
+
+        tracing::info!("Building command line reference pages...");
+
+        if let Some(output) = output {
+            let pages = render_pages(config)?;
+            for path in stale_pages(&pages, output)? {
+                tracing::info!("removing {}", path.to_relative());
+                std::fs::remove_file(path)?;
+            }
+            write_pages(&pages, output)?;
+            return Ok(());
+        }
+
+        let dist_config = config.dist()?;
+        let out_dir = dist_config.dist_working_directory(None).join("cli-doc");
+
+        let mut fingerprint = Fingerprint::new("cli-doc");
+        for package in dist_config.packages() {
+            for target in package.targets() {
+                if let Some(cmd) = target.command() {
+                    fingerprint.add_command(cmd);
+                }
+            }
+        }
+        if !fingerprint_args.force && fingerprint.is_fresh(dist_config)? {
+            tracing::info!("Command line reference pages are up to date");
+            return Ok(());
+        }
+        fingerprint.remove_outputs(dist_config)?;
+        crate::fs::remove_dir(&out_dir)?;
+
+        let outputs = write_pages(&render_pages(config)?, &out_dir)?;
+        fingerprint.save(dist_config, &outputs)?;
+
+        Ok(())
+    }
+}
+
+/// Reference pages of the commands.
+#[derive(Debug, Default)]
+struct Pages {
+    /// Names of the target commands
+    commands: Vec<String>,
+    /// Contents of the pages of the commands and their subcommands, keyed by
+    /// their file names
+    pages: BTreeMap<String, String>,
+}
+
+/// Renders the reference pages of all the commands.
+fn render_pages(config: &Config) -> Result<Pages> {
+    let mut pages = Pages::default();
+    for package in config.dist()?.packages() {
+        for target in package.targets() {
+            let Some(cmd) = target.command() else {
+                continue;
+            };
+            pages.commands.push(cmd.get_name().to_owned());
+            for mut cmd in iterate_commands(cmd.clone()) {
+                let command_name = cmd.get_name().to_owned();
+                let file_name = format!("{}.md", command_name.replace(' ', "-"));
+                let help = cmd.render_long_help().to_string();
+                let page = format!("# `{command_name}`\n\n```text\n{}\n```\n", help.trim_end());
+                pages.pages.insert(file_name, page);
+            }
+        }
+    }
+    Ok(pages)
+}
+
+/// Writes the pages into `dir`, returning the paths of the written files.
+fn write_pages(pages: &Pages, dir: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+    let mut paths = vec![];
+    for (file_name, contents) in &pages.pages {
+        let path = dir.join(file_name);
+        crate::fs::create_file(&path)?.write_all(contents.as_bytes())?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Returns the pages in `dir` of the commands which no longer exist.
+fn stale_pages(pages: &Pages, dir: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
+    let mut stale = vec![];
+    if !dir.is_dir() {
+        return Ok(stale);
+    }
+    for entry in dir.read_dir_utf8()? {
+        let entry = entry?;
+        let Some(stem) = entry.file_name().strip_suffix(".md") else {
+            continue;
+        };
+        let is_command_page = pages.commands.iter().any(|command| {
+            let command = command.replace(' ', "-");
+            stem == command
+                || stem
+                    .strip_prefix(&command)
+                    .is_some_and(|rest| rest.starts_with('-'))
+        });
+        if is_command_page && !pages.pages.contains_key(entry.file_name()) {
+            stale.push(entry.into_path());
+        }
+    }
+    stale.sort();
+    Ok(stale)
+}
+
+/// Checks that the pages in `dir` are the same as the rendered ones.
+fn check_pages(pages: &Pages, dir: &Utf8Path) -> Result<()> {
+    let mut outdated = vec![];
+    for (file_name, contents) in &pages.pages {
+        let path = dir.join(file_name);
+        match std::fs::read_to_string(&path) {
+            Ok(actual) if &actual == contents => {}
+            Ok(_) => outdated.push(format!("{} is outdated", path.to_relative())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                outdated.push(format!("{} is missing", path.to_relative()))
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    for path in stale_pages(pages, dir)? {
+        outdated.push(format!("{} is stale", path.to_relative()));
+    }
+    if !outdated.is_empty() {
+        for message in &outdated {
+            tracing::error!("{message}");
+        }
+        bail!(
+            "command line reference pages in {} are not up to date",
+            dir.to_relative()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn pages() -> Pages {
+        Pages {
+            commands: vec!["app".to_owned()],
+            pages: [("app.md", "# `app`\n"), ("app-sub.md", "# `app sub`\n")]
+                .map(|(name, contents)| (name.to_owned(), contents.to_owned()))
+                .into(),
+        }
+    }
+
+    #[test]
+    fn stale() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = Utf8PathBuf::try_from(temp_dir.path().to_owned()).unwrap();
+        let pages = pages();
+
+        assert!(
+            stale_pages(&pages, &dir.join("missing"))
+                .unwrap()
+                .is_empty()
+        );
+
+        write_pages(&pages, &dir).unwrap();
+        for name in ["app-removed.md", "application.md", "other.md", "app.txt"] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+        assert_eq!(
+            stale_pages(&pages, &dir).unwrap(),
+            [dir.join("app-removed.md")]
+        );
+    }
+
+    #[test]
+    fn check() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = Utf8PathBuf::try_from(temp_dir.path().to_owned()).unwrap();
+        let pages = pages();
+
+        assert!(check_pages(&pages, &dir).is_err());
+
+        write_pages(&pages, &dir).unwrap();
+        check_pages(&pages, &dir).unwrap();
+
+        std::fs::write(dir.join("app.md"), "outdated").unwrap();
+        assert!(check_pages(&pages, &dir).is_err());
+
+        write_pages(&pages, &dir).unwrap();
+        std::fs::write(dir.join("app-removed.md"), "").unwrap();
+        assert!(check_pages(&pages, &dir).is_err());
+    }
+}
@@ -1,15 +1,35 @@
 use std::fmt;
 
 use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
-use clap_complete::Generator;
+use clap::ValueEnum as _;
+use eyre::eyre;
 
-use crate::{Result, Run, config::Config, fs::ToRelative};
+use crate::{
+    Result, Run,
+    args::FingerprintArgs,
+    completion::{CompletionGenerator, Shell},
+    config::{Config, DistConfig},
+    fingerprint::Fingerprint,
+    fs::ToRelative,
+};
 
 /// Arguments definition of the `dist-build-completion` subcommand.
 #[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-build-completion.md"))]
 #[derive(Debug, Clone, Default, clap::Args)]
 #[non_exhaustive]
-pub struct DistBuildCompletion {}
+pub struct DistBuildCompletion {
+    /// Shell to generate completion files for
+    ///
+    /// One of `bash`, `elvish`, `fish`, `powershell`, `zsh`, `nushell` or the
+    /// name of a generator registered in the configuration. Can be specified
+    /// multiple times. Defaults to the shells and the generators in the
+    /// configuration.
+    #[clap(long = "shell", value_name = "SHELL")]
+    pub shells: Vec<String>,
+    /// Arguments to control the reuse of the previously generated artifacts.
+    #[clap(flatten)]
+    pub fingerprint_args: FingerprintArgs,
+}
 
 impl Run for DistBuildCompletion {
     fn run(&self, config: &Config) -> Result<()> {
@@ -17,99 +37,151 @@ impl Run for DistBuildCompletion {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Shell {
-    Bash,
-    Elvish,
-    Fish,
-    #[allow(clippy::enum_variant_names)]
-    PowerShell,
-    Zsh,
-    Nushell,
-}
-
-impl fmt::Display for Shell {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Shell::Bash => fmt::Display::fmt(&clap_complete::Shell::Bash, f),
-            Shell::Elvish => fmt::Display::fmt(&clap_complete::Shell::Elvish, f),
-            Shell::Fish => fmt::Display::fmt(&clap_complete::Shell::Fish, f),
-            Shell::PowerShell => fmt::Display::fmt(&clap_complete::Shell::PowerShell, f),
-            Shell::Zsh => fmt::Display::fmt(&clap_complete::Shell::Zsh, f),
-            Shell::Nushell => fmt::Display::fmt("nushell", f),
-        }
-    }
-}
-
-impl Generator for Shell {
-    fn file_name(&self, name: &str) -> String {
-        match self {
-            Shell::Bash => Generator::file_name(&clap_complete::Shell::Bash, name),
-            Shell::Elvish => Generator::file_name(&clap_complete::Shell::Elvish, name),
-            Shell::Fish => Generator::file_name(&clap_complete::Shell::Fish, name),
-            Shell::PowerShell => Generator::file_name(&clap_complete::Shell::PowerShell, name),
-            Shell::Zsh => Generator::file_name(&clap_complete::Shell::Zsh, name),
-            Shell::Nushell => Generator::file_name(&clap_complete_nushell::Nushell, name),
-        }
-    }
-
-    fn generate(&self, cmd: &clap::Command, buf: &mut dyn std::io::Write) {
-        match self {
-            Shell::Bash => Generator::generate(&clap_complete::Shell::Bash, cmd, buf),
-            Shell::Elvish => Generator::generate(&clap_complete::Shell::Elvish, cmd, buf),
-            Shell::Fish => Generator::generate(&clap_complete::Shell::Fish, cmd, buf),
-            Shell::PowerShell => Generator::generate(&clap_complete::Shell::PowerShell, cmd, buf),
-            Shell::Zsh => Generator::generate(&clap_complete::Shell::Zsh, cmd, buf),
-            Shell::Nushell => Generator::generate(&clap_complete_nushell::Nushell, cmd, buf),
-        }
-    }
-}
-
 impl DistBuildCompletion {
     /// Runs the `dist-build-completion` subcommand.
     #[tracing::instrument(name = "dist-build-completion", skip_all, err)]
     pub fn run(&self, config: &Config) -> Result<()> {
         tracing::info!("Building shell completion files...");
 
-        let Self {} = self;
+        let Self {
+            shells,
+            fingerprint_args,
+        } = self;
         let config = config.dist()?;
 
         let out_dir = config.dist_working_directory(None).join("completion");
-        crate::fs::remove_dir(&out_dir)?;
+        let generators = select_generators(config, shells)?;
 
-        let shells = [
-            Shell::Bash,
-            Shell::Elvish,
-            Shell::Fish,
-            Shell::PowerShell,
-            Shell::Zsh,
-            Shell::Nushell,
-        ];
+        let mut fingerprint = Fingerprint::new("completion");
+        for generator in &generators {
+            fingerprint.add(generator.to_string());
+        }
+        for package in config.packages() {
+            for target in package.targets() {
+                if let Some(cmd) = target.command() {
+                    fingerprint.add(target.name());
+                    fingerprint.add_command(cmd);
+                }
+            }
+        }
+        if !fingerprint_args.force && fingerprint.is_fresh(config)? {
+            tracing::info!("Shell completion files are up to date");
+            return Ok(());
+        }
+        fingerprint.remove_outputs(config)?;
+        crate::fs::remove_dir(&out_dir)?;
 
+        let mut outputs = vec![];
         for package in config.packages() {
             for target in package.targets() {
                 let target_name = target.name();
                 if let Some(cmd) = target.command() {
-                    for shell in shells {
-                        generate(shell, cmd, target_name, &out_dir)?;
+                    for generator in &generators {
+                        outputs.push(generate(generator, cmd, target_name, &out_dir)?);
                     }
                 }
             }
         }
+        fingerprint.save(config, &outputs)?;
 
         Ok(())
     }
 }
 
+/// Generator of the completion files for a shell.
+#[derive(Debug, Clone, Copy)]
+enum Generator<'a> {
+    Shell(Shell),
+    Custom(&'a CompletionGenerator),
+}
+
+impl fmt::Display for Generator<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Shell(shell) => fmt::Display::fmt(shell, f),
+            Self::Custom(generator) => f.write_str(generator.name()),
+        }
+    }
+}
+
+/// Returns the generators for the shells given on the command line, or the
+/// configured ones if no shell is given.
+fn select_generators<'a>(config: &'a DistConfig, shells: &[String]) -> Result<Vec<Generator<'a>>> {
+    let generators = if shells.is_empty() {
+        config
+            .completion_shells()
+            .iter()
+            .map(|shell| Generator::Shell(*shell))
+            .chain(config.completion_generators().iter().map(Generator::Custom))
+            .collect::<Vec<_>>()
+    } else {
+        shells
+            .iter()
+            .map(|name| {
+                if let Ok(shell) = Shell::from_str(name, true) {
+                    return Ok(Generator::Shell(shell));
+                }
+                config
+                    .completion_generators()
+                    .iter()
+                    .find(|generator| generator.name() == name)
+                    .map(Generator::Custom)
+                    .ok_or_else(|| eyre!("unknown shell: {name}"))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+    Ok(generators)
+}
+
 fn generate(
-    shell: Shell,
+    generator: &Generator,
     cmd: &clap::Command,
     bin_name: &str,
     out_dir: &Utf8Path,
 ) -> Result<Utf8PathBuf> {
     crate::fs::create_dir(out_dir)?;
-    let path = clap_complete::generate_to(shell, &mut cmd.clone(), bin_name, out_dir)?;
+    let mut cmd = cmd.clone();
+    let path = match generator {
+        Generator::Shell(shell) => clap_complete::generate_to(*shell, &mut cmd, bin_name, out_dir)?,
+        Generator::Custom(custom) => {
+            clap_complete::generate_to((*custom).clone(), &mut cmd, bin_name, out_dir)?
+        }
+    };
     let path = Utf8PathBuf::try_from(path)?;
-    tracing::info!("Generated {shell} completion file: {}", path.to_relative());
+    tracing::info!(
+        "Generated {generator} completion file: {}",
+        path.to_relative()
+    );
     Ok(path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clap_complete, config::DistConfigBuilder, workspace};
+
+    #[test]
+    fn generators() {
+        let config = DistConfigBuilder::new("completion-test", workspace::current())
+            .completion_shells([Shell::Bash, Shell::Zsh])
+            .completion_generator("bash-compat", clap_complete::Shell::Bash)
+            .build()
+            .unwrap();
+        let names = |shells: &[&str]| {
+            let shells = shells.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            select_generators(&config, &shells).map(|generators| {
+                generators
+                    .iter()
+                    .map(|generator| generator.to_string())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(names(&[]).unwrap(), ["bash", "zsh", "bash-compat"]);
+        assert_eq!(
+            names(&["FISH", "nushell", "bash-compat"]).unwrap(),
+            ["fish", "nushell", "bash-compat"]
+        );
+        assert!(names(&["unknown"]).is_err());
+    }
+}
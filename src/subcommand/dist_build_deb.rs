@@ -0,0 +1,562 @@
+use std::{
+    collections::BTreeSet,
+    fmt::Write as _,
+    fs::File,
+    io::{self, Write as _},
+};
+
+use cargo_metadata::{
+    Package,
+    camino::{Utf8Path, Utf8PathBuf},
+    semver::Version,
+};
+use flate2::{Compression, write::GzEncoder};
+
+use crate::{
+    Result, Run,
+    config::{Config, DistConfig, DistPackageConfig},
+    fhs::{self, FhsEntry, FhsEntryKind, FhsOptions},
+    spdx::Expression,
+};
+
+/// Arguments definition of the `dist-build-deb` subcommand.
+#[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-build-deb.md"))]
+#[derive(Debug, Clone, Default, clap::Args)]
+#[non_exhaustive]
+pub struct DistBuildDeb {}
+
+impl Run for DistBuildDeb {
+    fn run(&self, config: &Config) -> Result<()> {
+        self.run(config)
+    }
+}
+
+impl DistBuildDeb {
+    /// Runs the `dist-build-deb` subcommand.
+    #[tracing::instrument(name = "dist-build-deb", skip_all, err)]
+    pub fn run(&self, config: &Config) -> Result<()> {
+        tracing::info!("Building Debian packages...");
+
+        let Self {} = self;
+        let config = config.dist()?;
+
+        let options = FhsOptions {
+            zsh_completion_dir: "share/zsh/vendor-completions",
+        };
+
+        for target_triple in config.built_target_triples()? {
+            let Some(arch) = debian_arch(&target_triple) else {
+                tracing::info!("skipping non-Linux target: {target_triple}");
+                continue;
+            };
+            for package in config.packages() {
+                let entries = fhs::package_entries(config, package, &target_triple, options)?;
+                let path = build_deb(config, package, arch, &entries)?;
+                tracing::info!("Debian package created successfully: {path}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the Debian architecture name of the Linux target triple.
+///
+/// Only the GNU and musl environments are supported, since the binaries for
+/// other environments (e.g. Android) cannot run on Debian systems.
+fn debian_arch(target_triple: &str) -> Option<&'static str> {
+    let (_, env) = target_triple.split_once("-linux-")?;
+    if !env.starts_with("gnu") && !env.starts_with("musl") {
+        return None;
+    }
+    let arch = target_triple.split('-').next()?;
+    let is_hard_float = target_triple.ends_with("hf");
+    let arch = match arch {
+        "x86_64" => "amd64",
+        "i586" | "i686" => "i386",
+        "aarch64" => "arm64",
+        "armv7" | "thumbv7neon" => "armhf",
+        "arm" if is_hard_float => "armhf",
+        "arm" | "armv5te" => "armel",
+        "powerpc" => "powerpc",
+        "powerpc64" => "ppc64",
+        "powerpc64le" => "ppc64el",
+        "riscv64gc" => "riscv64",
+        "s390x" => "s390x",
+        "loongarch64" => "loong64",
+        "mips" => "mips",
+        "mipsel" => "mipsel",
+        "mips64" => "mips64",
+        "mips64el" => "mips64el",
+        "sparc64" => "sparc64",
+        _ => return None,
+    };
+    Some(arch)
+}
+
+fn build_deb(
+    config: &DistConfig,
+    package: &DistPackageConfig,
+    arch: &str,
+    entries: &[FhsEntry],
+) -> Result<Utf8PathBuf> {
+    let metadata = package.metadata();
+    let name = debian_package_name(&metadata.name);
+    let version = format!("{}-1", debian_version(&metadata.version));
+    let mtime = fhs::source_date_epoch()?;
+
+    let mut data_entries = entries
+        .iter()
+        .map(DataEntry::new)
+        .collect::<Result<Vec<_>>>()?;
+    data_entries.sort_by(|a, b| a.dest.cmp(&b.dest));
+
+    let copyright = copyright(metadata, &name)?;
+    let copyright_path = Utf8Path::new("usr/share/doc").join(&name).join("copyright");
+
+    let mut installed_size = copyright.len() as u64;
+    for entry in &data_entries {
+        installed_size += entry.size()?;
+    }
+
+    let control = control(
+        metadata,
+        &name,
+        &version,
+        arch,
+        installed_size.div_ceil(1024),
+    )?;
+    let conffiles = conffiles(entries);
+    let control_tar = {
+        let mut tar = TarBuilder::new(mtime);
+        tar.append_dir(".")?;
+        tar.append_file("./control", 0o644, control.as_bytes())?;
+        if !conffiles.is_empty() {
+            tar.append_file("./conffiles", 0o644, conffiles.as_bytes())?;
+        }
+        tar.finish()?
+    };
+
+    let data_tar = {
+        let mut tar = TarBuilder::new(mtime);
+        let dirs = data_entries
+            .iter()
+            .map(|entry| entry.dest.as_path())
+            .chain([copyright_path.as_path()])
+            .flat_map(|dest| dest.ancestors().skip(1))
+            .filter(|dir| !dir.as_str().is_empty())
+            .collect::<BTreeSet<_>>();
+        tar.append_dir(".")?;
+        for dir in dirs {
+            tar.append_dir(&format!("./{dir}/"))?;
+        }
+        for entry in &data_entries {
+            let path = format!("./{}", entry.dest);
+            match &entry.data {
+                Some(data) => tar.append_file(&path, entry.mode, data)?,
+                None => {
+                    let file = File::open(entry.src)?;
+                    let size = file.metadata()?.len();
+                    tar.append_reader(&path, entry.mode, size, file)?;
+                }
+            }
+        }
+        tar.append_file(&format!("./{copyright_path}"), 0o644, copyright.as_bytes())?;
+        tar.finish()?
+    };
+
+    let deb_path = config
+        .dist_target_directory()
+        .join(format!("{name}_{version}_{arch}.deb"));
+    let mut deb = crate::fs::create_file(&deb_path)?;
+    deb.write_all(b"!<arch>\n")?;
+    write_ar_entry(&mut deb, "debian-binary", mtime, b"2.0\n")?;
+    write_ar_entry(&mut deb, "control.tar.gz", mtime, &control_tar)?;
+    write_ar_entry(&mut deb, "data.tar.gz", mtime, &data_tar)?;
+    deb.sync_all()?;
+
+    Ok(deb_path)
+}
+
+/// File installed by the Debian package.
+struct DataEntry<'a> {
+    /// Path of the staged artifact.
+    src: &'a Utf8Path,
+    /// Path in the data archive, relative to the root directory.
+    dest: Utf8PathBuf,
+    mode: u32,
+    /// Contents of the file if they differ from the staged artifact.
+    data: Option<Vec<u8>>,
+}
+
+impl<'a> DataEntry<'a> {
+    fn new(entry: &'a FhsEntry) -> Result<Self> {
+        let dest = entry.system_path();
+        // Debian policy requires man pages compressed with `gzip -9n`
+        if entry.kind == FhsEntryKind::ManPage {
+            let mut enc = GzEncoder::new(vec![], Compression::best());
+            io::copy(&mut File::open(&entry.src)?, &mut enc)?;
+            return Ok(Self {
+                src: &entry.src,
+                dest: Utf8PathBuf::from(format!("{dest}.gz")),
+                mode: entry.mode,
+                data: Some(enc.finish()?),
+            });
+        }
+        Ok(Self {
+            src: &entry.src,
+            dest,
+            mode: entry.mode,
+            data: None,
+        })
+    }
+
+    fn size(&self) -> Result<u64> {
+        match &self.data {
+            Some(data) => Ok(data.len() as u64),
+            None => Ok(self.src.metadata()?.len()),
+        }
+    }
+}
+
+/// Converts the cargo package name into a valid Debian package name.
+fn debian_package_name(name: &str) -> String {
+    name.to_lowercase().replace('_', "-")
+}
+
+/// Converts the semver version into a Debian upstream version.
+///
+/// Debian upstream versions cannot contain `-` (it separates the Debian
+/// revision), and pre-release versions are separated with `~`, so that they
+/// are sorted before the release version.
+fn debian_version(version: &Version) -> String {
+    let mut s = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if !version.pre.is_empty() {
+        s.push('~');
+        s.push_str(&version.pre.as_str().replace('-', "."));
+    }
+    if !version.build.is_empty() {
+        s.push('+');
+        s.push_str(&version.build.as_str().replace('-', "."));
+    }
+    s
+}
+
+fn control(
+    package: &Package,
+    name: &str,
+    version: &str,
+    arch: &str,
+    installed_size: u64,
+) -> Result<String> {
+    let maintainer = match package.authors.first() {
+        Some(author) => author.clone(),
+        None => {
+            tracing::warn!(
+                "`authors` of package `{}` is empty, using a placeholder as the maintainer of the Debian package",
+                package.name
+            );
+            format!("{} maintainers", package.name)
+        }
+    };
+    let description = package
+        .description
+        .as_deref()
+        .unwrap_or(&package.name)
+        .trim();
+    let (synopsis, extended) = description.split_once('\n').unwrap_or((description, ""));
+
+    let mut control = String::new();
+    writeln!(control, "Package: {name}")?;
+    writeln!(control, "Version: {version}")?;
+    writeln!(control, "Architecture: {arch}")?;
+    writeln!(control, "Maintainer: {maintainer}")?;
+    writeln!(control, "Installed-Size: {installed_size}")?;
+    writeln!(control, "Section: utils")?;
+    writeln!(control, "Priority: optional")?;
+    if let Some(homepage) = package.homepage.as_ref().or(package.repository.as_ref()) {
+        writeln!(control, "Homepage: {homepage}")?;
+    }
+    writeln!(control, "Description: {}", synopsis.trim())?;
+    for line in extended.trim().lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            writeln!(control, " .")?;
+        } else {
+            writeln!(control, " {line}")?;
+        }
+    }
+    Ok(control)
+}
+
+/// Lists the configuration files of the package, which are preserved by dpkg
+/// when modified by the user.
+fn conffiles(entries: &[FhsEntry]) -> String {
+    entries
+        .iter()
+        .filter(|entry| entry.is_config())
+        .map(|entry| format!("/{}\n", entry.system_path()))
+        .collect()
+}
+
+/// Generates the machine-readable `copyright` file of the package.
+///
+/// `NOASSERTION` is written as a placeholder for the copyright holders and the
+/// license which are not specified in the package metadata.
+fn copyright(package: &Package, name: &str) -> Result<String> {
+    let license = match package.license.as_deref().map(str::parse::<Expression>) {
+        Some(Ok(license)) => Some(license),
+        Some(Err(e)) => {
+            tracing::warn!(
+                "`license` of package `{}` is not a valid SPDX expression, using `NOASSERTION` as the license of the Debian package: {e}",
+                package.name
+            );
+            None
+        }
+        None => {
+            tracing::warn!(
+                "`license` of package `{}` is not set, using `NOASSERTION` as the license of the Debian package",
+                package.name
+            );
+            None
+        }
+    };
+
+    let mut copyright = String::new();
+    writeln!(
+        copyright,
+        "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/"
+    )?;
+    writeln!(copyright, "Upstream-Name: {}", package.name)?;
+    if let Some(repository) = &package.repository {
+        writeln!(copyright, "Source: {repository}")?;
+    }
+    writeln!(copyright)?;
+    writeln!(copyright, "Files: *")?;
+    if package.authors.is_empty() {
+        writeln!(copyright, "Copyright: NOASSERTION")?;
+    } else {
+        writeln!(copyright, "Copyright: {}", package.authors.join("\n "))?;
+    }
+    match &license {
+        Some(license) => writeln!(copyright, "License: {}", dep5_license(license))?,
+        None => writeln!(copyright, "License: NOASSERTION")?,
+    }
+
+    let mut ids = BTreeSet::new();
+    match &license {
+        Some(license) => collect_license_ids(license, &mut ids),
+        None => {
+            ids.insert("NOASSERTION");
+        }
+    }
+    for id in ids {
+        writeln!(copyright)?;
+        writeln!(copyright, "License: {id}")?;
+        if id == "NOASSERTION" {
+            writeln!(copyright, " The license of the package is not specified.")?;
+        } else {
+            writeln!(
+                copyright,
+                " See the license files in /usr/share/doc/{name}/ or\n https://spdx.org/licenses/{id}.html"
+            )?;
+        }
+    }
+    Ok(copyright)
+}
+
+/// Converts the SPDX license expression into the license syntax of the
+/// machine-readable `copyright` file.
+///
+/// `and` binds tighter than `or`, and a comma lowers the precedence of the
+/// preceding `or` expressions, as the syntax has no parentheses.
+fn dep5_license(expr: &Expression) -> String {
+    match expr {
+        Expression::License {
+            id,
+            exception: None,
+        } => id.clone(),
+        Expression::License {
+            id,
+            exception: Some(exception),
+        } => {
+            let exception = exception.strip_suffix("-exception").unwrap_or(exception);
+            format!("{id} with {exception} exception")
+        }
+        Expression::And(lhs, rhs) => {
+            let has_or = [lhs, rhs]
+                .iter()
+                .any(|expr| matches!(***expr, Expression::Or(..)));
+            let sep = if has_or { ", and" } else { " and" };
+            format!("{}{sep} {}", dep5_license(lhs), dep5_license(rhs))
+        }
+        Expression::Or(lhs, rhs) => format!("{} or {}", dep5_license(lhs), dep5_license(rhs)),
+    }
+}
+
+fn collect_license_ids<'a>(expr: &'a Expression, ids: &mut BTreeSet<&'a str>) {
+    match expr {
+        Expression::License { id, .. } => {
+            ids.insert(id);
+        }
+        Expression::And(lhs, rhs) | Expression::Or(lhs, rhs) => {
+            collect_license_ids(lhs, ids);
+            collect_license_ids(rhs, ids);
+        }
+    }
+}
+
+fn write_ar_entry(w: &mut impl io::Write, name: &str, mtime: u64, data: &[u8]) -> Result<()> {
+    // Common ar header: name, mtime, owner, group, mode, size, magic
+    writeln!(
+        w,
+        "{name:<16}{mtime:<12}{:<6}{:<6}{:<8}{:<10}`",
+        0,
+        0,
+        "100644",
+        data.len()
+    )?;
+    w.write_all(data)?;
+    if !data.len().is_multiple_of(2) {
+        w.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Builds a `tar.gz` archive whose entries are owned by root.
+struct TarBuilder {
+    builder: tar::Builder<GzEncoder<Vec<u8>>>,
+    mtime: u64,
+}
+
+impl TarBuilder {
+    fn new(mtime: u64) -> Self {
+        let enc = GzEncoder::new(vec![], Compression::best());
+        Self {
+            builder: tar::Builder::new(enc),
+            mtime,
+        }
+    }
+
+    fn header(&self, entry_type: tar::EntryType, mode: u32, size: u64) -> Result<tar::Header> {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(entry_type);
+        header.set_mode(mode);
+        header.set_size(size);
+        header.set_mtime(self.mtime);
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_username("root")?;
+        header.set_groupname("root")?;
+        Ok(header)
+    }
+
+    fn append_dir(&mut self, path: &str) -> Result<()> {
+        let mut header = self.header(tar::EntryType::Directory, 0o755, 0)?;
+        self.builder.append_data(&mut header, path, io::empty())?;
+        Ok(())
+    }
+
+    fn append_file(&mut self, path: &str, mode: u32, data: &[u8]) -> Result<()> {
+        self.append_reader(path, mode, data.len() as u64, data)
+    }
+
+    fn append_reader(
+        &mut self,
+        path: &str,
+        mode: u32,
+        size: u64,
+        reader: impl io::Read,
+    ) -> Result<()> {
+        let mut header = self.header(tar::EntryType::Regular, mode, size)?;
+        self.builder.append_data(&mut header, path, reader)?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Vec<u8>> {
+        let enc = self.builder.into_inner()?;
+        Ok(enc.finish()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arch() {
+        let cases = [
+            ("x86_64-unknown-linux-gnu", Some("amd64")),
+            ("x86_64-unknown-linux-musl", Some("amd64")),
+            ("i686-unknown-linux-gnu", Some("i386")),
+            ("aarch64-unknown-linux-gnu", Some("arm64")),
+            ("armv7-unknown-linux-gnueabihf", Some("armhf")),
+            ("arm-unknown-linux-gnueabihf", Some("armhf")),
+            ("arm-unknown-linux-gnueabi", Some("armel")),
+            ("armv5te-unknown-linux-musleabi", Some("armel")),
+            ("powerpc64le-unknown-linux-gnu", Some("ppc64el")),
+            ("riscv64gc-unknown-linux-gnu", Some("riscv64")),
+            ("aarch64-linux-android", None),
+            ("x86_64-linux-android", None),
+            ("armv7-linux-androideabi", None),
+            ("aarch64-unknown-linux-ohos", None),
+            ("x86_64-pc-windows-msvc", None),
+            ("aarch64-apple-darwin", None),
+            ("wasm32-unknown-unknown", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(debian_arch(input), expected, "input: {input}");
+        }
+    }
+
+    #[test]
+    fn package_name() {
+        let cases = [
+            ("app", "app"),
+            ("my_app", "my-app"),
+            ("My_App-cli", "my-app-cli"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(debian_package_name(input), expected, "input: {input}");
+        }
+    }
+
+    #[test]
+    fn version() {
+        let cases = [
+            ("1.0.0", "1.0.0"),
+            ("1.0.0-alpha", "1.0.0~alpha"),
+            ("1.0.0-alpha.1", "1.0.0~alpha.1"),
+            ("1.0.0-alpha-1", "1.0.0~alpha.1"),
+            ("1.0.0+build-5", "1.0.0+build.5"),
+            ("1.0.0-rc-1+git-abc", "1.0.0~rc.1+git.abc"),
+        ];
+        for (input, expected) in cases {
+            let version = input.parse::<Version>().unwrap();
+            let version = debian_version(&version);
+            assert_eq!(version, expected, "input: {input}");
+            assert!(!version.contains('-'), "input: {input}");
+        }
+    }
+
+    #[test]
+    fn license() {
+        let cases = [
+            ("MIT", "MIT"),
+            ("MIT OR Apache-2.0", "MIT or Apache-2.0"),
+            ("MIT AND Unicode-3.0", "MIT and Unicode-3.0"),
+            (
+                "(MIT OR Apache-2.0) AND Unicode-3.0",
+                "MIT or Apache-2.0, and Unicode-3.0",
+            ),
+            (
+                "Apache-2.0 WITH LLVM-exception OR MIT",
+                "Apache-2.0 with LLVM exception or MIT",
+            ),
+        ];
+        for (input, expected) in cases {
+            let expr = input.parse::<Expression>().unwrap();
+            assert_eq!(dep5_license(&expr), expected, "input: {input}");
+        }
+    }
+}
@@ -1,12 +1,19 @@
-use eyre::eyre;
+use cargo_metadata::camino::Utf8Path;
 
-use crate::{Result, Run, config::Config, fs::ToRelative};
+use crate::{Result, Run, args::FingerprintArgs, config::Config, fingerprint::Fingerprint};
 
 /// Arguments definition of the `dist-build-doc` subcommand.
+///
+/// The extra files of the packages are also placed in the `extra/<package>/`
+/// directory of the dist working directory by this subcommand.
 #[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-build-doc.md"))]
 #[derive(Debug, Clone, Default, clap::Args)]
 #[non_exhaustive]
-pub struct DistBuildDoc {}
+pub struct DistBuildDoc {
+    /// Arguments to control the reuse of the previously generated artifacts.
+    #[clap(flatten)]
+    pub fingerprint_args: FingerprintArgs,
+}
 
 impl Run for DistBuildDoc {
     fn run(&self, config: &Config) -> Result<()> {
@@ -18,20 +25,39 @@ impl DistBuildDoc {
     /// Runs the `dist-build-doc` subcommand.
     #[tracing::instrument(name = "dist-build-doc", skip_all, err)]
     pub fn run(&self, config: &Config) -> Result<()> {
-        tracing::info!("Building documents...");
+        tracing::info!("Building documents and extra files...");
 
-        let Self {} = self;
+        let Self { fingerprint_args } = self;
         let config = config.dist()?;
 
         let packages = config.packages();
 
         let working_dir = config.dist_working_directory(None);
         let doc_dir = working_dir.join("doc");
+        let extra_dir = working_dir.join("extra");
         let add_package_dir = packages.len() > 1;
-        crate::fs::remove_dir(&doc_dir)?;
 
-        let Self {} = self;
+        let mut fingerprint = Fingerprint::new("doc");
+        for package in packages {
+            fingerprint.add(package.name().as_ref());
+            for file in package
+                .document_dist_files()
+                .iter()
+                .chain(package.extra_files())
+            {
+                fingerprint.add(file);
+                fingerprint.add_file(file.src())?;
+            }
+        }
+        if !fingerprint_args.force && fingerprint.is_fresh(config)? {
+            tracing::info!("Documents and extra files are up to date");
+            return Ok(());
+        }
+        fingerprint.remove_outputs(config)?;
+        crate::fs::remove_dir(&doc_dir)?;
+        crate::fs::remove_dir(&extra_dir)?;
 
+        let mut outputs = vec![];
         for package in packages {
             let dest_dir = if add_package_dir {
                 doc_dir.join(package.name().as_ref())
@@ -39,15 +65,34 @@ impl DistBuildDoc {
                 doc_dir.clone()
             };
 
-            for src_file in package.documents() {
-                let file_name = src_file.file_name().ok_or_else(|| {
-                    eyre!("document file has no name: {}", src_file.to_relative())
-                })?;
-                let dest_file = dest_dir.join(file_name);
-                crate::fs::copy(src_file, dest_file)?;
+            for file in package.document_dist_files() {
+                let dest_file = dest_dir.join(file.dest());
+                crate::fs::copy(file.src(), &dest_file)?;
+                outputs.push(dest_file);
+            }
+
+            for file in package.extra_files() {
+                let dest_file = extra_dir.join(package.name().as_ref()).join(file.dest());
+                crate::fs::copy(file.src(), &dest_file)?;
+                set_mode(&dest_file, file.mode())?;
+                outputs.push(dest_file);
             }
         }
+        fingerprint.save(config, &outputs)?;
 
         Ok(())
     }
 }
+
+#[cfg(unix)]
+fn set_mode(path: &Utf8Path, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Utf8Path, _mode: u32) -> Result<()> {
+    Ok(())
+}
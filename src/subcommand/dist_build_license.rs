@@ -1,12 +1,31 @@
-use eyre::eyre;
+use std::{collections::BTreeMap, io::Write, sync::LazyLock};
 
-use crate::{Result, Run, config::Config, fs::ToRelative};
+use cargo_metadata::Package;
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    Result, Run,
+    args::FingerprintArgs,
+    config::{Config, DistConfig},
+    dependency::{self, THIRD_PARTY_LICENSES},
+    fingerprint::Fingerprint,
+    workspace::PackageExt as _,
+};
 
 /// Arguments definition of the `dist-build-license` subcommand.
 #[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-build-license.md"))]
 #[derive(Debug, Clone, Default, clap::Args)]
 #[non_exhaustive]
-pub struct DistBuildLicense {}
+pub struct DistBuildLicense {
+    /// Also build the license files of the third-party crates the executables
+    /// depend on
+    #[clap(long)]
+    pub third_party: bool,
+
+    /// Arguments to control the reuse of the previously generated artifacts.
+    #[clap(flatten)]
+    pub fingerprint_args: FingerprintArgs,
+}
 
 impl Run for DistBuildLicense {
     fn run(&self, config: &Config) -> Result<()> {
@@ -20,39 +39,313 @@ impl DistBuildLicense {
     pub fn run(&self, config: &Config) -> Result<()> {
         tracing::info!("Building license files...");
 
-        let Self {} = self;
+        let Self {
+            third_party,
+            fingerprint_args,
+        } = self;
         let config = config.dist()?;
 
-        let packages = config.packages();
+        build_package_licenses(config, fingerprint_args.force)?;
+        if *third_party || config.third_party_licenses() {
+            build_third_party_licenses(config)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Copies the license files of the distributed packages.
+fn build_package_licenses(config: &DistConfig, force: bool) -> Result<()> {
+    let packages = config.packages();
+
+    let working_dir = config.dist_working_directory(None);
+    let mut fingerprint = Fingerprint::new("license");
+    for package in packages {
+        fingerprint.add(package.name().as_ref());
+        for file in package.license_dist_files() {
+            fingerprint.add(file.dest());
+            fingerprint.add_file(file.src())?;
+        }
+    }
+    if !force && fingerprint.is_fresh(config)? {
+        tracing::info!("License files are up to date");
+        return Ok(());
+    }
+    fingerprint.remove_outputs(config)?;
+
+    let license_dir;
+    let add_package_dir;
+    if packages.len() > 1 {
+        license_dir = working_dir.join("license");
+        add_package_dir = true;
+        crate::fs::remove_dir(&license_dir)?;
+    } else {
+        license_dir = working_dir;
+        add_package_dir = false;
+    }
 
-        let working_dir = config.dist_working_directory(None);
-        let license_dir;
-        let add_package_dir;
-        if packages.len() > 1 {
-            license_dir = working_dir.join("license");
-            add_package_dir = true;
-            crate::fs::remove_dir(&license_dir)?;
+    let mut outputs = vec![];
+    for package in config.packages() {
+        let dest_dir = if add_package_dir {
+            license_dir.join(package.name().as_ref())
         } else {
-            license_dir = working_dir;
-            add_package_dir = false;
-        }
-
-        for package in config.packages() {
-            let dest_dir = if add_package_dir {
-                license_dir.join(package.name().as_ref())
-            } else {
-                license_dir.clone()
-            };
-
-            for src_file in package.license_files() {
-                let file_name = src_file
-                    .file_name()
-                    .ok_or_else(|| eyre!("license file has no name: {}", src_file.to_relative()))?;
-                let dest_file = dest_dir.join(file_name);
-                crate::fs::copy(src_file, dest_file)?;
+            license_dir.clone()
+        };
+
+        for file in package.license_dist_files() {
+            let dest_file = dest_dir.join(file.dest());
+            crate::fs::copy(file.src(), &dest_file)?;
+            outputs.push(dest_file);
+        }
+    }
+    fingerprint.save(config, &outputs)?;
+
+    Ok(())
+}
+
+/// Writes the license files of the third-party crates for each built target
+/// triple.
+fn build_third_party_licenses(config: &DistConfig) -> Result<()> {
+    let target_triples = config.built_target_triples()?;
+    if target_triples.is_empty() {
+        tracing::warn!(
+            "no built targets found, skipping third-party licenses (run `cargo xtask dist-build-bin` first)"
+        );
+        return Ok(());
+    }
+
+    for target_triple in &target_triples {
+        // the packages may be built with different features
+        let metadata = config
+            .packages()
+            .iter()
+            .map(|package| dependency::package_metadata(config, package, target_triple))
+            .collect::<Result<Vec<_>>>()?;
+        let mut dependencies = BTreeMap::new();
+        for (package, metadata) in config.packages().iter().zip(&metadata) {
+            for dependency in dependency::normal_dependencies(metadata, &[&package.metadata().id])?
+            {
+                dependencies.insert(&dependency.id, dependency);
             }
         }
+        let mut dependencies = dependencies.into_values().collect::<Vec<_>>();
+        dependencies.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        let crates = dependencies
+            .into_iter()
+            .map(ThirdPartyCrate::collect)
+            .collect::<Result<Vec<_>>>()?;
 
-        Ok(())
+        let dir = config.dist_working_directory(Some(target_triple));
+        let title = format!(
+            "Third-party licenses of {} ({target_triple})",
+            config.name()
+        );
+
+        let mut file = crate::fs::create_file(dir.join(THIRD_PARTY_LICENSES))?;
+        write_text(&mut file, &title, &crates)?;
+        let mut file = crate::fs::create_file(dir.join(format!("{THIRD_PARTY_LICENSES}.html")))?;
+        write_html(&mut file, &title, &crates)?;
+        tracing::info!(
+            "{target_triple}: collected licenses of {} third-party crates",
+            crates.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Third-party crate and its license texts.
+struct ThirdPartyCrate<'a> {
+    package: &'a Package,
+    /// Names and contents of the license files
+    license_texts: Vec<(String, String)>,
+}
+
+impl<'a> ThirdPartyCrate<'a> {
+    fn collect(package: &'a Package) -> Result<Self> {
+        static RE: LazyLock<Regex> = LazyLock::new(|| {
+            RegexBuilder::new(r"^(?:LICEN[CS]E|COPYING|NOTICE|UNLICENSE)(?:-|_|\.|$)")
+                .case_insensitive(true)
+                .build()
+                .unwrap()
+        });
+
+        let src_dir = package.root_directory();
+        let mut files = vec![];
+        if let Some(license_file) = &package.license_file {
+            files.push(src_dir.join(license_file));
+        }
+        for entry in src_dir.read_dir_utf8()? {
+            let entry = entry?;
+            if entry.file_type()?.is_file()
+                && RE.is_match(entry.file_name())
+                && !files.iter().any(|file| file == entry.path())
+            {
+                files.push(entry.into_path());
+            }
+        }
+        files.sort();
+
+        let license_texts = files
+            .into_iter()
+            .map(|file| {
+                let name = file.file_name().unwrap_or_default().to_owned();
+                let text = String::from_utf8_lossy(&std::fs::read(&file)?).into_owned();
+                Ok((name, text))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            package,
+            license_texts,
+        })
+    }
+
+    fn license(&self) -> &str {
+        self.package.license.as_deref().unwrap_or("unknown")
+    }
+}
+
+fn write_text(w: &mut impl Write, title: &str, crates: &[ThirdPartyCrate]) -> Result<()> {
+    let rule = "-".repeat(80);
+    writeln!(w, "{title}")?;
+    writeln!(w, "{}", "=".repeat(title.chars().count()))?;
+    writeln!(w)?;
+    for krate in crates {
+        let package = krate.package;
+        writeln!(
+            w,
+            "* {} {} ({})",
+            package.name,
+            package.version,
+            krate.license()
+        )?;
+    }
+    for krate in crates {
+        let package = krate.package;
+        writeln!(w)?;
+        writeln!(w, "{rule}")?;
+        writeln!(w)?;
+        writeln!(w, "{} {}", package.name, package.version)?;
+        writeln!(w, "License: {}", krate.license())?;
+        if let Some(repository) = &package.repository {
+            writeln!(w, "Repository: {repository}")?;
+        }
+        if krate.license_texts.is_empty() {
+            writeln!(w)?;
+            writeln!(w, "(no license file found in the package)")?;
+        }
+        for (name, text) in &krate.license_texts {
+            writeln!(w)?;
+            writeln!(w, "--- {name} ---")?;
+            writeln!(w)?;
+            writeln!(w, "{}", text.trim_end())?;
+        }
+    }
+    Ok(())
+}
+
+fn write_html(w: &mut impl Write, title: &str, crates: &[ThirdPartyCrate]) -> Result<()> {
+    let id = |package: &Package| format!("{}-{}", package.name, package.version);
+    writeln!(w, "<!DOCTYPE html>")?;
+    writeln!(w, "<html>")?;
+    writeln!(w, "<head>")?;
+    writeln!(w, "<meta charset=\"utf-8\">")?;
+    writeln!(w, "<title>{}</title>", escape_html(title))?;
+    writeln!(w, "</head>")?;
+    writeln!(w, "<body>")?;
+    writeln!(w, "<h1>{}</h1>", escape_html(title))?;
+    writeln!(w, "<ul>")?;
+    for krate in crates {
+        let package = krate.package;
+        writeln!(
+            w,
+            "<li><a href=\"#{}\">{} {}</a> ({})</li>",
+            escape_html(&id(package)),
+            escape_html(&package.name),
+            package.version,
+            escape_html(krate.license())
+        )?;
+    }
+    writeln!(w, "</ul>")?;
+    for krate in crates {
+        let package = krate.package;
+        writeln!(w, "<section id=\"{}\">", escape_html(&id(package)))?;
+        writeln!(
+            w,
+            "<h2>{} {}</h2>",
+            escape_html(&package.name),
+            package.version
+        )?;
+        writeln!(w, "<p>License: {}</p>", escape_html(krate.license()))?;
+        if let Some(repository) = &package.repository {
+            let repository = escape_html(repository);
+            writeln!(
+                w,
+                "<p>Repository: <a href=\"{repository}\">{repository}</a></p>"
+            )?;
+        }
+        if krate.license_texts.is_empty() {
+            writeln!(w, "<p>(no license file found in the package)</p>")?;
+        }
+        for (name, text) in &krate.license_texts {
+            writeln!(w, "<h3>{}</h3>", escape_html(name))?;
+            writeln!(w, "<pre>{}</pre>", escape_html(text.trim_end()))?;
+        }
+        writeln!(w, "</section>")?;
+    }
+    writeln!(w, "</body>")?;
+    writeln!(w, "</html>")?;
+    Ok(())
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace;
+
+    #[test]
+    fn html() {
+        assert_eq!(
+            escape_html(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn text() {
+        let package = workspace::current()
+            .packages
+            .iter()
+            .find(|package| package.name.as_str() == "eyre")
+            .unwrap();
+        let krate = ThirdPartyCrate::collect(package).unwrap();
+        assert!(!krate.license_texts.is_empty());
+
+        let mut text = vec![];
+        write_text(&mut text, "Title", std::slice::from_ref(&krate)).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert!(text.starts_with(&format!(
+            "Title\n=====\n\n* eyre {} ({})\n",
+            package.version,
+            krate.license()
+        )));
+        for (name, _) in &krate.license_texts {
+            assert!(text.contains(&format!("--- {name} ---")), "name: {name}");
+        }
     }
 }
@@ -1,16 +1,28 @@
-use std::iter;
+use std::{io::Write, process::Command};
 
 use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
-use chrono::{Datelike, Utc};
+use chrono::{DateTime, Utc};
 use clap_mangen::Man;
+use eyre::ensure;
 
-use crate::{Result, Run, config::Config};
+use crate::{
+    Result, Run,
+    args::FingerprintArgs,
+    command_tree::iterate_commands,
+    config::{Config, DistConfig, DistTargetConfig, ManDate},
+    fingerprint::Fingerprint,
+    process::CommandExt,
+};
 
 /// Arguments definition of the `dist-build-man` subcommand.
 #[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-build-man.md"))]
 #[derive(Debug, Clone, Default, clap::Args)]
 #[non_exhaustive]
-pub struct DistBuildMan {}
+pub struct DistBuildMan {
+    /// Arguments to control the reuse of the previously generated artifacts.
+    #[clap(flatten)]
+    pub fingerprint_args: FingerprintArgs,
+}
 
 impl Run for DistBuildMan {
     fn run(&self, config: &Config) -> Result<()> {
@@ -24,48 +36,86 @@ impl DistBuildMan {
     pub fn run(&self, config: &Config) -> Result<()> {
         tracing::info!("Building man pages...");
 
-        let Self {} = self;
+        let Self { fingerprint_args } = self;
         let config = config.dist()?;
 
         let man_dir = config.dist_working_directory(None).join("man");
-        let section = "1";
-        crate::fs::remove_dir(&man_dir)?;
 
+        let mut pages = vec![];
         for package in config.packages() {
             for target in package.targets() {
                 if let Some(cmd) = target.command() {
-                    let it = dist_build_man_pages(&man_dir, package.name(), cmd.clone(), section)?;
-                    for res in it {
-                        let (path, man) = res?;
-                        let mut file = crate::fs::create_file(path)?;
-                        man.render(&mut file)?;
-                    }
+                    let date = man_date(config, target.man_date())?;
+                    pages.push((package.name().as_ref(), target, cmd, date));
+                }
+            }
+        }
+
+        let mut fingerprint = Fingerprint::new("man");
+        for (package_name, target, cmd, date) in &pages {
+            fingerprint.add(package_name);
+            fingerprint.add_command(cmd);
+            fingerprint.add(target.man_section());
+            fingerprint.add(target.man_manual());
+            fingerprint.add(target.man_extra_sections());
+            fingerprint.add(date);
+        }
+        if !fingerprint_args.force && fingerprint.is_fresh(config)? {
+            tracing::info!("Man pages are up to date");
+            return Ok(());
+        }
+        fingerprint.remove_outputs(config)?;
+        crate::fs::remove_dir(&man_dir)?;
+
+        let mut outputs = vec![];
+        for (package_name, target, cmd, date) in pages {
+            let it = dist_build_man_pages(&man_dir, package_name, target, cmd.clone(), date)?;
+            for res in it {
+                let ManPage {
+                    path,
+                    man,
+                    extra_sections,
+                } = res?;
+                let mut file = crate::fs::create_file(&path)?;
+                man.render(&mut file)?;
+                for (title, content) in extra_sections {
+                    render_extra_section(&mut file, title, content)?;
                 }
+                outputs.push(path);
             }
         }
+        fingerprint.save(config, &outputs)?;
 
         Ok(())
     }
 }
 
-fn dist_build_man_pages(
+struct ManPage<'a> {
+    path: Utf8PathBuf,
+    man: Man,
+    /// Sections appended to the page rendered by [`Man`]
+    extra_sections: &'a [(String, String)],
+}
+
+fn dist_build_man_pages<'a>(
     man_dir: &Utf8Path,
     package_name: &str,
+    target: &'a DistTargetConfig,
     cmd: clap::Command,
-    section: impl Into<String>,
-) -> Result<impl Iterator<Item = Result<(Utf8PathBuf, Man)>>> {
-    let capitalized_name = {
-        let mut cs = package_name.chars();
-        match cs.next() {
-            Some(c) => c.to_uppercase().collect::<String>() + cs.as_str(),
-            None => String::new(),
+    date: String,
+) -> Result<impl Iterator<Item = Result<ManPage<'a>>>> {
+    let section = target.man_section().to_owned();
+    let manual_name = match target.man_manual() {
+        Some(manual) => manual.to_owned(),
+        None => {
+            let mut cs = package_name.chars();
+            let capitalized_name = match cs.next() {
+                Some(c) => c.to_uppercase().collect::<String>() + cs.as_str(),
+                None => String::new(),
+            };
+            format!("{capitalized_name} Command Manual")
         }
     };
-    let section = section.into();
-
-    let now = Utc::now();
-    let manual_name = format!("{capitalized_name} Command Manual");
-    let date = format!("{:04}-{:02}-{:02}", now.year(), now.month(), now.day());
     let source = format!(
         "{} {}",
         cmd.get_name().replace(' ', "-"),
@@ -75,7 +125,14 @@ fn dist_build_man_pages(
     );
 
     let man_dir = man_dir.to_owned();
+    let root_name = cmd.get_name().to_owned();
     let it = iterate_commands(cmd).map(move |cmd| {
+        // extra sections are added only to the page of the target command
+        let extra_sections = if cmd.get_name() == root_name {
+            target.man_extra_sections()
+        } else {
+            &[]
+        };
         let command_name = cmd.get_name().replace(' ', "-");
         let filename = format!("{command_name}.{}", section);
         let path = man_dir.join(filename);
@@ -85,44 +142,112 @@ fn dist_build_man_pages(
             .date(&date)
             .source(&source)
             .manual(&manual_name);
-        Ok((path, man))
+        Ok(ManPage {
+            path,
+            man,
+            extra_sections,
+        })
     });
 
     Ok(it)
 }
 
-fn iterate_commands(cmd: clap::Command) -> Box<dyn Iterator<Item = clap::Command>> {
-    #[allow(clippy::needless_collect)]
-    let subcommands = cmd.get_subcommands().cloned().collect::<Vec<_>>();
-    let command_name = cmd.get_name().to_string();
-    let command_version = cmd.get_version().map(str::to_string);
-    let command_long_version = cmd.get_long_version().map(str::to_string);
-    let command_author = cmd.get_author().map(str::to_string);
-
-    let it = iter::once(cmd).chain(
-        subcommands
-            .into_iter()
-            .map(move |mut subcommand| {
-                let name = format!("{command_name} {}", subcommand.get_name());
-                subcommand = subcommand.name(name);
-                if subcommand.get_version().is_none()
-                    && let Some(version) = &command_version
-                {
-                    subcommand = subcommand.version(version);
-                }
-                if subcommand.get_long_version().is_none()
-                    && let Some(long_version) = &command_long_version
-                {
-                    subcommand = subcommand.long_version(long_version);
-                }
-                if subcommand.get_author().is_none()
-                    && let Some(author) = &command_author
-                {
-                    subcommand = subcommand.author(author);
-                }
-                subcommand
-            })
-            .flat_map(iterate_commands),
-    );
-    Box::new(it)
+/// Returns the date recorded in the man pages in `YYYY-MM-DD` format.
+fn man_date(config: &DistConfig, date: &ManDate) -> Result<String> {
+    let format = |date: DateTime<Utc>| date.format("%Y-%m-%d").to_string();
+    let date = match date {
+        ManDate::Today => format(Utc::now()),
+        ManDate::CommitDate => {
+            let date = Command::new("git")
+                .args(["log", "-1", "--format=%cs"])
+                .workspace_stdout(config.metadata())?;
+            let date = date.trim();
+            ensure!(
+                !date.is_empty(),
+                "no commit found in the workspace repository"
+            );
+            date.to_owned()
+        }
+        ManDate::SourceDateEpoch => match crate::time::source_date_epoch()? {
+            Some(timestamp) => format(crate::time::to_date_time(timestamp)?),
+            None => format(Utc::now()),
+        },
+        ManDate::Fixed(date) => date.clone(),
+    };
+    Ok(date)
+}
+
+/// Writes a section with the roff `content` to the man page.
+fn render_extra_section(w: &mut impl Write, title: &str, content: &str) -> Result<()> {
+    writeln!(w, ".SH \"{}\"", title.replace('"', "\\(dq"))?;
+    writeln!(w, "{}", content.trim_end())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::DistConfigBuilder, workspace};
+
+    #[test]
+    fn man_pages() {
+        let (_, package) =
+            DistConfigBuilder::from_package_name(workspace::current(), "xtask").unwrap();
+        let cmd = clap::Command::new("app")
+            .version("1.0.0")
+            .subcommand(clap::Command::new("sub"));
+        let target = package
+            .binary_by_name("xtask")
+            .unwrap()
+            .man_section("8")
+            .man_extra_section("SEE ALSO", ".B other\n")
+            .build()
+            .unwrap();
+
+        let pages = dist_build_man_pages(
+            Utf8Path::new("man"),
+            "app",
+            &target,
+            cmd,
+            "2024-01-02".to_owned(),
+        )
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+        let paths = pages
+            .iter()
+            .map(|page| page.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["man/app.8", "man/app-sub.8"]);
+        // extra sections are added only to the page of the target command
+        assert_eq!(pages[0].extra_sections.len(), 1);
+        assert!(pages[1].extra_sections.is_empty());
+
+        let mut out = vec![];
+        pages[0].man.render(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(
+            out.contains(".TH APP 8 2024-01-02 \"app 1.0.0\" \"App Command Manual\""),
+            "{out}"
+        );
+    }
+
+    #[test]
+    fn fixed_date() {
+        let config = DistConfigBuilder::new("man-test", workspace::current())
+            .build()
+            .unwrap();
+        let date = ManDate::Fixed("2024-01-02".to_owned());
+        assert_eq!(man_date(&config, &date).unwrap(), "2024-01-02");
+    }
+
+    #[test]
+    fn extra_section() {
+        let mut out = vec![];
+        render_extra_section(&mut out, "SEE \"ALSO\"", ".B other\n\n").unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            ".SH \"SEE \\(dqALSO\\(dq\"\n.B other\n"
+        );
+    }
 }
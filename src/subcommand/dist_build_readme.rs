@@ -1,10 +1,29 @@
-use crate::{Result, Run, config::Config};
+use std::io::Write;
+
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use eyre::eyre;
+
+use crate::{
+    Result, Run,
+    args::FingerprintArgs,
+    config::{Config, DistPackageConfig, ReadmeFormat},
+    fingerprint::Fingerprint,
+};
 
 /// Arguments definition of the `dist-build-readme` subcommand.
+///
+/// The README files are copied as is, unless the transformations are
+/// configured by
+/// [`DistPackageConfigBuilder`](crate::config::DistPackageConfigBuilder) (e.g.
+/// [`readme_absolute_links`](crate::config::DistPackageConfigBuilder::readme_absolute_links)).
 #[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-build-readme.md"))]
 #[derive(Debug, Clone, Default, clap::Args)]
 #[non_exhaustive]
-pub struct DistBuildReadme {}
+pub struct DistBuildReadme {
+    /// Arguments to control the reuse of the previously generated artifacts.
+    #[clap(flatten)]
+    pub fingerprint_args: FingerprintArgs,
+}
 
 impl Run for DistBuildReadme {
     fn run(&self, config: &Config) -> Result<()> {
@@ -18,12 +37,34 @@ impl DistBuildReadme {
     pub fn run(&self, config: &Config) -> Result<()> {
         tracing::info!("Building READMEs...");
 
-        let Self {} = self;
+        let Self { fingerprint_args } = self;
         let config = config.dist()?;
 
         let packages = config.packages();
 
         let working_dir = config.dist_working_directory(None);
+        let mut fingerprint = Fingerprint::new("readme");
+        for package in packages {
+            fingerprint.add(package.name().as_ref());
+            if let Some(readme) = &package.metadata().readme {
+                fingerprint.add(readme);
+                fingerprint.add_file(&package.root_directory().join(readme))?;
+                fingerprint.add(package.readme_absolute_links());
+                if package.readme_absolute_links() {
+                    fingerprint.add(package.readme_link_base_url(false)?);
+                    fingerprint.add(package.readme_link_base_url(true)?);
+                }
+                fingerprint.add(package.readme_strip_sections());
+                fingerprint.add(package.readme_strip_badges());
+                fingerprint.add(package.readme_formats());
+            }
+        }
+        if !fingerprint_args.force && fingerprint.is_fresh(config)? {
+            tracing::info!("READMEs are up to date");
+            return Ok(());
+        }
+        fingerprint.remove_outputs(config)?;
+
         let readme_dir;
         let add_package_dir;
         if packages.len() > 1 {
@@ -35,8 +76,7 @@ impl DistBuildReadme {
             add_package_dir = false;
         }
 
-        let Self {} = self;
-
+        let mut outputs = vec![];
         for package in packages {
             if let Some(readme) = &package.metadata().readme {
                 let src_file = package.root_directory().join(readme);
@@ -46,10 +86,91 @@ impl DistBuildReadme {
                     readme_dir.clone()
                 };
                 let dest_file = dest_dir.join(readme);
-                crate::fs::copy(src_file, dest_file)?;
+                let workspace_root = &config.metadata().workspace_root;
+                outputs.extend(build_readme(
+                    package,
+                    workspace_root,
+                    &src_file,
+                    &dest_file,
+                )?);
             }
         }
+        fingerprint.save(config, &outputs)?;
 
         Ok(())
     }
 }
+
+/// Writes the README file of the package and its rendered versions,
+/// returning the paths of the written files.
+fn build_readme(
+    package: &DistPackageConfig,
+    workspace_root: &Utf8Path,
+    src_file: &Utf8Path,
+    dest_file: &Utf8Path,
+) -> Result<Vec<Utf8PathBuf>> {
+    let transformed = package.readme_absolute_links()
+        || !package.readme_strip_sections().is_empty()
+        || package.readme_strip_badges()
+        || !package.readme_formats().is_empty();
+    if !transformed {
+        crate::fs::copy(src_file, dest_file)?;
+        return Ok(vec![dest_file.to_owned()]);
+    }
+
+    let mut source = std::fs::read_to_string(src_file)?;
+    if !package.readme_strip_sections().is_empty() || package.readme_strip_badges() {
+        source = crate::readme::strip(
+            &source,
+            package.readme_strip_sections(),
+            package.readme_strip_badges(),
+        );
+    }
+    if package.readme_absolute_links() {
+        // relative links are resolved from the directory of the README file,
+        // assuming the workspace root is the repository root
+        let src_dir = src_file
+            .parent()
+            .ok_or_else(|| eyre!("readme file has no parent: {src_file}"))?;
+        let base_dir = src_dir
+            .strip_prefix(workspace_root)
+            .map_err(|_| eyre!("readme file is outside of the workspace: {src_file}"))?;
+        let base_dir = base_dir
+            .components()
+            .map(|c| c.as_str())
+            .collect::<Vec<_>>()
+            .join("/");
+        source = crate::readme::rewrite_links(
+            &source,
+            &base_dir,
+            &package.readme_link_base_url(false)?,
+            &package.readme_link_base_url(true)?,
+        );
+    }
+
+    let mut outputs = vec![];
+    write_file(dest_file, &source)?;
+    outputs.push(dest_file.to_owned());
+
+    for &format in package.readme_formats() {
+        let file = dest_file.with_extension(format.extension());
+        if file == dest_file {
+            tracing::warn!("README file is already in the format: {src_file}");
+            continue;
+        }
+        let contents = match format {
+            ReadmeFormat::PlainText => crate::readme::to_plain_text(&source),
+            ReadmeFormat::Html => crate::readme::to_html(&source, package.name()),
+        };
+        write_file(&file, &contents)?;
+        outputs.push(file);
+    }
+
+    Ok(outputs)
+}
+
+fn write_file(path: &Utf8Path, contents: &str) -> Result<()> {
+    let mut file = crate::fs::create_file(path)?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,789 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    io::Write as _,
+};
+
+use cargo_metadata::{
+    Package,
+    camino::{Utf8Path, Utf8PathBuf},
+};
+use eyre::eyre;
+use serde::Serialize;
+
+use crate::{
+    Result, Run,
+    archive::ArchiveFormat,
+    config::{Config, DistConfig},
+    dist_manifest::{ArchiveEntry, DistManifest, PackageEntry},
+    fhs::{self, FhsEntry, FhsEntryKind, FhsOptions},
+};
+
+/// Arguments definition of the `dist-build-recipes` subcommand.
+///
+/// A recipe is built for each package manager, installing all the artifacts
+/// contained in the archives. The name, version, description and license of
+/// the recipes are taken from the first package to distribute, so a warning
+/// is emitted if there are multiple packages.
+#[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-build-recipes.md"))]
+#[derive(Debug, Clone, Default, clap::Args)]
+#[non_exhaustive]
+pub struct DistBuildRecipes {}
+
+impl Run for DistBuildRecipes {
+    fn run(&self, config: &Config) -> Result<()> {
+        self.run(config)
+    }
+}
+
+impl DistBuildRecipes {
+    /// Runs the `dist-build-recipes` subcommand.
+    #[tracing::instrument(name = "dist-build-recipes", skip_all, err)]
+    pub fn run(&self, config: &Config) -> Result<()> {
+        tracing::info!("Building package manager recipes...");
+
+        let Self {} = self;
+        let config = config.dist()?;
+
+        let manifest = DistManifest::read(DistManifest::path(config))?;
+        if manifest.archives.is_empty() {
+            tracing::warn!("no archives found (run `cargo xtask dist-archive` first)");
+            return Ok(());
+        }
+        let Some(package) = config.packages().first() else {
+            tracing::warn!("no packages to distribute");
+            return Ok(());
+        };
+        if config.packages().len() > 1 {
+            tracing::warn!(
+                "multiple packages to distribute, the recipes are built with the metadata of the first package `{}`",
+                package.name()
+            );
+        }
+        let package = package.metadata();
+        let recipe_dir = config.dist_target_directory().join("recipes");
+
+        let assets = select_assets(config, &manifest, ArchiveFormat::Tar, homebrew_platform)?;
+        if let Some(asset) = assets.first() {
+            let entries = archive_entries(config, &asset.target)?;
+            let path = recipe_dir
+                .join("homebrew")
+                .join(format!("{}.rb", package.name));
+            write_recipe(&path, &homebrew_formula(package, &assets, &entries)?)?;
+        } else {
+            tracing::info!("skipping Homebrew formula: no macOS or Linux archives found");
+        }
+
+        let assets = select_assets(config, &manifest, ArchiveFormat::Zip, scoop_platform)?;
+        if let Some(asset) = assets.first() {
+            let entries = archive_entries(config, &asset.target)?;
+            let path = recipe_dir
+                .join("scoop")
+                .join(format!("{}.json", package.name));
+            write_recipe(&path, &scoop_manifest(package, &assets, &entries)?)?;
+        } else {
+            tracing::info!("skipping Scoop manifest: no Windows archives found");
+        }
+
+        let assets = select_assets(config, &manifest, ArchiveFormat::Tar, aur_platform)?;
+        if let Some(asset) = assets.first() {
+            let entries = archive_entries(config, &asset.target)?;
+            let path = recipe_dir.join("aur").join("PKGBUILD");
+            write_recipe(&path, &aur_pkgbuild(package, &assets, &entries)?)?;
+        } else {
+            tracing::info!("skipping AUR PKGBUILD: no Linux archives found");
+        }
+
+        Ok(())
+    }
+}
+
+/// Archive selected for a platform supported by a package manager.
+#[derive(Debug)]
+struct Asset<'a> {
+    platform: &'static str,
+    target: String,
+    archive: &'a ArchiveEntry,
+    url: String,
+    prefix: String,
+}
+
+/// Selects an archive for each platform supported by the package manager.
+///
+/// Only the archives of the current distribution name and package versions
+/// are considered, so that stale archives left in the manifest are ignored.
+/// Archives in the preferred format and compressed with gzip are selected if
+/// multiple archives are built for the same platform.
+fn select_assets<'a>(
+    config: &DistConfig,
+    manifest: &'a DistManifest,
+    preferred_format: ArchiveFormat,
+    platform: fn(&str) -> Option<&'static str>,
+) -> Result<Vec<Asset<'a>>> {
+    let rank = |archive: &ArchiveEntry| {
+        (
+            archive.format != preferred_format.to_string(),
+            archive.compression != "gzip",
+        )
+    };
+
+    let packages = PackageEntry::from_config(config);
+    let archives = manifest
+        .archives
+        .iter()
+        .filter(|archive| !archive.symbols && archive.is_current(config.name(), &packages));
+
+    let mut assets = Vec::<Asset>::new();
+    for archive in archives {
+        let Some(target) = &archive.target else {
+            continue;
+        };
+        let Some(platform) = platform(target) else {
+            continue;
+        };
+        if let Some(asset) = assets.iter().find(|asset| asset.platform == platform)
+            && rank(asset.archive) <= rank(archive)
+        {
+            continue;
+        }
+        assets.retain(|asset| asset.platform != platform);
+        assets.push(Asset {
+            platform,
+            target: target.clone(),
+            archive,
+            url: config.download_url(target, &archive.file)?,
+            prefix: config.archive_prefix(target)?,
+        });
+    }
+    Ok(assets)
+}
+
+/// Artifact contained in the archive, and its destination path relative to
+/// the installation prefix.
+#[derive(Debug)]
+struct ArchiveFile {
+    path: Utf8PathBuf,
+    entry: FhsEntry,
+}
+
+/// Collects the artifacts contained in the archive for the given target triple.
+fn archive_entries(config: &DistConfig, target_triple: &str) -> Result<Vec<ArchiveFile>> {
+    let bin_dir = config.dist_working_directory(Some(target_triple));
+    let noarch_dir = config.dist_working_directory(None);
+
+    let mut files = vec![];
+    for package in config.packages() {
+        for entry in fhs::package_entries(config, package, target_triple, FhsOptions::default())? {
+            let path = entry
+                .src
+                .strip_prefix(&bin_dir)
+                .or_else(|_| entry.src.strip_prefix(&noarch_dir))
+                .map_err(|_| eyre!("artifact not found in the archive: {}", entry.src))?
+                .to_owned();
+            files.push(ArchiveFile { path, entry });
+        }
+    }
+    Ok(files)
+}
+
+fn write_recipe(path: &Utf8Path, content: &str) -> Result<()> {
+    let mut file = crate::fs::create_file(path)?;
+    file.write_all(content.as_bytes())?;
+    tracing::info!("Recipe created successfully: {path}");
+    Ok(())
+}
+
+/// Returns the first line of the package description.
+fn summary(package: &Package) -> &str {
+    let description = package.description.as_deref().unwrap_or(&package.name);
+    description.trim().lines().next().unwrap_or_default().trim()
+}
+
+fn homepage(package: &Package) -> Option<&str> {
+    package
+        .homepage
+        .as_deref()
+        .or(package.repository.as_deref())
+}
+
+fn homebrew_platform(target_triple: &str) -> Option<&'static str> {
+    let arch = target_triple.split('-').next()?;
+    let platform = match arch {
+        "x86_64" if target_triple.contains("-apple-darwin") => "macos-intel",
+        "aarch64" if target_triple.contains("-apple-darwin") => "macos-arm",
+        "x86_64" if target_triple.contains("-linux-") => "linux-intel",
+        "aarch64" if target_triple.contains("-linux-") => "linux-arm",
+        _ => return None,
+    };
+    Some(platform)
+}
+
+fn homebrew_formula(package: &Package, assets: &[Asset], files: &[ArchiveFile]) -> Result<String> {
+    let class_name = package
+        .name
+        .split(['-', '_'])
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+    let desc = summary(package).trim_end_matches('.');
+
+    let mut s = String::new();
+    writeln!(s, "class {class_name} < Formula")?;
+    writeln!(s, "  desc {}", ruby_string(desc))?;
+    if let Some(homepage) = homepage(package) {
+        writeln!(s, "  homepage {}", ruby_string(homepage))?;
+    }
+    writeln!(s, "  version {}", ruby_string(&package.version.to_string()))?;
+    if let Some(license) = &package.license {
+        writeln!(s, "  license {}", homebrew_license(license))?;
+    }
+
+    for os in ["macos", "linux"] {
+        let os_assets = assets
+            .iter()
+            .filter(|asset| asset.platform.starts_with(os))
+            .collect::<Vec<_>>();
+        if os_assets.is_empty() {
+            continue;
+        }
+        writeln!(s)?;
+        writeln!(s, "  on_{os} do")?;
+        for asset in os_assets {
+            let (_, arch) = asset.platform.split_once('-').unwrap_or_default();
+            writeln!(s, "    on_{arch} do")?;
+            writeln!(s, "      url {}", ruby_string(&asset.url))?;
+            writeln!(
+                s,
+                "      sha256 {}",
+                ruby_string(&asset.archive.digests.sha256)
+            )?;
+            writeln!(s, "    end")?;
+        }
+        writeln!(s, "  end")?;
+    }
+
+    writeln!(s)?;
+    writeln!(s, "  def install")?;
+    for file in files {
+        let dest_dir = file.entry.dest.parent().unwrap_or(Utf8Path::new(""));
+        let dest_name = file.entry.dest.file_name().unwrap_or_default();
+        let dir = match dest_dir.as_str() {
+            // Homebrew installs the root-relative files under its prefix
+            dir if file.entry.is_root_relative() => match dir.strip_prefix("etc") {
+                Some("") => "etc".to_owned(),
+                Some(sub_dir) if sub_dir.starts_with('/') => {
+                    format!("(etc/{})", ruby_string(&sub_dir[1..]))
+                }
+                _ => format!("(prefix/{})", ruby_string(dir)),
+            },
+            "bin" => "bin".to_owned(),
+            "lib" => "lib".to_owned(),
+            "include" => "include".to_owned(),
+            "share/bash-completion/completions" => "bash_completion".to_owned(),
+            "share/zsh/site-functions" => "zsh_completion".to_owned(),
+            "share/fish/vendor_completions.d" => "fish_completion".to_owned(),
+            dir => match dir.strip_prefix("share/man/") {
+                Some(
+                    section @ ("man1" | "man2" | "man3" | "man4" | "man5" | "man6" | "man7"
+                    | "man8"),
+                ) => section.to_owned(),
+                _ if dir == format!("share/doc/{}", package.name) => "doc".to_owned(),
+                _ => format!("(prefix/{})", ruby_string(dir)),
+            },
+        };
+        if file.path.file_name() == Some(dest_name) {
+            writeln!(s, "    {dir}.install {}", ruby_string(file.path.as_str()))?;
+        } else {
+            writeln!(
+                s,
+                "    {dir}.install {} => {}",
+                ruby_string(file.path.as_str()),
+                ruby_string(dest_name)
+            )?;
+        }
+    }
+    writeln!(s, "  end")?;
+
+    if let Some(file) = files
+        .iter()
+        .find(|file| file.entry.kind == FhsEntryKind::Executable)
+    {
+        let exe_name = file.entry.dest.file_name().unwrap_or_default();
+        writeln!(s)?;
+        writeln!(s, "  test do")?;
+        writeln!(s, "    system bin/{}, \"--help\"", ruby_string(exe_name))?;
+        writeln!(s, "  end")?;
+    }
+    writeln!(s, "end")?;
+    Ok(s)
+}
+
+/// Converts the SPDX license expression into the Homebrew license DSL.
+fn homebrew_license(license: &str) -> String {
+    // `/` is the deprecated separator of the `license` field in Cargo.toml
+    let license = license.replace('/', " OR ");
+    let is_simple = |id: &str| !id.is_empty() && !id.contains([' ', '(', ')']);
+    for (op, dsl) in [(" OR ", "any_of"), (" AND ", "all_of")] {
+        let ids = license.split(op).map(str::trim).collect::<Vec<_>>();
+        if ids.len() > 1 && ids.iter().all(|id| is_simple(id)) {
+            let ids = ids.iter().map(|id| ruby_string(id)).collect::<Vec<_>>();
+            return format!("{dsl}: [{}]", ids.join(", "));
+        }
+    }
+    let license = license.trim();
+    if is_simple(license) {
+        ruby_string(license)
+    } else {
+        ":cannot_represent".to_owned()
+    }
+}
+
+/// Quotes the string as a Ruby string literal.
+fn ruby_string(s: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' | '\\' | '#' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn scoop_platform(target_triple: &str) -> Option<&'static str> {
+    if !target_triple.contains("-windows-") {
+        return None;
+    }
+    let platform = match target_triple.split('-').next()? {
+        "x86_64" => "64bit",
+        "i586" | "i686" => "32bit",
+        "aarch64" => "arm64",
+        _ => return None,
+    };
+    Some(platform)
+}
+
+fn scoop_manifest(package: &Package, assets: &[Asset], files: &[ArchiveFile]) -> Result<String> {
+    #[derive(Serialize)]
+    struct Manifest<'a> {
+        version: String,
+        description: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        homepage: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        license: Option<String>,
+        architecture: BTreeMap<&'static str, Architecture<'a>>,
+        bin: Vec<&'a str>,
+    }
+    #[derive(Serialize)]
+    struct Architecture<'a> {
+        url: &'a str,
+        hash: &'a str,
+        #[serde(skip_serializing_if = "str::is_empty")]
+        extract_dir: &'a str,
+    }
+
+    let architecture = assets
+        .iter()
+        .map(|asset| {
+            let arch = Architecture {
+                url: &asset.url,
+                hash: &asset.archive.digests.sha256,
+                extract_dir: &asset.prefix,
+            };
+            (asset.platform, arch)
+        })
+        .collect();
+
+    let manifest = Manifest {
+        version: package.version.to_string(),
+        description: summary(package),
+        homepage: homepage(package),
+        // Scoop uses `|` for dual licenses and `,` for multiple licenses
+        license: package
+            .license
+            .as_ref()
+            .map(|license| license.replace(" OR ", "|").replace(" AND ", ",")),
+        architecture,
+        bin: files
+            .iter()
+            .filter(|file| file.entry.kind == FhsEntryKind::Executable)
+            .map(|file| file.path.as_str())
+            .collect(),
+    };
+    let mut s = serde_json::to_string_pretty(&manifest)?;
+    s.push('\n');
+    Ok(s)
+}
+
+fn aur_platform(target_triple: &str) -> Option<&'static str> {
+    if !target_triple.contains("-linux-") {
+        return None;
+    }
+    let platform = match target_triple.split('-').next()? {
+        "x86_64" => "x86_64",
+        "i686" => "i686",
+        "aarch64" => "aarch64",
+        "armv7" => "armv7h",
+        "riscv64gc" => "riscv64",
+        _ => return None,
+    };
+    Some(platform)
+}
+
+fn aur_pkgbuild(package: &Package, assets: &[Asset], files: &[ArchiveFile]) -> Result<String> {
+    let name = package.name.to_lowercase().replace('_', "-");
+    let pkgname = format!("{name}-bin");
+    // `pkgver` cannot contain hyphens
+    let pkgver = package.version.to_string().replace(['-', '+'], "_");
+
+    let mut s = String::new();
+    if let Some(author) = package.authors.first() {
+        writeln!(s, "# Maintainer: {author}")?;
+    }
+    writeln!(s, "pkgname={pkgname}")?;
+    writeln!(s, "pkgver={pkgver}")?;
+    writeln!(s, "pkgrel=1")?;
+    writeln!(s, "pkgdesc={}", shell_string(summary(package)))?;
+    let arches = assets
+        .iter()
+        .map(|asset| shell_string(asset.platform))
+        .collect::<Vec<_>>();
+    writeln!(s, "arch=({})", arches.join(" "))?;
+    if let Some(homepage) = homepage(package) {
+        writeln!(s, "url={}", shell_string(homepage))?;
+    }
+    let licenses = aur_licenses(package.license.as_deref().unwrap_or("custom"))
+        .into_iter()
+        .map(shell_string)
+        .collect::<Vec<_>>();
+    writeln!(s, "license=({})", licenses.join(" "))?;
+    writeln!(s, "provides=({})", shell_string(&name))?;
+    writeln!(s, "conflicts=({})", shell_string(&name))?;
+    let backup = files
+        .iter()
+        .filter(|file| file.entry.is_config())
+        .map(|file| shell_string(file.entry.system_path().as_str()))
+        .collect::<Vec<_>>();
+    if !backup.is_empty() {
+        writeln!(s, "backup=({})", backup.join(" "))?;
+    }
+    for asset in assets {
+        let source = format!("{}::{}", asset.archive.file, asset.url);
+        writeln!(s, "source_{}=({})", asset.platform, shell_string(&source))?;
+        writeln!(
+            s,
+            "sha256sums_{}=({})",
+            asset.platform,
+            shell_string(&asset.archive.digests.sha256)
+        )?;
+    }
+
+    writeln!(s)?;
+    writeln!(s, "package() {{")?;
+    let prefixes = assets
+        .iter()
+        .map(|asset| asset.prefix.as_str())
+        .collect::<BTreeSet<_>>();
+    if prefixes.len() == 1 {
+        let prefix = prefixes.first().copied().unwrap_or_default();
+        writeln!(s, "  cd \"$srcdir\"/{}", shell_string(prefix))?;
+    } else {
+        writeln!(s, "  case \"$CARCH\" in")?;
+        for asset in assets {
+            writeln!(
+                s,
+                "    {}) cd \"$srcdir\"/{} ;;",
+                asset.platform,
+                shell_string(&asset.prefix)
+            )?;
+        }
+        writeln!(s, "  esac")?;
+    }
+    for file in files {
+        let dest = match file.entry.kind {
+            #[cfg(feature = "subcommand-dist-build-license")]
+            FhsEntryKind::License => {
+                let file_name = file.entry.dest.file_name().unwrap_or_default();
+                Utf8Path::new("usr/share/licenses")
+                    .join(&pkgname)
+                    .join(file_name)
+            }
+            _ => file.entry.system_path(),
+        };
+        writeln!(
+            s,
+            "  install -Dm{:o} {} \"$pkgdir\"/{}",
+            file.entry.mode,
+            shell_string(file.path.as_str()),
+            shell_string(dest.as_str())
+        )?;
+    }
+    writeln!(s, "}}")?;
+    Ok(s)
+}
+
+/// Splits the SPDX license expression into the licenses listed in the
+/// `license` array of PKGBUILD.
+fn aur_licenses(license: &str) -> Vec<&str> {
+    // `/` is the deprecated separator of the `license` field in Cargo.toml
+    let mut licenses = vec![];
+    for id in license
+        .split(['/', '(', ')'])
+        .flat_map(|s| s.split(" OR "))
+        .flat_map(|s| s.split(" AND "))
+        .map(str::trim)
+    {
+        if !id.is_empty() && !licenses.contains(&id) {
+            licenses.push(id);
+        }
+    }
+    licenses
+}
+
+/// Quotes the string as a single-quoted shell word.
+fn shell_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{config::DistConfigBuilder, dist_manifest::Digests, workspace};
+
+    use super::*;
+
+    #[test]
+    fn homebrew_license_dsl() {
+        let cases = [
+            ("MIT", r#""MIT""#),
+            ("MIT OR Apache-2.0", r#"any_of: ["MIT", "Apache-2.0"]"#),
+            ("MIT/Apache-2.0", r#"any_of: ["MIT", "Apache-2.0"]"#),
+            ("MIT AND Unicode-3.0", r#"all_of: ["MIT", "Unicode-3.0"]"#),
+            ("(MIT OR Apache-2.0) AND Unicode-3.0", ":cannot_represent"),
+            ("Apache-2.0 WITH LLVM-exception", ":cannot_represent"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(homebrew_license(input), expected, "input: {input}");
+        }
+    }
+
+    #[test]
+    fn aur_license_array() {
+        let cases = [
+            ("MIT", &["MIT"][..]),
+            ("MIT OR Apache-2.0", &["MIT", "Apache-2.0"]),
+            ("MIT/Apache-2.0", &["MIT", "Apache-2.0"]),
+            (
+                "(MIT OR Apache-2.0) AND Unicode-3.0",
+                &["MIT", "Apache-2.0", "Unicode-3.0"],
+            ),
+            ("MIT OR Apache-2.0 AND MIT", &["MIT", "Apache-2.0"]),
+            (
+                "Apache-2.0 WITH LLVM-exception OR MIT",
+                &["Apache-2.0 WITH LLVM-exception", "MIT"],
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(aur_licenses(input), expected, "input: {input}");
+        }
+    }
+
+    #[test]
+    fn ruby_string_literal() {
+        let cases = [
+            ("app", r#""app""#),
+            (r#"say "hi""#, r#""say \"hi\"""#),
+            ("#{name}", r#""\#{name}""#),
+            (r"C:\bin", r#""C:\\bin""#),
+            ("a\nb", r#""a\nb""#),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(ruby_string(input), expected, "input: {input}");
+        }
+    }
+
+    #[test]
+    fn shell_string_word() {
+        let cases = [
+            ("app", "'app'"),
+            ("$HOME/bin", "'$HOME/bin'"),
+            ("it's", r"'it'\''s'"),
+            ("", "''"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(shell_string(input), expected, "input: {input}");
+        }
+    }
+
+    #[test]
+    fn current_assets() {
+        let workspace = workspace::current();
+        let (dist_config, pkg_config) = DistConfigBuilder::from_root_package(workspace).unwrap();
+        let config = dist_config
+            .package(pkg_config.build().unwrap())
+            .build()
+            .unwrap();
+
+        let archive = |file: &str, name: &str, version: Option<&str>| {
+            let mut packages = PackageEntry::from_config(&config);
+            if let Some(version) = version {
+                packages[0].version = version.to_owned();
+            }
+            ArchiveEntry {
+                file: file.to_owned(),
+                name: name.to_owned(),
+                target: Some("x86_64-unknown-linux-gnu".to_owned()),
+                format: "tar".to_owned(),
+                compression: "gzip".to_owned(),
+                size: 0,
+                digests: Digests {
+                    sha256: String::new(),
+                },
+                symbols: false,
+                packages,
+                sboms: vec![],
+            }
+        };
+        let mut manifest = DistManifest::default();
+        for archive in [
+            archive("old.tar.gz", "cli-xtask-v0.0.1", Some("0.0.1")),
+            archive("stale.tar.gz", config.name(), Some("0.0.1")),
+            archive("other.tar.gz", "other", None),
+            ArchiveEntry {
+                format: "zip".to_owned(),
+                ..archive("current.zip", config.name(), None)
+            },
+            ArchiveEntry {
+                symbols: true,
+                ..archive("current-symbols.tar.gz", config.name(), None)
+            },
+        ] {
+            manifest.insert_archive(archive);
+        }
+
+        let assets = select_assets(&config, &manifest, ArchiveFormat::Tar, aur_platform).unwrap();
+        let files = assets
+            .iter()
+            .map(|asset| asset.archive.file.as_str())
+            .collect::<Vec<_>>();
+        // stale and symbols archives in the preferred format are not selected
+        assert_eq!(files, ["current.zip"]);
+    }
+
+    #[test]
+    fn formula() {
+        let package: Package = serde_json::from_value(serde_json::json!({
+            "name": "my-app",
+            "version": "1.2.3",
+            "id": "path+file:///my-app#1.2.3",
+            "description": "A sample application.\nMore details.",
+            "license": "MIT OR Apache-2.0",
+            "repository": "https://github.com/owner/my-app",
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": "/my-app/Cargo.toml",
+        }))
+        .unwrap();
+        let archive = |file: &str, sha256: &str| ArchiveEntry {
+            file: file.to_owned(),
+            name: "my-app".to_owned(),
+            target: None,
+            format: "tar".to_owned(),
+            compression: "gzip".to_owned(),
+            size: 0,
+            digests: Digests {
+                sha256: sha256.to_owned(),
+            },
+            symbols: false,
+            packages: vec![],
+            sboms: vec![],
+        };
+        let macos = archive("my-app-aarch64-apple-darwin.tar.gz", "aaaa");
+        let linux = archive("my-app-x86_64-unknown-linux-gnu.tar.gz", "bbbb");
+        fn asset<'a>(platform: &'static str, archive: &'a ArchiveEntry) -> Asset<'a> {
+            Asset {
+                platform,
+                target: String::new(),
+                archive,
+                url: format!("https://example.com/{}", archive.file),
+                prefix: String::new(),
+            }
+        }
+        let assets = [asset("macos-arm", &macos), asset("linux-intel", &linux)];
+        let file = |path: &str, dest: &str, kind| ArchiveFile {
+            path: path.into(),
+            entry: FhsEntry {
+                src: Utf8PathBuf::new(),
+                dest: dest.into(),
+                mode: 0o644,
+                kind,
+            },
+        };
+        let files = [
+            file("my-app", "bin/my-app", FhsEntryKind::Executable),
+            file(
+                "man/man1/my-app.1",
+                "share/man/man1/my-app.1",
+                FhsEntryKind::ManPage,
+            ),
+            file(
+                "completion/my-app.bash",
+                "share/bash-completion/completions/my-app",
+                FhsEntryKind::Completion,
+            ),
+            file(
+                "README.md",
+                "share/doc/my-app/README.md",
+                FhsEntryKind::Document,
+            ),
+        ];
+
+        let expected = r#"class MyApp < Formula
+  desc "A sample application"
+  homepage "https://github.com/owner/my-app"
+  version "1.2.3"
+  license any_of: ["MIT", "Apache-2.0"]
+
+  on_macos do
+    on_arm do
+      url "https://example.com/my-app-aarch64-apple-darwin.tar.gz"
+      sha256 "aaaa"
+    end
+  end
+
+  on_linux do
+    on_intel do
+      url "https://example.com/my-app-x86_64-unknown-linux-gnu.tar.gz"
+      sha256 "bbbb"
+    end
+  end
+
+  def install
+    bin.install "my-app"
+    man1.install "man/man1/my-app.1"
+    bash_completion.install "completion/my-app.bash" => "my-app"
+    doc.install "README.md"
+  end
+
+  test do
+    system bin/"my-app", "--help"
+  end
+end
+"#;
+        assert_eq!(
+            homebrew_formula(&package, &assets, &files).unwrap(),
+            expected
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use cargo_metadata::{
+    camino::{Utf8Path, Utf8PathBuf},
+    semver::Version,
+};
+use eyre::eyre;
+use rpm::{BuildConfig, CompressionType, FileOptions, PackageBuilder, Timestamp};
+
+use crate::{
+    Result, Run,
+    config::{Config, DistConfig, DistPackageConfig},
+    fhs::{self, FhsEntry, FhsEntryKind, FhsOptions},
+};
+
+/// Arguments definition of the `dist-build-rpm` subcommand.
+#[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-build-rpm.md"))]
+#[derive(Debug, Clone, Default, clap::Args)]
+#[non_exhaustive]
+pub struct DistBuildRpm {}
+
+impl Run for DistBuildRpm {
+    fn run(&self, config: &Config) -> Result<()> {
+        self.run(config)
+    }
+}
+
+impl DistBuildRpm {
+    /// Runs the `dist-build-rpm` subcommand.
+    #[tracing::instrument(name = "dist-build-rpm", skip_all, err)]
+    pub fn run(&self, config: &Config) -> Result<()> {
+        tracing::info!("Building RPM packages...");
+
+        let Self {} = self;
+        let config = config.dist()?;
+
+        for target_triple in config.built_target_triples()? {
+            let Some(arch) = rpm_arch(&target_triple) else {
+                tracing::info!("skipping non-Linux target: {target_triple}");
+                continue;
+            };
+            for package in config.packages() {
+                let entries =
+                    fhs::package_entries(config, package, &target_triple, FhsOptions::default())?;
+                let path = build_rpm(config, package, arch, &entries)?;
+                tracing::info!("RPM package created successfully: {path}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the RPM architecture name of the Linux target triple.
+///
+/// Only the GNU and musl environments are supported, since the binaries for
+/// other environments (e.g. Android) cannot run on RPM-based systems.
+fn rpm_arch(target_triple: &str) -> Option<&'static str> {
+    let (_, env) = target_triple.split_once("-linux-")?;
+    if !env.starts_with("gnu") && !env.starts_with("musl") {
+        return None;
+    }
+    let arch = target_triple.split('-').next()?;
+    let is_hard_float = target_triple.ends_with("hf");
+    let arch = match arch {
+        "x86_64" => "x86_64",
+        "i586" => "i586",
+        "i686" => "i686",
+        "aarch64" => "aarch64",
+        "armv7" | "thumbv7neon" => "armv7hl",
+        // `arm-*` targets are ARMv6, with or without hardware floating point
+        "arm" if is_hard_float => "armv6hl",
+        "arm" => "armv6l",
+        "armv5te" => "armv5tel",
+        "powerpc" => "ppc",
+        "powerpc64" => "ppc64",
+        "powerpc64le" => "ppc64le",
+        "riscv64gc" => "riscv64",
+        "s390x" => "s390x",
+        "loongarch64" => "loongarch64",
+        "sparc64" => "sparc64",
+        _ => return None,
+    };
+    Some(arch)
+}
+
+fn build_rpm(
+    config: &DistConfig,
+    package: &DistPackageConfig,
+    arch: &str,
+    entries: &[FhsEntry],
+) -> Result<Utf8PathBuf> {
+    let metadata = package.metadata();
+    let name = metadata.name.as_str();
+    let version = rpm_version(&metadata.version);
+    let release = "1";
+    let timestamp = fhs::source_date_epoch()?;
+    let timestamp = u32::try_from(timestamp)
+        .map_err(|_| eyre!("timestamp out of range for RPM packages: {timestamp}"))?;
+
+    let license = match &metadata.license {
+        Some(license) => license.as_str(),
+        None => {
+            tracing::warn!(
+                "`license` of package `{name}` is not set, using `LicenseRef-UNKNOWN` as the license of the RPM package"
+            );
+            "LicenseRef-UNKNOWN"
+        }
+    };
+    let description = metadata.description.as_deref().unwrap_or(name).trim();
+    let summary = description.lines().next().unwrap_or_default().trim();
+
+    let mut builder = PackageBuilder::new(name, &version, license, arch, summary);
+    builder
+        .using_config(
+            BuildConfig::v4()
+                .compression(CompressionType::Gzip)
+                .source_date(Timestamp(timestamp)),
+        )
+        .release(release)
+        .description(description);
+    if let Some(url) = metadata.homepage.as_ref().or(metadata.repository.as_ref()) {
+        builder.url(url);
+    }
+    if let Some(repository) = &metadata.repository {
+        builder.vcs(repository);
+    }
+    if let Some(author) = metadata.authors.first() {
+        builder.packager(author);
+    }
+
+    let mut entries = entries.iter().collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.dest.cmp(&b.dest));
+    let doc_dir = Utf8Path::new("/usr/share/doc").join(name);
+    let mut owns_doc_dir = false;
+    for entry in entries {
+        let dest = Utf8Path::new("/").join(entry.system_path());
+        // permission bits always fit in `u16`
+        let options = FileOptions::new(dest.as_str()).permissions(entry.mode as u16);
+        let options = match entry.kind {
+            FhsEntryKind::Executable | FhsEntryKind::Library | FhsEntryKind::Completion => options,
+            #[cfg(feature = "subcommand-dist-build-bin")]
+            FhsEntryKind::Header => options,
+            FhsEntryKind::ManPage => options.doc(),
+            FhsEntryKind::Document => {
+                owns_doc_dir = true;
+                options.doc()
+            }
+            #[cfg(feature = "subcommand-dist-build-license")]
+            FhsEntryKind::License => {
+                owns_doc_dir = true;
+                options.license()
+            }
+            #[cfg(feature = "subcommand-dist-build-doc")]
+            FhsEntryKind::Extra | FhsEntryKind::RootExtra => options,
+            #[cfg(feature = "subcommand-dist-build-doc")]
+            FhsEntryKind::Config => options.config().noreplace(),
+        };
+        builder.with_file(&entry.src, options)?;
+    }
+    if owns_doc_dir {
+        builder.with_dir_entry(FileOptions::dir(doc_dir.as_str()).permissions(0o755))?;
+    }
+
+    let rpm_path = config
+        .dist_target_directory()
+        .join(format!("{name}-{version}-{release}.{arch}.rpm"));
+    let rpm = builder.build()?;
+    let mut file = crate::fs::create_file(&rpm_path)?;
+    rpm.write(&mut file)?;
+    file.sync_all()?;
+
+    Ok(rpm_path)
+}
+
+/// Converts the semver version into an RPM version.
+///
+/// RPM versions cannot contain `-`, and pre-release versions are separated
+/// with `~`, so that they are sorted before the release version.
+fn rpm_version(version: &Version) -> String {
+    let mut s = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if !version.pre.is_empty() {
+        s.push('~');
+        s.push_str(&version.pre.as_str().replace('-', "_"));
+    }
+    if !version.build.is_empty() {
+        s.push('+');
+        s.push_str(&version.build.as_str().replace('-', "_"));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arch() {
+        let cases = [
+            ("x86_64-unknown-linux-gnu", Some("x86_64")),
+            ("x86_64-unknown-linux-musl", Some("x86_64")),
+            ("i686-unknown-linux-gnu", Some("i686")),
+            ("aarch64-unknown-linux-gnu", Some("aarch64")),
+            ("armv7-unknown-linux-gnueabihf", Some("armv7hl")),
+            ("thumbv7neon-unknown-linux-gnueabihf", Some("armv7hl")),
+            ("arm-unknown-linux-gnueabihf", Some("armv6hl")),
+            ("arm-unknown-linux-gnueabi", Some("armv6l")),
+            ("arm-unknown-linux-musleabi", Some("armv6l")),
+            ("armv5te-unknown-linux-gnueabi", Some("armv5tel")),
+            ("powerpc64le-unknown-linux-gnu", Some("ppc64le")),
+            ("riscv64gc-unknown-linux-gnu", Some("riscv64")),
+            ("aarch64-linux-android", None),
+            ("x86_64-linux-android", None),
+            ("armv7-linux-androideabi", None),
+            ("aarch64-unknown-linux-ohos", None),
+            ("x86_64-apple-darwin", None),
+            ("x86_64-pc-windows-msvc", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(rpm_arch(input), expected, "input: {input}");
+        }
+    }
+
+    #[test]
+    fn version() {
+        let cases = [
+            ("1.0.0", "1.0.0"),
+            ("1.0.0-alpha", "1.0.0~alpha"),
+            ("1.0.0-alpha.1", "1.0.0~alpha.1"),
+            ("1.0.0-alpha-1", "1.0.0~alpha_1"),
+            ("1.0.0+build-5", "1.0.0+build_5"),
+            ("1.0.0-rc-1+git-abc", "1.0.0~rc_1+git_abc"),
+        ];
+        for (input, expected) in cases {
+            let version = input.parse::<Version>().unwrap();
+            let version = rpm_version(&version);
+            assert_eq!(version, expected, "input: {input}");
+            assert!(!version.contains('-'), "input: {input}");
+        }
+    }
+}
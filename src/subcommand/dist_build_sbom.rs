@@ -0,0 +1,781 @@
+use std::{collections::BTreeMap, io::Write as _};
+
+use cargo_metadata::{Metadata, Package, PackageId, TargetKind};
+use chrono::Utc;
+use eyre::eyre;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    Result, Run,
+    config::{Config, DistConfig},
+    dependency,
+    sbom::SbomFormat,
+    spdx::Expression,
+};
+
+/// Arguments definition of the `dist-build-sbom` subcommand.
+#[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-build-sbom.md"))]
+#[derive(Debug, Clone, Default, clap::Args)]
+#[non_exhaustive]
+pub struct DistBuildSbom {}
+
+impl Run for DistBuildSbom {
+    fn run(&self, config: &Config) -> Result<()> {
+        self.run(config)
+    }
+}
+
+impl DistBuildSbom {
+    /// Runs the `dist-build-sbom` subcommand.
+    #[tracing::instrument(name = "dist-build-sbom", skip_all, err)]
+    pub fn run(&self, config: &Config) -> Result<()> {
+        tracing::info!("Building SBOMs...");
+
+        let Self {} = self;
+        let config = config.dist()?;
+
+        let target_triples = config.built_target_triples()?;
+        if target_triples.is_empty() {
+            tracing::warn!(
+                "no built targets found, skipping SBOMs (run `cargo xtask dist-build-bin` first)"
+            );
+            return Ok(());
+        }
+
+        let lockfile = Lockfile::read(config.metadata())?;
+        let timestamp = timestamp()?;
+        for target_triple in &target_triples {
+            let dir = config.dist_working_directory(Some(target_triple));
+            for package in config.packages() {
+                let metadata = dependency::package_metadata(config, package, target_triple)?;
+                let bom = Bom::new(&metadata, &package.metadata().id, &lockfile)?;
+                for format in SbomFormat::ALL {
+                    let contents = match format {
+                        SbomFormat::CycloneDx => bom.cyclonedx(&timestamp)?,
+                        SbomFormat::Spdx => bom.spdx(config, target_triple, &timestamp)?,
+                    };
+                    let path = dir.join(format.file_name(package.name()));
+                    crate::fs::create_file(&path)?.write_all(contents.as_bytes())?;
+                }
+                tracing::info!(
+                    "{target_triple}: {} depends on {} packages",
+                    package.name(),
+                    bom.components.len() - 1
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checksums of the packages recorded in `Cargo.lock`.
+#[derive(Debug, Default, Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+    checksum: Option<String>,
+}
+
+impl Lockfile {
+    fn read(workspace: &Metadata) -> Result<Self> {
+        let path = workspace.workspace_root.join("Cargo.lock");
+        if !path.is_file() {
+            tracing::warn!("Cargo.lock not found, checksums are omitted from the SBOMs");
+            return Ok(Self::default());
+        }
+        let lockfile = toml::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|e| eyre!("failed to parse {path}: {e}"))?;
+        Ok(lockfile)
+    }
+
+    fn checksum(&self, package: &Package) -> Option<&str> {
+        let version = package.version.to_string();
+        let source = package.source.as_ref().map(|source| source.repr.as_str());
+        self.package
+            .iter()
+            .find(|locked| {
+                locked.name == package.name.as_str()
+                    && locked.version == version
+                    && locked.source.as_deref() == source
+            })?
+            .checksum
+            .as_deref()
+    }
+}
+
+/// Returns the creation time of the SBOMs.
+///
+/// `SOURCE_DATE_EPOCH` is used if set, so that the SBOMs can be built
+/// reproducibly.
+fn timestamp() -> Result<String> {
+    let time = match crate::time::source_date_epoch()? {
+        Some(timestamp) => crate::time::to_date_time(timestamp)?,
+        None => Utc::now(),
+    };
+    Ok(time.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+}
+
+/// Packages a distributed package consists of.
+struct Bom<'a> {
+    /// The distributed package followed by its dependencies
+    components: Vec<BomPackage<'a>>,
+}
+
+struct BomPackage<'a> {
+    package: &'a Package,
+    /// License expression normalized to the SPDX syntax
+    license: Option<String>,
+    checksum: Option<&'a str>,
+    dependencies: Vec<&'a PackageId>,
+}
+
+impl<'a> Bom<'a> {
+    fn new(metadata: &'a Metadata, root: &'a PackageId, lockfile: &'a Lockfile) -> Result<Self> {
+        let mut graph = dependency::normal_dependency_graph(metadata, &[root])?;
+        let mut packages = graph
+            .keys()
+            .map(|id| {
+                metadata
+                    .packages
+                    .iter()
+                    .find(|package| &package.id == *id)
+                    .ok_or_else(|| eyre!("package not found in metadata: {id}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // the distributed package comes first
+        packages.sort_by(|a, b| {
+            (&a.id != root, &a.name, &a.version).cmp(&(&b.id != root, &b.name, &b.version))
+        });
+
+        let components = packages
+            .into_iter()
+            .map(|package| {
+                let license = package.license.as_ref().map(|license| {
+                    license
+                        .parse::<Expression>()
+                        .map_or_else(|_| license.clone(), |expr| expr.to_string())
+                });
+                let mut dependencies = graph.remove(&package.id).unwrap_or_default();
+                dependencies.sort();
+                dependencies.dedup();
+                BomPackage {
+                    package,
+                    license,
+                    checksum: lockfile.checksum(package),
+                    dependencies,
+                }
+            })
+            .collect();
+        Ok(Self { components })
+    }
+
+    fn root(&self) -> &BomPackage<'a> {
+        &self.components[0]
+    }
+
+    /// Renders the CycloneDX JSON document.
+    fn cyclonedx(&self, timestamp: &str) -> Result<String> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Document<'a> {
+            bom_format: &'static str,
+            spec_version: &'static str,
+            version: u32,
+            metadata: Metadata<'a>,
+            components: Vec<Component<'a>>,
+            dependencies: Vec<Dependency>,
+        }
+        #[derive(Serialize)]
+        struct Metadata<'a> {
+            timestamp: &'a str,
+            tools: Tools,
+            component: Component<'a>,
+        }
+        #[derive(Serialize)]
+        struct Tools {
+            components: Vec<Tool>,
+        }
+        #[derive(Serialize)]
+        struct Tool {
+            #[serde(rename = "type")]
+            kind: &'static str,
+            name: &'static str,
+            version: &'static str,
+        }
+        #[derive(Serialize)]
+        struct Component<'a> {
+            #[serde(rename = "type")]
+            kind: &'static str,
+            #[serde(rename = "bom-ref")]
+            bom_ref: String,
+            name: &'a str,
+            version: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            description: Option<&'a str>,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            licenses: Vec<License<'a>>,
+            purl: String,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            hashes: Vec<Hash<'a>>,
+        }
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        enum License<'a> {
+            Expression(&'a str),
+            License { name: &'a str },
+        }
+        #[derive(Serialize)]
+        struct Hash<'a> {
+            alg: &'static str,
+            content: &'a str,
+        }
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Dependency {
+            #[serde(rename = "ref")]
+            bom_ref: String,
+            depends_on: Vec<String>,
+        }
+
+        fn component<'b>(component: &'b BomPackage<'_>, is_root: bool) -> Component<'b> {
+            let package = component.package;
+            let is_application = is_root
+                && package
+                    .targets
+                    .iter()
+                    .any(|target| target.kind.contains(&TargetKind::Bin));
+            let licenses = component
+                .license
+                .as_deref()
+                .map(|license| {
+                    // licenses which are not valid SPDX expressions are
+                    // recorded by their names
+                    if license.parse::<Expression>().is_ok() {
+                        License::Expression(license)
+                    } else {
+                        License::License { name: license }
+                    }
+                })
+                .into_iter()
+                .collect();
+            Component {
+                kind: if is_application {
+                    "application"
+                } else {
+                    "library"
+                },
+                bom_ref: purl(package),
+                name: package.name.as_str(),
+                version: package.version.to_string(),
+                description: package.description.as_deref().map(str::trim),
+                licenses,
+                purl: purl(package),
+                hashes: component
+                    .checksum
+                    .map(|checksum| Hash {
+                        alg: "SHA-256",
+                        content: checksum,
+                    })
+                    .into_iter()
+                    .collect(),
+            }
+        }
+        let purls = self
+            .components
+            .iter()
+            .map(|component| (&component.package.id, purl(component.package)))
+            .collect::<BTreeMap<_, _>>();
+
+        let document = Document {
+            bom_format: "CycloneDX",
+            spec_version: "1.5",
+            version: 1,
+            metadata: Metadata {
+                timestamp,
+                tools: Tools {
+                    components: vec![Tool {
+                        kind: "application",
+                        name: env!("CARGO_PKG_NAME"),
+                        version: env!("CARGO_PKG_VERSION"),
+                    }],
+                },
+                component: component(self.root(), true),
+            },
+            components: self.components[1..]
+                .iter()
+                .map(|c| component(c, false))
+                .collect(),
+            dependencies: self
+                .components
+                .iter()
+                .map(|component| Dependency {
+                    bom_ref: purls[&component.package.id].clone(),
+                    depends_on: component
+                        .dependencies
+                        .iter()
+                        .map(|id| purls[id].clone())
+                        .collect(),
+                })
+                .collect(),
+        };
+        let mut s = serde_json::to_string_pretty(&document)?;
+        s.push('\n');
+        Ok(s)
+    }
+
+    /// Renders the SPDX JSON document.
+    fn spdx(&self, config: &DistConfig, target_triple: &str, timestamp: &str) -> Result<String> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Document<'a> {
+            spdx_version: &'static str,
+            data_license: &'static str,
+            #[serde(rename = "SPDXID")]
+            spdx_id: &'static str,
+            name: String,
+            document_namespace: String,
+            creation_info: CreationInfo<'a>,
+            packages: Vec<Package<'a>>,
+            relationships: Vec<Relationship>,
+        }
+        #[derive(Serialize)]
+        struct CreationInfo<'a> {
+            created: &'a str,
+            creators: Vec<String>,
+        }
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Package<'a> {
+            name: &'a str,
+            #[serde(rename = "SPDXID")]
+            spdx_id: String,
+            version_info: String,
+            download_location: String,
+            files_analyzed: bool,
+            license_concluded: &'static str,
+            license_declared: &'a str,
+            copyright_text: &'static str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            description: Option<&'a str>,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            checksums: Vec<Checksum<'a>>,
+            external_refs: Vec<ExternalRef>,
+        }
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Checksum<'a> {
+            algorithm: &'static str,
+            checksum_value: &'a str,
+        }
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ExternalRef {
+            reference_category: &'static str,
+            reference_type: &'static str,
+            reference_locator: String,
+        }
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Relationship {
+            spdx_element_id: String,
+            relationship_type: &'static str,
+            related_spdx_element: String,
+        }
+
+        let root = self.root().package;
+        let spdx_ids = self
+            .components
+            .iter()
+            .map(|component| (&component.package.id, spdx_id(component.package)))
+            .collect::<BTreeMap<_, _>>();
+
+        let packages = self
+            .components
+            .iter()
+            .map(|component| {
+                let package = component.package;
+                // only the valid SPDX expressions are allowed
+                let license_declared = component
+                    .license
+                    .as_deref()
+                    .filter(|license| license.parse::<Expression>().is_ok())
+                    .unwrap_or("NOASSERTION");
+                Package {
+                    name: package.name.as_str(),
+                    spdx_id: spdx_ids[&package.id].clone(),
+                    version_info: package.version.to_string(),
+                    download_location: download_location(package),
+                    files_analyzed: false,
+                    license_concluded: "NOASSERTION",
+                    license_declared,
+                    copyright_text: "NOASSERTION",
+                    description: package.description.as_deref().map(str::trim),
+                    checksums: component
+                        .checksum
+                        .map(|checksum| Checksum {
+                            algorithm: "SHA256",
+                            checksum_value: checksum,
+                        })
+                        .into_iter()
+                        .collect(),
+                    external_refs: vec![ExternalRef {
+                        reference_category: "PACKAGE-MANAGER",
+                        reference_type: "purl",
+                        reference_locator: purl(package),
+                    }],
+                }
+            })
+            .collect();
+
+        let mut relationships = vec![Relationship {
+            spdx_element_id: "SPDXRef-DOCUMENT".to_owned(),
+            relationship_type: "DESCRIBES",
+            related_spdx_element: spdx_ids[&root.id].clone(),
+        }];
+        for component in &self.components {
+            for id in &component.dependencies {
+                relationships.push(Relationship {
+                    spdx_element_id: spdx_ids[&component.package.id].clone(),
+                    relationship_type: "DEPENDS_ON",
+                    related_spdx_element: spdx_ids[id].clone(),
+                });
+            }
+        }
+
+        // the namespace must be unique for each document, and is derived from
+        // the contents so that the document is reproducible
+        let mut hasher = Sha256::new();
+        for component in &self.components {
+            hasher.update(component.package.id.repr.as_bytes());
+            hasher.update(component.checksum.unwrap_or_default().as_bytes());
+        }
+        let hash = short_hash(hasher);
+        let name = format!("{}-{}-{target_triple}", root.name, root.version);
+        let document_namespace =
+            format!("https://spdx.org/spdxdocs/{}/{name}-{hash}", config.name());
+
+        let document = Document {
+            spdx_version: "SPDX-2.3",
+            data_license: "CC0-1.0",
+            spdx_id: "SPDXRef-DOCUMENT",
+            name,
+            document_namespace,
+            creation_info: CreationInfo {
+                created: timestamp,
+                creators: vec![format!(
+                    "Tool: {}-{}",
+                    env!("CARGO_PKG_NAME"),
+                    env!("CARGO_PKG_VERSION")
+                )],
+            },
+            packages,
+            relationships,
+        };
+        let mut s = serde_json::to_string_pretty(&document)?;
+        s.push('\n');
+        Ok(s)
+    }
+}
+
+/// Returns the package URL of the package.
+///
+/// Packages not from crates.io are qualified by their sources, since packages
+/// with the same name and version may come from different sources.
+fn purl(package: &Package) -> String {
+    let purl = format!("pkg:cargo/{}@{}", package.name, package.version);
+    let qualifier = match &package.source {
+        Some(source) if source.is_crates_io() => None,
+        Some(source) => match git_source(&source.repr) {
+            Some((url, rev)) => Some(("vcs_url", format!("git+{url}@{rev}"))),
+            None => {
+                let url = source
+                    .repr
+                    .strip_prefix("registry+")
+                    .unwrap_or(&source.repr);
+                Some(("repository_url", url.to_owned()))
+            }
+        },
+        None => None,
+    };
+    match qualifier {
+        Some((key, value)) => format!("{purl}?{key}={}", percent_encode(&value)),
+        None => purl,
+    }
+}
+
+/// Percent-encodes the qualifier value of the package URL.
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_' | b'~' | b':' | b'/') {
+                char::from(b).to_string()
+            } else {
+                format!("%{b:02X}")
+            }
+        })
+        .collect()
+}
+
+/// Returns the SPDX identifier of the package.
+///
+/// The identifier ends with the hash of the package ID, since the name and
+/// version of the package don't identify the package uniquely once the
+/// disallowed characters are replaced.
+fn spdx_id(package: &Package) -> String {
+    // only letters, numbers, `.` and `-` are allowed
+    let name = format!("{}-{}", package.name, package.version)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    let hash = short_hash(Sha256::new_with_prefix(package.id.repr.as_bytes()));
+    format!("SPDXRef-Package-{name}-{hash}")
+}
+
+/// Returns the first 8 bytes of the digest as a hex string.
+fn short_hash(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .take(8)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Splits the git source of the package into the repository URL and the
+/// revision.
+fn git_source(repr: &str) -> Option<(&str, &str)> {
+    let (url, rev) = repr.strip_prefix("git+")?.split_once('#')?;
+    let url = url.split('?').next().unwrap_or(url);
+    Some((url, rev))
+}
+
+/// Returns the location to download the package from.
+fn download_location(package: &Package) -> String {
+    match &package.source {
+        Some(source) if source.is_crates_io() => format!(
+            "https://crates.io/api/v1/crates/{}/{}/download",
+            package.name, package.version
+        ),
+        Some(source) => match source.repr.strip_prefix("git+") {
+            Some(url) => format!("git+{}", url.split('#').next().unwrap_or(url)),
+            None => "NOASSERTION".to_owned(),
+        },
+        None => "NOASSERTION".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DistConfigBuilder;
+
+    fn package(name: &str, source: Option<&str>) -> Package {
+        let id = match source {
+            Some(source) => format!("{source}#{name}@1.0.0"),
+            None => format!("path+file:///{name}#1.0.0"),
+        };
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": id,
+            "source": source,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/{name}/Cargo.toml"),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn package_url() {
+        let cases = [
+            (
+                package(
+                    "foo",
+                    Some("registry+https://github.com/rust-lang/crates.io-index"),
+                ),
+                "pkg:cargo/foo@1.0.0",
+            ),
+            (package("foo", None), "pkg:cargo/foo@1.0.0"),
+            (
+                package(
+                    "foo",
+                    Some("git+https://github.com/owner/foo?branch=main#0123abcd"),
+                ),
+                "pkg:cargo/foo@1.0.0?vcs_url=git%2Bhttps://github.com/owner/foo%400123abcd",
+            ),
+            (
+                package("foo", Some("sparse+https://example.com/index/")),
+                "pkg:cargo/foo@1.0.0?repository_url=sparse%2Bhttps://example.com/index/",
+            ),
+            (
+                package("foo", Some("registry+https://example.com/index")),
+                "pkg:cargo/foo@1.0.0?repository_url=https://example.com/index",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(purl(&input), expected, "input: {}", input.id);
+        }
+    }
+
+    #[test]
+    fn spdx_id_is_unique() {
+        let packages = [
+            package("foo-bar", None),
+            package("foo_bar", None),
+            package(
+                "foo-bar",
+                Some("registry+https://github.com/rust-lang/crates.io-index"),
+            ),
+        ];
+        let ids = packages.iter().map(spdx_id).collect::<Vec<_>>();
+        for (package, id) in packages.iter().zip(&ids) {
+            assert!(id.starts_with("SPDXRef-Package-foo-bar-1.0.0-"), "id: {id}");
+            assert!(
+                id.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-')),
+                "id: {id}"
+            );
+            assert_eq!(
+                ids.iter().filter(|other| *other == id).count(),
+                1,
+                "package: {}",
+                package.id
+            );
+        }
+    }
+
+    #[test]
+    fn source_location() {
+        let cases = [
+            (
+                package(
+                    "foo",
+                    Some("registry+https://github.com/rust-lang/crates.io-index"),
+                ),
+                "https://crates.io/api/v1/crates/foo/1.0.0/download",
+            ),
+            (
+                package(
+                    "foo",
+                    Some("git+https://github.com/owner/foo?branch=main#0123abcd"),
+                ),
+                "git+https://github.com/owner/foo?branch=main",
+            ),
+            (
+                package("foo", Some("registry+https://example.com/index")),
+                "NOASSERTION",
+            ),
+            (package("foo", None), "NOASSERTION"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(download_location(&input), expected, "input: {}", input.id);
+        }
+
+        let cases = [
+            (
+                "git+https://github.com/owner/foo?branch=main#0123abcd",
+                Some(("https://github.com/owner/foo", "0123abcd")),
+            ),
+            ("git+https://github.com/owner/foo", None),
+            ("registry+https://example.com/index", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(git_source(input), expected, "input: {input}");
+        }
+    }
+
+    #[test]
+    fn lockfile_checksum() {
+        let crates_io = "registry+https://github.com/rust-lang/crates.io-index";
+        let lockfile = Lockfile {
+            package: vec![
+                LockedPackage {
+                    name: "foo".to_owned(),
+                    version: "1.0.0".to_owned(),
+                    source: Some(crates_io.to_owned()),
+                    checksum: Some("0123".to_owned()),
+                },
+                LockedPackage {
+                    name: "bar".to_owned(),
+                    version: "1.0.0".to_owned(),
+                    source: None,
+                    checksum: None,
+                },
+            ],
+        };
+        let cases = [
+            (package("foo", Some(crates_io)), Some("0123")),
+            (
+                package("foo", Some("registry+https://example.com/index")),
+                None,
+            ),
+            (package("bar", None), None),
+            (package("baz", Some(crates_io)), None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(lockfile.checksum(&input), expected, "input: {}", input.id);
+        }
+    }
+
+    #[test]
+    fn documents() {
+        let metadata = crate::workspace::current();
+        let root = metadata.root_package().unwrap();
+        let lockfile = Lockfile::default();
+        let bom = Bom::new(metadata, &root.id, &lockfile).unwrap();
+
+        assert_eq!(bom.root().package.id, root.id);
+        let names = bom
+            .components
+            .iter()
+            .map(|component| component.package.name.as_str())
+            .collect::<Vec<_>>();
+        assert!(names.contains(&"eyre"), "names: {names:?}");
+        assert!(!names.contains(&"clap_derive"), "names: {names:?}");
+        assert!(names[1..].is_sorted(), "names: {names:?}");
+
+        let timestamp = "2024-01-02T03:04:05Z";
+        let cyclonedx: serde_json::Value =
+            serde_json::from_str(&bom.cyclonedx(timestamp).unwrap()).unwrap();
+        assert_eq!(cyclonedx["bomFormat"], "CycloneDX");
+        assert_eq!(cyclonedx["metadata"]["timestamp"], timestamp);
+        assert_eq!(
+            cyclonedx["dependencies"].as_array().unwrap().len(),
+            bom.components.len()
+        );
+
+        let (dist_config, package_config) = DistConfigBuilder::from_root_package(metadata).unwrap();
+        let config = dist_config
+            .package(package_config.build().unwrap())
+            .build()
+            .unwrap();
+        let spdx: serde_json::Value = serde_json::from_str(
+            &bom.spdx(&config, "x86_64-unknown-linux-gnu", timestamp)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(spdx["spdxVersion"], "SPDX-2.3");
+        assert_eq!(spdx["creationInfo"]["created"], timestamp);
+        assert_eq!(
+            spdx["packages"].as_array().unwrap().len(),
+            bom.components.len()
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use cargo_metadata::camino::Utf8PathBuf;
+use eyre::bail;
+
+use crate::{
+    Result, Run,
+    config::Config,
+    fhs::{self, FhsOptions},
+    install_manifest::{self, InstallManifest},
+};
+
+/// Arguments definition of the `dist-install` subcommand.
+#[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-install.md"))]
+#[derive(Debug, Clone, Default, clap::Args)]
+#[non_exhaustive]
+pub struct DistInstall {
+    /// Installation prefix (e.g. `/usr/local`)
+    #[clap(long, value_name = "DIR")]
+    pub prefix: Utf8PathBuf,
+    /// Directory to stage the installation into, prepended to the prefix
+    #[clap(long, value_name = "DIR")]
+    pub destdir: Option<Utf8PathBuf>,
+    /// Target triple of the artifacts to install [default: the only built target]
+    #[clap(long = "target")]
+    pub target_triple: Option<String>,
+}
+
+impl Run for DistInstall {
+    fn run(&self, config: &Config) -> Result<()> {
+        self.run(config)
+    }
+}
+
+impl DistInstall {
+    /// Runs the `dist-install` subcommand.
+    #[tracing::instrument(name = "dist-install", skip_all, err)]
+    pub fn run(&self, config: &Config) -> Result<()> {
+        let Self {
+            prefix,
+            destdir,
+            target_triple,
+        } = self;
+        let config = config.dist()?;
+
+        let target_triple = match target_triple {
+            Some(target_triple) => target_triple.clone(),
+            None => {
+                let mut target_triples = config.built_target_triples()?;
+                match target_triples.len() {
+                    0 => bail!("no built targets found (run `cargo xtask dist-build-bin` first)"),
+                    1 => target_triples.remove(0),
+                    _ => bail!(
+                        "multiple targets are built, specify one of them with `--target`: {}",
+                        target_triples.join(", ")
+                    ),
+                }
+            }
+        };
+        let target_triple = target_triple.as_str();
+        let root = install_manifest::install_root(prefix, destdir.as_deref());
+        tracing::info!("Installing artifacts into {root}...");
+
+        for package in config.packages() {
+            let package_name = package.name().as_ref();
+            let entries =
+                fhs::package_entries(config, package, target_triple, FhsOptions::default())?;
+            let manifest = InstallManifest {
+                files: entries
+                    .iter()
+                    .map(|entry| entry.dest.clone())
+                    .chain([InstallManifest::path(package_name)])
+                    .collect(),
+            };
+
+            // remove files installed by the previous installation but not by this one
+            if let Some(old) = InstallManifest::read(&root, package_name)? {
+                old.remove_files(&root, |file| !manifest.files.iter().any(|f| f == file))?;
+            }
+
+            for entry in &entries {
+                let dest = root.join(&entry.dest);
+                crate::fs::copy(&entry.src, &dest)?;
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt as _;
+                    std::fs::set_permissions(&dest, std::fs::Permissions::from_mode(entry.mode))?;
+                }
+            }
+            manifest.write(&root, package_name)?;
+            tracing::info!("Package `{package_name}` installed successfully");
+        }
+
+        Ok(())
+    }
+}
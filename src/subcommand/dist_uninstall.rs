@@ -0,0 +1,50 @@
+use cargo_metadata::camino::Utf8PathBuf;
+
+use crate::{
+    Result, Run,
+    config::Config,
+    install_manifest::{self, InstallManifest},
+};
+
+/// Arguments definition of the `dist-uninstall` subcommand.
+#[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-dist-uninstall.md"))]
+#[derive(Debug, Clone, Default, clap::Args)]
+#[non_exhaustive]
+pub struct DistUninstall {
+    /// Installation prefix given to `dist-install`
+    #[clap(long, value_name = "DIR")]
+    pub prefix: Utf8PathBuf,
+    /// Staging directory given to `dist-install`
+    #[clap(long, value_name = "DIR")]
+    pub destdir: Option<Utf8PathBuf>,
+}
+
+impl Run for DistUninstall {
+    fn run(&self, config: &Config) -> Result<()> {
+        self.run(config)
+    }
+}
+
+impl DistUninstall {
+    /// Runs the `dist-uninstall` subcommand.
+    #[tracing::instrument(name = "dist-uninstall", skip_all, err)]
+    pub fn run(&self, config: &Config) -> Result<()> {
+        let Self { prefix, destdir } = self;
+        let config = config.dist()?;
+
+        let root = install_manifest::install_root(prefix, destdir.as_deref());
+        tracing::info!("Uninstalling artifacts from {root}...");
+
+        for package in config.packages() {
+            let Some(manifest) = InstallManifest::read(&root, package.name().as_ref())? else {
+                tracing::warn!("package `{}` is not installed", package.name());
+                continue;
+            };
+            // the manifest lists itself, so it is removed together
+            manifest.remove_files(&root, |_| true)?;
+            tracing::info!("Package `{}` uninstalled successfully", package.name());
+        }
+
+        Ok(())
+    }
+}
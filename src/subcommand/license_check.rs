@@ -0,0 +1,158 @@
+use cargo_metadata::{Metadata, Package};
+use eyre::{bail, eyre};
+
+use crate::{
+    Result, Run, args::WorkspaceArgs, config::Config, dependency::DependencyPaths, spdx::Expression,
+};
+
+/// Arguments definition of the `license-check` subcommand.
+///
+/// The license policy is configured in the `[workspace.metadata]` table of
+/// the workspace manifest. The workspaces without the policy are skipped.
+///
+/// ```toml
+/// [workspace.metadata.cli-xtask.license-check]
+/// # licenses the dependencies are allowed to be licensed under
+/// # (all licenses not denied are allowed if omitted)
+/// allow = ["MIT", "Apache-2.0", "Unicode-3.0"]
+/// # licenses the dependencies must not be licensed under
+/// deny = ["GPL-3.0-only"]
+/// # names of the packages not checked
+/// skip = ["my-private-crate"]
+/// ```
+///
+/// A license with an exception (`<license> WITH <exception>`) matches both
+/// `"<license>"` and `"<license> WITH <exception>"` in the lists.
+#[cfg_attr(doc, doc = include_str!("../../doc/cargo-xtask-license-check.md"))]
+#[derive(Debug, Clone, Default, clap::Args)]
+#[non_exhaustive]
+pub struct LicenseCheck {
+    /// Workspaces to check the licenses of the dependencies of
+    #[clap(flatten)]
+    pub workspace_args: WorkspaceArgs,
+}
+
+impl Run for LicenseCheck {
+    fn run(&self, config: &Config) -> Result<()> {
+        self.run(config)
+    }
+}
+
+impl LicenseCheck {
+    /// Runs the `license-check` subcommand.
+    #[tracing::instrument(name = "license-check", skip_all, err)]
+    pub fn run(&self, _config: &Config) -> Result<()> {
+        let Self { workspace_args } = self;
+
+        let mut violations = 0;
+        for workspace in workspace_args.workspaces() {
+            let Some(policy) = LicensePolicy::from_metadata(workspace)? else {
+                tracing::info!(
+                    "No license policy configured for workspace {}",
+                    workspace.workspace_root
+                );
+                continue;
+            };
+            tracing::info!("Checking licenses of the dependencies...");
+            violations += check_workspace(workspace, &policy)?;
+        }
+        if violations > 0 {
+            bail!("{violations} packages violate the license policy");
+        }
+
+        Ok(())
+    }
+}
+
+/// License policy of a workspace.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct LicensePolicy {
+    #[serde(default)]
+    allow: Vec<String>,
+    #[serde(default)]
+    deny: Vec<String>,
+    #[serde(default)]
+    skip: Vec<String>,
+}
+
+impl LicensePolicy {
+    fn from_metadata(workspace: &Metadata) -> Result<Option<Self>> {
+        let table = || {
+            workspace
+                .workspace_metadata
+                .get("cli-xtask")?
+                .get("license-check")
+        };
+        let Some(table) = table() else {
+            return Ok(None);
+        };
+        let policy = serde_json::from_value(table.clone())
+            .map_err(|e| eyre!("invalid `workspace.metadata.cli-xtask.license-check`: {e}"))?;
+        Ok(Some(policy))
+    }
+
+    fn accepts(&self, id: &str, exception: Option<&str>) -> bool {
+        let with_exception = exception.map(|exception| format!("{id} WITH {exception}"));
+        let matches = |list: &[String]| {
+            list.iter().any(|entry| {
+                // SPDX identifiers are case-insensitive
+                entry.eq_ignore_ascii_case(id)
+                    || with_exception
+                        .as_ref()
+                        .is_some_and(|with_exception| entry.eq_ignore_ascii_case(with_exception))
+            })
+        };
+        !matches(&self.deny) && (self.allow.is_empty() || matches(&self.allow))
+    }
+}
+
+/// Checks the licenses of the dependencies of the workspace, returning the
+/// number of violations.
+fn check_workspace(workspace: &Metadata, policy: &LicensePolicy) -> Result<usize> {
+    let paths = DependencyPaths::new(workspace)?;
+
+    let mut packages = workspace
+        .packages
+        .iter()
+        .filter(|package| !workspace.workspace_members.contains(&package.id))
+        .filter(|package| !policy.skip.contains(&package.name))
+        .collect::<Vec<_>>();
+    packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+    let mut violations = 0;
+    for package in packages {
+        let Some(reason) = violation(package, policy) else {
+            continue;
+        };
+        let path = paths
+            .path(&package.id)
+            .iter()
+            .map(|package| format!("{} v{}", package.name, package.version))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        tracing::error!(
+            "{} v{}: {reason}\n  dependency path: {path}",
+            package.name,
+            package.version
+        );
+        violations += 1;
+    }
+    Ok(violations)
+}
+
+/// Returns the reason why the package violates the policy, if it does.
+fn violation(package: &Package, policy: &LicensePolicy) -> Option<String> {
+    let Some(license) = &package.license else {
+        return Some("no license expression".to_owned());
+    };
+    let expr = match license.parse::<Expression>() {
+        Ok(expr) => expr,
+        Err(e) => return Some(format!("invalid license expression: {e}")),
+    };
+    if expr.evaluate(&mut |id, exception| policy.accepts(id, exception)) {
+        None
+    } else {
+        Some(format!("license `{license}` is not allowed"))
+    }
+}
@@ -66,6 +66,18 @@ impl Lint {
                 package_args: feature_args.package_args.clone(),
                 extra_options: ["--check"].into_iter().map(String::from).collect(),
             }),
+            // cargo xtask dist-build-cli-doc --check
+            #[cfg(feature = "subcommand-dist-build-cli-doc")]
+            Box::new(super::DistBuildCliDoc {
+                output: None,
+                check: true,
+                fingerprint_args: Default::default(),
+            }),
+            // cargo xtask license-check
+            #[cfg(feature = "subcommand-license-check")]
+            Box::new(super::LicenseCheck {
+                workspace_args: feature_args.package_args.workspace_args.clone(),
+            }),
             // cargo udeps
             #[cfg(feature = "subcommand-udeps")]
             Box::new(super::Udeps {
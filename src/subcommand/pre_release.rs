@@ -25,6 +25,11 @@ impl PreRelease {
             Box::new(super::Lint {
                 feature_args: crate::args::FeatureArgs::EXHAUSTIVE,
             }),
+            // `lint` runs `license-check` if enabled
+            #[cfg(all(feature = "subcommand-license-check", not(feature = "subcommand-lint")))]
+            Box::new(super::LicenseCheck {
+                workspace_args: crate::args::WorkspaceArgs::EXHAUSTIVE,
+            }),
             #[cfg(feature = "subcommand-test")]
             Box::new(super::Test {
                 env_args: Default::default(),
@@ -0,0 +1,54 @@
+//! Timestamps recorded in the distribution artifacts.
+
+use eyre::{bail, eyre};
+
+use crate::Result;
+
+/// Returns the timestamp given by the `SOURCE_DATE_EPOCH` environment
+/// variable, or `None` if it is not set.
+///
+/// `SOURCE_DATE_EPOCH` is the number of seconds since the Unix epoch, used to
+/// build the artifacts reproducibly. Surrounding whitespace is ignored.
+///
+/// See <https://reproducible-builds.org/specs/source-date-epoch/>.
+pub(crate) fn source_date_epoch() -> Result<Option<u64>> {
+    match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(value) => {
+            let timestamp = value
+                .trim()
+                .parse()
+                .map_err(|e| eyre!("invalid SOURCE_DATE_EPOCH `{value}`: {e}"))?;
+            Ok(Some(timestamp))
+        }
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(e) => bail!("invalid SOURCE_DATE_EPOCH: {e}"),
+    }
+}
+
+/// Converts the timestamp into a date time in UTC.
+#[cfg(any(
+    feature = "subcommand-dist-build-man",
+    feature = "subcommand-dist-build-sbom"
+))]
+pub(crate) fn to_date_time(timestamp: u64) -> Result<chrono::DateTime<chrono::Utc>> {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp, 0))
+        .ok_or_else(|| eyre!("SOURCE_DATE_EPOCH out of range: {timestamp}"))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(any(
+        feature = "subcommand-dist-build-man",
+        feature = "subcommand-dist-build-sbom"
+    ))]
+    fn to_date_time() {
+        assert_eq!(
+            super::to_date_time(1_704_164_645).unwrap().to_rfc3339(),
+            "2024-01-02T03:04:05+00:00"
+        );
+        assert!(super::to_date_time(u64::MAX).is_err());
+    }
+}
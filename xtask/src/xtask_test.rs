@@ -73,16 +73,32 @@ fn test_workspace(workspace: &Metadata, cargo_llvm_cov: bool) -> Result<()> {
             "dist-archive" => test_dist_archive(&cargo)?,
             "dist-build" => cargo.spawn(["xtask", "dist-build"])?,
             "dist-build-bin" => cargo.spawn(["xtask", "dist-build-bin"])?,
+            "dist-build-cli-doc" => cargo.spawn(["xtask", "dist-build-cli-doc"])?,
             "dist-build-completion" => cargo.spawn(["xtask", "dist-build-completion"])?,
+            "dist-build-deb" => cargo.spawn(["xtask", "dist-build-deb"])?,
             "dist-build-doc" => cargo.spawn(["xtask", "dist-build-doc"])?,
             "dist-build-license" => cargo.spawn(["xtask", "dist-build-license"])?,
             "dist-build-man" => cargo.spawn(["xtask", "dist-build-man"])?,
             "dist-build-readme" => cargo.spawn(["xtask", "dist-build-readme"])?,
+            "dist-build-recipes" => cargo.spawn(["xtask", "dist-build-recipes"])?,
+            "dist-build-rpm" => cargo.spawn(["xtask", "dist-build-rpm"])?,
+            "dist-build-sbom" => cargo.spawn(["xtask", "dist-build-sbom"])?,
             "dist-clean" => cargo.spawn(["xtask", "dist-clean"])?,
+            "dist-install" => test_dist_install(&cargo, subcommands.contains(&"dist-uninstall"))?,
+            "dist-uninstall" => cargo.spawn([
+                "xtask",
+                "dist-uninstall",
+                "--prefix",
+                cargo
+                    .target_directory()
+                    .join("xtask-test/uninstall")
+                    .as_str(),
+            ])?,
             "doc" => cargo.spawn(["xtask", "doc"])?,
             "docsrs" => cargo.spawn(["xtask", "docsrs"])?,
             "fmt" => cargo.spawn(["xtask", "fmt"])?,
             "help" => {}
+            "license-check" => cargo.spawn(["xtask", "license-check"])?,
             "lint" => cargo.spawn(["xtask", "lint"])?,
             "pre-release" => cargo.spawn(["xtask", "pre-release"])?,
             "sync-rdme" => cargo.spawn(["xtask", "sync-rdme"])?,
@@ -99,6 +115,30 @@ fn test_workspace(workspace: &Metadata, cargo_llvm_cov: bool) -> Result<()> {
     Ok(())
 }
 
+fn test_dist_install(cargo: &Cargo, test_uninstall: bool) -> Result<()> {
+    cargo.cleanup()?;
+    cargo.spawn(["xtask", "dist-build"])?;
+
+    let destdir = cargo.target_directory().join("xtask-test/install");
+    if destdir.is_dir() {
+        fs::remove_dir_all(&destdir)?;
+    }
+    let args = ["--prefix", "/usr/local", "--destdir", destdir.as_str()];
+    cargo.spawn(["xtask", "dist-install"].into_iter().chain(args))?;
+
+    let root = destdir.join("usr/local");
+    assert!(root.join("bin/app").is_file() || root.join("bin/app.exe").is_file());
+    assert!(root.join("share/cli-xtask/app.manifest").is_file());
+
+    if test_uninstall {
+        cargo.spawn(["xtask", "dist-uninstall"].into_iter().chain(args))?;
+        assert!(!root.join("bin").exists());
+        assert!(!root.join("share").exists());
+    }
+
+    Ok(())
+}
+
 fn test_dist_archive(cargo: &Cargo) -> Result<()> {
     // if working directory is empty, no artifacts and dist directory created
     cargo.cleanup()?;
@@ -127,6 +167,8 @@ fn test_dist_archive(cargo: &Cargo) -> Result<()> {
     assert!(distdir.join("app-v0.1.0-arch2.tar.gz").exists());
     assert!(distdir.join("app-v0.1.0-arch1.tar.gz").exists());
     assert!(!distdir.join("app-v0.1.0-noarch.tar.gz").exists());
+    assert!(distdir.join("app-v0.1.0-arch1.tar.gz.sha256").exists());
+    assert!(distdir.join("dist-manifest.json").exists());
 
     // noarch archive created if noarch directory exists and other directory not
     // exists